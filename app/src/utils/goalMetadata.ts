@@ -0,0 +1,79 @@
+import { Program } from '@coral-xyz/anchor';
+import { PublicKey } from '@solana/web3.js';
+
+// Shape of the off-chain JSON referenced by a GoalMetadata account.
+export type GoalMetadataDocument = {
+  title?: string;
+  description?: string;
+  image?: string;
+  // locale code -> { title, description }
+  localized?: Record<string, { title?: string; description?: string }>;
+};
+
+export type VerifiedGoalMetadata = {
+  verified: boolean;
+  document: GoalMetadataDocument | null;
+  error?: string;
+};
+
+export const findGoalMetadataPda = (goal: PublicKey, programId: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from('goal-metadata'), goal.toBuffer()],
+    programId,
+  )[0];
+
+export const sha256 = async (bytes: Uint8Array) =>
+  new Uint8Array(await crypto.subtle.digest('SHA-256', bytes));
+
+const bytesEqual = (a: Uint8Array, b: Uint8Array) =>
+  a.length === b.length && a.every((value, i) => value === b[i]);
+
+// Fetches the JSON at `uri` and checks its sha256 against the on-chain
+// `contentHash` before parsing, so a swapped or edited document is never shown.
+export async function fetchVerifiedGoalMetadata(
+  uri: string,
+  contentHash: number[] | Uint8Array,
+): Promise<VerifiedGoalMetadata> {
+  let body: Uint8Array;
+  try {
+    const response = await fetch(uri);
+    if (!response.ok) {
+      return { verified: false, document: null, error: `HTTP ${response.status}` };
+    }
+    body = new Uint8Array(await response.arrayBuffer());
+  } catch (e) {
+    return { verified: false, document: null, error: String(e) };
+  }
+
+  const digest = await sha256(body);
+  if (!bytesEqual(digest, Uint8Array.from(contentHash))) {
+    return { verified: false, document: null, error: 'content hash mismatch' };
+  }
+
+  try {
+    const document = JSON.parse(new TextDecoder().decode(body)) as GoalMetadataDocument;
+    return { verified: true, document };
+  } catch (e) {
+    return { verified: false, document: null, error: 'metadata is not valid JSON' };
+  }
+}
+
+// Loads the goal's GoalMetadata account, if any, and the verified document it
+// points to. Returns null for goals that only carry the inline summary.
+export async function fetchGoalMetadata(
+  program: Program,
+  goal: PublicKey,
+): Promise<VerifiedGoalMetadata | null> {
+  const account = await (program.account as any).goalMetadata.fetchNullable(
+    findGoalMetadataPda(goal, program.programId),
+  );
+  if (!account) return null;
+  return fetchVerifiedGoalMetadata(account.uri, account.contentHash);
+}
+
+// Title and description to show for a verified document, preferring the
+// requested locale.
+export const localizedMetadata = (document: GoalMetadataDocument, locale: string) => ({
+  title: document.localized?.[locale]?.title ?? document.title,
+  description: document.localized?.[locale]?.description ?? document.description,
+});
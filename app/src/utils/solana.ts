@@ -34,3 +34,8 @@ export const decodeFixedString = (bytes: number[] = []) => {
   return textDecoder.decode(view.slice(0, end));
 };
 
+
+// Decodes the first `length` bytes of a fixed-size buffer, as used by the
+// zero-copy goal's description and description_len.
+export const decodeSizedString = (bytes: number[] = [], length = 0) =>
+  textDecoder.decode(new Uint8Array(bytes).slice(0, length));
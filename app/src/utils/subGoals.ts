@@ -0,0 +1,39 @@
+import { Program } from '@coral-xyz/anchor';
+import { PublicKey } from '@solana/web3.js';
+import idl from '../idl/gluex.json';
+
+// setup_goal creates one subgoal per habit checkpoint and a single subgoal
+// for surprise goals; staged goals get one per stage.
+export const HABIT_CHECKPOINTS = 3;
+
+export const findSubGoalPda = (goal: PublicKey, index: number, programId: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from('gluex-subgoal'), goal.toBuffer(), Buffer.from([index])],
+    programId,
+  )[0];
+
+// Subgoal PDAs passed as remaining accounts to setup_goal and add_subgoals.
+export const subGoalMetas = (goal: PublicKey, count: number, programId: PublicKey, from = 0) =>
+  Array.from({ length: count }, (_, offset) => ({
+    pubkey: findSubGoalPda(goal, from + offset, programId),
+    isWritable: true,
+    isSigner: false,
+  }));
+
+// Loads the SubGoalAccount PDAs of a goal in index order, skipping any that
+// do not exist.
+export async function fetchSubGoals(program: Program, goal: PublicKey, count: number) {
+  const keys = Array.from({ length: count }, (_, index) => findSubGoalPda(goal, index, program.programId));
+  const accounts = await (program.account as any).subGoalAccount.fetchMultiple(keys);
+  return accounts
+    .map((account: any, index: number) => (account ? { publicKey: keys[index], account } : null))
+    .filter(Boolean);
+}
+
+// Goal enums are stored as u8 in the zero-copy goal; map them back to the
+// camelCase variant name the Anchor coder uses for Borsh enums.
+export const goalEnumKey = (typeName: string, value: number) => {
+  const type = (idl as any).types.find((item: any) => item.name === typeName);
+  const name: string | undefined = type?.type?.variants?.[value]?.name;
+  return name ? name.charAt(0).toLowerCase() + name.slice(1) : 'Unknown';
+};
@@ -4,7 +4,9 @@ import { FC, useCallback, useEffect, useMemo, useState } from 'react';
 import { useWallet } from '@solana/wallet-adapter-react';
 import { useGlueXProgram } from '../../hooks/useGlueXProgram';
 import useNotificationStore from '../../stores/useNotificationStore';
-import { decodeFixedString, lamportsToSol, solToLamports, toUnixSeconds } from '../../utils/solana';
+import { decodeFixedString, decodeSizedString, lamportsToSol, solToLamports, toUnixSeconds } from '../../utils/solana';
+import { fetchGoalMetadata, localizedMetadata } from '../../utils/goalMetadata';
+import { HABIT_CHECKPOINTS, fetchSubGoals, findSubGoalPda, goalEnumKey, subGoalMetas } from '../../utils/subGoals';
 import { useLanguage } from '../../contexts/LanguageProvider';
 import { useNetworkConfiguration } from '../../contexts/NetworkConfigurationProvider';
 
@@ -37,6 +39,9 @@ const statusColorMap: Record<string, string> = {
   paid: 'badge-success',
 };
 
+// matches MAX_DESCRIPTION_BYTES in the program; localized text goes in GoalMetadata
+const MAX_DESCRIPTION_BYTES = 512;
+
 const defaultStage: StageInput = { title: '', deadline: '', amount: 0 };
 
 const copy = {
@@ -68,6 +73,7 @@ const copy = {
     listTitle: 'Active spaces',
     refresh: 'Refresh',
     emptyBanner: 'Connect your wallet or create a mission to see it here.',
+    metadataUnverified: 'Off-chain details could not be verified; showing the on-chain summary.',
     info: {
       room: 'Room',
       relation: 'Relation',
@@ -136,6 +142,7 @@ const copy = {
     listTitle: '当前激励空间',
     refresh: '刷新',
     emptyBanner: '连接钱包或创建任务后即可查看列表。',
+    metadataUnverified: '链下详情校验失败，仅显示链上摘要。',
     info: {
       room: '空间',
       relation: '关系',
@@ -261,10 +268,18 @@ const GoalsView: FC = () => {
     try {
       const { fetchAllTotalGoals } = await import('../../utils/totalGoalFetcher');
       const items = await fetchAllTotalGoals(program as any);
+      const mine = items.filter((item: any) =>
+        (item.account.issuer as unknown as PublicKey).equals(wallet.publicKey) ||
+        (item.account.taker as unknown as PublicKey).equals(wallet.publicKey),
+      );
+      // subgoals live in their own PDAs, so load them alongside each goal
       setGoals(
-        items.filter((item: any) =>
-          (item.account.issuer as unknown as PublicKey).equals(wallet.publicKey) ||
-          (item.account.taker as unknown as PublicKey).equals(wallet.publicKey),
+        await Promise.all(
+          mine.map(async (item: any) => ({
+            ...item,
+            subGoals: await fetchSubGoals(program as any, item.publicKey, item.account.activeSubGoals),
+            metadata: await fetchGoalMetadata(program as any, item.publicKey),
+          })),
         ),
       );
     } catch (error) {
@@ -326,13 +341,26 @@ const GoalsView: FC = () => {
         deadline: new BN(toUnixSeconds(stage.deadline)),
         incentiveAmount: new BN(solToLamports(stage.amount)),
         autoReleaseAt: new BN(0),
+        prerequisites: [],
       }));
   };
 
+  // number of SubGoalAccount PDAs setup_goal creates for the chosen event type
+  const subGoalCount = () => {
+    if (isHabit) return HABIT_CHECKPOINTS;
+    if (isTarget) return buildSubGoals().length;
+    return 1;
+  };
+
   const goalConfig = () => ({
     startTime: new BN(toUnixSeconds(startDate)),
     surpriseTime: new BN(isSurprise ? toUnixSeconds(surpriseDate) : 0),
     checkpointInterval: new BN(isHabit ? habitIntervalDays * 24 * 3600 : 0),
+    surpriseWindow: null,
+    sealedMessage: null,
+    sequential: false,
+    latePenalty: null,
+    deadManSwitch: null,
   });
 
   const handleCreateGoal = async () => {
@@ -348,6 +376,10 @@ const GoalsView: FC = () => {
       notify('error', t.notifications.invalidTaker, (error as Error).message);
       return;
     }
+    if (new TextEncoder().encode(description).length > MAX_DESCRIPTION_BYTES) {
+      notify('error', `Description must fit in ${MAX_DESCRIPTION_BYTES} bytes; add details as goal metadata`);
+      return;
+    }
 
     setLoading(true);
     try {
@@ -397,6 +429,10 @@ const GoalsView: FC = () => {
       const accounts: any = {
         goals: goalsPda,
         payer: wallet.publicKey,
+        matchingPool: null,
+        matchingAllowance: null,
+        issuerProfile: null,
+        template: null,
         systemProgram: SystemProgram.programId,
       };
       accounts.feePool = feePoolAcct ? feePoolPda : null;
@@ -417,6 +453,7 @@ const GoalsView: FC = () => {
           goalConfig(),
         )
         .accounts(accounts)
+        .remainingAccounts(subGoalMetas(goalsPda, subGoalCount(), program.programId))
         .rpc();
 
       // record tx signature for the newly created goal PDA
@@ -446,6 +483,7 @@ const GoalsView: FC = () => {
         .submitProof(index, uri)
         .accounts({
           goals: goal.publicKey,
+          subGoal: findSubGoalPda(goal.publicKey, index, program.programId),
           taker: wallet.publicKey,
         })
         .rpc();
@@ -465,8 +503,10 @@ const GoalsView: FC = () => {
         .reviewSubgoal(index, approve)
         .accounts({
           goals: goal.publicKey,
+          subGoal: findSubGoalPda(goal.publicKey, index, program.programId),
           issuer: wallet.publicKey,
           takerAccount: goal.account.taker,
+          beneficiaries: null,
         })
         .rpc();
       setLastTxs((prev) => ({ ...prev, [goal.publicKey.toBase58()]: sig }));
@@ -482,10 +522,14 @@ const GoalsView: FC = () => {
     if (!program) return;
     try {
       const sig = await program.methods
-        .triggerSurprise()
+        // goals created here carry no sealed message key
+        .triggerSurprise(null)
         .accounts({
           goals: goal.publicKey,
+          // surprise goals have a single subgoal
+          subGoal: findSubGoalPda(goal.publicKey, 0, program.programId),
           takerAccount: goal.account.taker,
+          beneficiaries: null,
         })
         .rpc();
       setLastTxs((prev) => ({ ...prev, [goal.publicKey.toBase58()]: sig }));
@@ -573,8 +617,8 @@ const GoalsView: FC = () => {
                 >
                   {proofUri}
                 </a>
-                {subGoal.submitted_at ? (
-                  <div className="text-xs text-slate-500 mt-1">Submitted: {unixToLocale(subGoal.submitted_at)}</div>
+                {subGoal.submittedAt?.toNumber() ? (
+                  <div className="text-xs text-slate-500 mt-1">Submitted: {unixToLocale(subGoal.submittedAt)}</div>
                 ) : null}
               </div>
             );
@@ -680,6 +724,7 @@ const GoalsView: FC = () => {
                   onChange={(event) => setDescription(event.target.value)}
                   onFocus={() => setFocusedField('description')}
                   onBlur={() => setFocusedField(null)}
+                  maxLength={MAX_DESCRIPTION_BYTES}
                   rows={4}
                 />
                 {description && (
@@ -689,7 +734,7 @@ const GoalsView: FC = () => {
                 )}
               </div>
               <div className="label">
-                <span className="label-text-alt text-slate-500">{description.length} characters</span>
+                <span className="label-text-alt text-slate-500">{new TextEncoder().encode(description).length} / {MAX_DESCRIPTION_BYTES} bytes</span>
               </div>
             </label>
             <div className="grid grid-cols-2 gap-4">
@@ -947,6 +992,11 @@ const GoalsView: FC = () => {
           {goals.map((goal, goalIndex) => {
             const goalKey = goal.publicKey.toBase58();
             const isExpanded = expandedGoals.has(goalKey);
+            const summary = decodeSizedString(goal.account.description, goal.account.descriptionLen);
+            // rich details only come from a document whose hash matches the chain
+            const details = goal.metadata?.verified && goal.metadata.document
+              ? localizedMetadata(goal.metadata.document, language)
+              : null;
             return (
             <div
               key={goalKey}
@@ -957,11 +1007,17 @@ const GoalsView: FC = () => {
                 <div className="flex justify-between items-start">
                   <div className="flex-1">
                     <h3 className="card-title text-lg bg-gradient-to-r from-indigo-400 to-fuchsia-400 bg-clip-text text-transparent">
-                      {goal.account.description}
+                      {details?.title || summary}
                     </h3>
+                    {details?.description && (
+                      <p className="text-sm text-slate-300 mt-1 whitespace-pre-line">{details.description}</p>
+                    )}
+                    {goal.metadata && !goal.metadata.verified && (
+                      <p className="text-xs text-yellow-400 mt-1">{t.metadataUnverified}</p>
+                    )}
                     <div className="flex items-center gap-2 mt-1">
                       <span className="badge badge-outline badge-sm">
-                        {readEnumKey(goal.account.eventype)}
+                        {goalEnumKey('EventType', goal.account.eventype)}
                       </span>
                       <span className="text-sm font-semibold text-indigo-400">
                       {lamportsToSol(goal.account.totalIncentiveAmount)} SOL
@@ -989,11 +1045,11 @@ const GoalsView: FC = () => {
                   <div className={`text-xs text-slate-500 space-y-1 transition-all duration-300 ${isExpanded ? 'max-h-96' : 'max-h-0 overflow-hidden'}`}>
                     <p className="flex items-center gap-2">
                       <span className="w-16 text-slate-400">{t.info.room}:</span>
-                      <span className="badge badge-sm">{readEnumKey(goal.account.room)}</span>
+                      <span className="badge badge-sm">{goalEnumKey('Roomspace', goal.account.room)}</span>
                     </p>
                     <p className="flex items-center gap-2">
                       <span className="w-16 text-slate-400">{t.info.relation}:</span>
-                      <span className="badge badge-sm">{readEnumKey(goal.account.relations)}</span>
+                      <span className="badge badge-sm">{goalEnumKey('Relations', goal.account.relations)}</span>
                     </p>
                     <p className="flex items-center gap-2">
                       <span className="w-16 text-slate-400">{t.info.completion}:</span>
@@ -1017,8 +1073,8 @@ const GoalsView: FC = () => {
                   </div>
                 </button>
                 <div className="grid gap-3">
-                  {goal.account.subGoals.map((subGoal: any, index: number) =>
-                    renderSubGoal(goal, subGoal, index),
+                  {goal.subGoals.map((subGoal: any) =>
+                    renderSubGoal(goal, subGoal.account, subGoal.account.index),
                   )}
                 </div>
                 <div className="flex flex-wrap gap-2">
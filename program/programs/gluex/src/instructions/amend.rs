@@ -0,0 +1,264 @@
+use super::create::create_sub_goal_accounts;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeSubGoalAmendment<'info> {
+    #[account(
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        init,
+        payer = proposer,
+        space = SUB_GOAL_AMENDMENT_SPACE,
+        seeds = [b"subgoal-amendment", goals.key().as_ref()],
+        bump
+    )]
+    pub amendment: Account<'info, SubGoalAmendment>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn propose_subgoal_amendment(
+    ctx: Context<ProposeSubGoalAmendment>,
+    edits: Vec<SubGoalEdit>,
+    additions: Vec<SubGoalInput>,
+) -> Result<()> {
+    let goals = ctx.accounts.goals.load()?;
+    let proposer = ctx.accounts.proposer.key();
+    require!(
+        proposer == goals.issuer || proposer == goals.taker,
+        GluXError::UnauthorizedSigner
+    );
+    validate_amendment(&goals, &edits, &additions)?;
+
+    let amendment = &mut ctx.accounts.amendment;
+    amendment.goal = ctx.accounts.goals.key();
+    amendment.proposer = proposer;
+    amendment.edits = edits;
+    amendment.additions = additions;
+    amendment.base_index = goals.active_sub_goals;
+    amendment.proposed_at = Clock::get()?.unix_timestamp;
+    amendment.bump = ctx.bumps.amendment;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptSubGoalAmendment<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"subgoal-amendment", goals.key().as_ref()],
+        bump = amendment.bump
+    )]
+    pub amendment: Account<'info, SubGoalAmendment>,
+    /// CHECK: receives the proposal rent, pinned to the stored proposer
+    #[account(mut, address = amendment.proposer)]
+    pub proposer: AccountInfo<'info>,
+    #[account(mut)]
+    pub counterparty: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts: the edited SubGoalAccounts in edit order, then one
+    // new SubGoalAccount PDA per addition in index order
+}
+
+pub fn accept_subgoal_amendment<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AcceptSubGoalAmendment<'info>>,
+) -> Result<()> {
+    let goal_key = ctx.accounts.goals.key();
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    let amendment = &ctx.accounts.amendment;
+
+    // parties may have changed hands since the proposal
+    let counterparty = if amendment.proposer == goals.issuer {
+        goals.taker
+    } else {
+        require_keys_eq!(amendment.proposer, goals.taker, GluXError::AmendmentInvalid);
+        goals.issuer
+    };
+    require_keys_eq!(
+        ctx.accounts.counterparty.key(),
+        counterparty,
+        GluXError::AmendmentUnauthorized
+    );
+    require!(
+        goals.active_sub_goals == amendment.base_index,
+        GluXError::AmendmentInvalid
+    );
+    validate_amendment(&goals, &amendment.edits, &amendment.additions)?;
+    require!(
+        ctx.remaining_accounts.len() == amendment.edits.len() + amendment.additions.len(),
+        GluXError::SubGoalAccountMismatch
+    );
+    let (edit_accounts, new_accounts) = ctx.remaining_accounts.split_at(amendment.edits.len());
+
+    let allocated_before = goals.allocated_amount;
+    for (edit, info) in amendment.edits.iter().zip(edit_accounts.iter()) {
+        let mut sub_goal = Account::<SubGoalAccount>::try_from(info)?;
+        require!(
+            sub_goal.goal == goal_key && sub_goal.index == edit.index,
+            GluXError::SubGoalAccountMismatch
+        );
+        require!(
+            !matches!(sub_goal.status, SubGoalStatus::Approved | SubGoalStatus::Paid),
+            GluXError::SubGoalAlreadyFinalized
+        );
+
+        if edit.deactivate {
+            sub_goal.is_active = false;
+            goals.settle_sub_goal(edit.index, sub_goal.incentive_amount);
+        } else {
+            if let Some(title) = edit.title.as_ref() {
+                sub_goal.title = string_to_fixed(title);
+            }
+            if let Some(deadline) = edit.deadline {
+                sub_goal.deadline = deadline;
+            }
+            if let Some(amount) = edit.incentive_amount {
+                goals.allocated_amount = goals
+                    .allocated_amount
+                    .saturating_sub(sub_goal.incentive_amount)
+                    .saturating_add(amount);
+                sub_goal.incentive_amount = amount;
+            }
+            if let Some(auto_release_at) = edit.auto_release_at {
+                sub_goal.auto_release_at = auto_release_at;
+            }
+        }
+        sub_goal.exit(ctx.program_id)?;
+    }
+
+    let added: u64 = amendment.additions.iter().map(|goal| goal.incentive_amount).sum();
+    goals.allocated_amount = goals.allocated_amount.saturating_add(added);
+    // goals that start over-allocated (fees) may still shrink or reshuffle
+    require!(
+        goals.allocated_amount <= goals.unreleased_amount()
+            || goals.allocated_amount <= allocated_before,
+        GluXError::AmendmentExceedsBalance
+    );
+
+    if !amendment.additions.is_empty() {
+        let existing = goals.active_sub_goals;
+        let prerequisites = prerequisite_masks(existing as usize, &amendment.additions)?;
+        let normalized: Vec<SubGoal> = amendment.additions.iter().map(SubGoal::from_input).collect();
+        goals.active_sub_goals = existing + normalized.len() as u8;
+        drop(goals);
+        create_sub_goal_accounts(
+            ctx.program_id,
+            &ctx.accounts.counterparty.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            goal_key,
+            existing,
+            &normalized,
+            &prerequisites,
+            new_accounts,
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelSubGoalAmendment<'info> {
+    #[account(
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"subgoal-amendment", goals.key().as_ref()],
+        bump = amendment.bump
+    )]
+    pub amendment: Account<'info, SubGoalAmendment>,
+    /// CHECK: receives the proposal rent, pinned to the stored proposer
+    #[account(mut, address = amendment.proposer)]
+    pub proposer: AccountInfo<'info>,
+    pub authority: Signer<'info>,
+}
+
+// Either party may withdraw or reject an open proposal.
+pub fn cancel_subgoal_amendment(ctx: Context<CancelSubGoalAmendment>) -> Result<()> {
+    let goals = ctx.accounts.goals.load()?;
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == goals.issuer
+            || authority == goals.taker
+            || authority == ctx.accounts.amendment.proposer,
+        GluXError::UnauthorizedSigner
+    );
+    Ok(())
+}
+
+fn validate_amendment(
+    goals: &TotalGoal,
+    edits: &[SubGoalEdit],
+    additions: &[SubGoalInput],
+) -> Result<()> {
+    require!(
+        !matches!(goals.eventype(), EventType::SurpriseTime | EventType::Streaming),
+        GluXError::EventTypeNotSupport
+    );
+    require!(
+        goals.failed == 0 && goals.refund_started == 0,
+        GluXError::FundingClosed
+    );
+    require!(
+        !edits.is_empty() || !additions.is_empty(),
+        GluXError::AmendmentInvalid
+    );
+    require!(
+        edits.len() <= MAX_AMENDMENT_EDITS && additions.len() <= MAX_AMENDMENT_ADDITIONS,
+        GluXError::AmendmentInvalid
+    );
+
+    let mut seen: u64 = 0;
+    for edit in edits.iter() {
+        require!(
+            edit.index < goals.active_sub_goals,
+            GluXError::SubGoalIndexOutOfBounds
+        );
+        let bit = 1u64 << edit.index;
+        // settled subgoals are final, and each subgoal is edited at most once
+        require!(
+            seen & bit == 0 && goals.settled_mask & bit == 0,
+            GluXError::AmendmentInvalid
+        );
+        seen |= bit;
+        if let Some(title) = edit.title.as_ref() {
+            require!(
+                title.len() <= MAX_SUBGOAL_TITLE_LENGTH,
+                GluXError::AmendmentInvalid
+            );
+        }
+    }
+
+    if !additions.is_empty() {
+        require!(
+            matches!(goals.eventype(), EventType::TargetAchieve),
+            GluXError::EventTypeNotSupport
+        );
+        require!(
+            goals.active_sub_goals as usize + additions.len() <= MAXIUMUN_SUBGOALS,
+            GluXError::SubGoalNumExceed
+        );
+        for input in additions.iter() {
+            require!(
+                input.title.len() <= MAX_SUBGOAL_TITLE_LENGTH
+                    && input.prerequisites.len() <= MAX_AMENDMENT_PREREQUISITES,
+                GluXError::AmendmentInvalid
+            );
+        }
+        prerequisite_masks(goals.active_sub_goals as usize, additions)?;
+    }
+    Ok(())
+}
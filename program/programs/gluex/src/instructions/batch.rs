@@ -0,0 +1,218 @@
+use super::create::{
+    create_pda_account, create_sub_goal_accounts, param_check, prepare_sub_goals, zero_copy_goal, GoalSpec,
+};
+use super::manage::{apply_review, Payees};
+use super::registry::claim_goal_id;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+pub struct BatchSetupGoals<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // optional fee pool: if present, every goal pays its own protocol fee
+    #[account(mut)]
+    pub fee_pool: Option<Account<'info, FeePool>>,
+    /// CHECK: the payer's UserGoalIndex PDA, empty when they have none; see claim_goal_id
+    #[account(mut, seeds = [b"user-goal-index", payer.key().as_ref()], bump)]
+    pub issuer_index: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts, per taker in order: the goal PDA for
+    // (payer, taker, goal_id), then its SubGoalAccount PDAs in index order
+}
+
+// Creates one identical goal per taker. All goals share `goal_id`, which is
+// unique per (issuer, taker) pair, so a class or team can be set up with a
+// handful of transactions.
+pub fn batch_setup_goals<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchSetupGoals<'info>>,
+    takers: Vec<Pubkey>,
+    goal_id: i64,
+    input: BatchGoalInput,
+) -> Result<()> {
+    require!(
+        !takers.is_empty() && takers.len() <= MAX_BATCH_GOALS,
+        GluXError::BatchInvalid
+    );
+    let payer_info = ctx.accounts.payer.to_account_info();
+    param_check(
+        &payer_info,
+        &input.description,
+        &input.eventype,
+        &input.sub_goals,
+        input.total_incentive_amount,
+        input.completion_time,
+        input.locked_amount,
+        input.unlock_time,
+        &input.config,
+    )?;
+    require!(
+        input
+            .total_incentive_amount
+            .saturating_mul(takers.len() as u64)
+            <= payer_info.lamports(),
+        GluXError::PayerAccountInsufficient
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let (sub_goals, active_sub_goals, start_time, surprise_ts, checkpoint_interval) =
+        prepare_sub_goals(
+            &input.eventype,
+            &input.sub_goals,
+            input.total_incentive_amount,
+            input.completion_time,
+            &input.config,
+            now,
+        )?;
+    let prerequisites = match input.eventype {
+        EventType::TargetAchieve => prerequisite_masks(0, &input.sub_goals)?,
+        _ => vec![0u64; sub_goals.len()],
+    };
+    let per_goal = 1 + sub_goals.len();
+    require!(
+        ctx.remaining_accounts.len() == takers.len() * per_goal,
+        GluXError::BatchInvalid
+    );
+    claim_goal_id(&ctx.accounts.issuer_index, goal_id)?;
+
+    let fee = ctx
+        .accounts
+        .fee_pool
+        .as_ref()
+        .map(|pool| pool.fee_for(input.total_incentive_amount))
+        .unwrap_or(0);
+    let deposited = input.total_incentive_amount.saturating_sub(fee);
+    let spec = GoalSpec {
+        description: &input.description,
+        room: input.room,
+        relations: input.relations,
+        eventype: input.eventype,
+        active_sub_goals,
+        total_incentive_amount: input.total_incentive_amount,
+        deposited_amount: deposited,
+        completion_time: input.completion_time,
+        locked_amount: input.locked_amount,
+        unlock_time: input.unlock_time,
+        start_time,
+        surprise_ts,
+        checkpoint_interval,
+        slot: Clock::get()?.slot,
+        config: &input.config,
+    };
+
+    let system_info = ctx.accounts.system_program.to_account_info();
+    let issuer = ctx.accounts.payer.key();
+    let id_bytes = goal_id.to_le_bytes();
+    let rent = Rent::get()?.minimum_balance(GOAL_ACCOUNT_SPACE);
+    for (taker, accounts) in takers.iter().zip(ctx.remaining_accounts.chunks(per_goal)) {
+        let goal_info = &accounts[0];
+        let (expected, bump) = Pubkey::find_program_address(
+            &[b"gluex-goals", issuer.as_ref(), taker.as_ref(), &id_bytes],
+            ctx.program_id,
+        );
+        require_keys_eq!(expected, goal_info.key(), GluXError::BatchInvalid);
+
+        let bump_seed = [bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"gluex-goals",
+            issuer.as_ref(),
+            taker.as_ref(),
+            &id_bytes,
+            &bump_seed,
+        ]];
+        create_pda_account(
+            &payer_info,
+            &system_info,
+            goal_info,
+            rent.saturating_add(deposited),
+            GOAL_ACCOUNT_SPACE,
+            ctx.program_id,
+            signer_seeds,
+        )?;
+        {
+            let mut data = goal_info.try_borrow_mut_data()?;
+            spec.write(zero_copy_goal(&mut data)?, issuer, *taker, goal_id, bump);
+        }
+
+        create_sub_goal_accounts(
+            ctx.program_id,
+            &payer_info,
+            &system_info,
+            goal_info.key(),
+            0,
+            &sub_goals,
+            &prerequisites,
+            &accounts[1..],
+        )?;
+    }
+
+    // fees are charged per goal but collected in a single transfer
+    let total_fee = fee.saturating_mul(takers.len() as u64);
+    if total_fee > 0 {
+        if let Some(fee_pool) = ctx.accounts.fee_pool.as_ref() {
+            system_program::transfer(
+                CpiContext::new(
+                    system_info,
+                    system_program::Transfer {
+                        from: payer_info,
+                        to: fee_pool.to_account_info(),
+                    },
+                ),
+                total_fee,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct BatchReviewSubGoals<'info> {
+    // the issuer of every goal, or the verifier of every reviewed subgoal
+    pub reviewer: Signer<'info>,
+    // remaining accounts, per goal in order: the goal, its SubGoalAccount at
+    // `subgoal_index`, and the taker receiving the payout. Goals with a
+    // beneficiary split must be reviewed one at a time.
+}
+
+// Applies the same review decision to one subgoal index across many goals,
+// e.g. approving week 1 for a whole class.
+// `is_multiple_of` is newer than the SBF toolchain's rustc.
+#[allow(clippy::manual_is_multiple_of)]
+pub fn batch_review_subgoals<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchReviewSubGoals<'info>>,
+    subgoal_index: u8,
+    approve: bool,
+) -> Result<()> {
+    let accounts = ctx.remaining_accounts;
+    require!(
+        !accounts.is_empty()
+            && accounts.len() % 3 == 0
+            && accounts.len() / 3 <= MAX_BATCH_GOALS,
+        GluXError::BatchInvalid
+    );
+    let reviewer = ctx.accounts.reviewer.key();
+
+    for entry in accounts.chunks(3) {
+        let (goal_info, sub_goal_info, taker_info) = (&entry[0], &entry[1], &entry[2]);
+        let loader = AccountLoader::<TotalGoal>::try_from(goal_info)?;
+        let mut goals = loader.load_mut()?;
+        let mut sub_goal = Account::<SubGoalAccount>::try_from(sub_goal_info)?;
+        require!(
+            sub_goal.goal == goal_info.key() && sub_goal.index == subgoal_index,
+            GluXError::SubGoalAccountMismatch
+        );
+
+        apply_review(
+            &mut goals,
+            goal_info,
+            &mut sub_goal,
+            reviewer,
+            &Payees::taker(taker_info),
+            subgoal_index,
+            approve,
+        )?;
+        sub_goal.exit(ctx.program_id)?;
+    }
+    Ok(())
+}
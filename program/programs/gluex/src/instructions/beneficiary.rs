@@ -0,0 +1,86 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ConfigureBeneficiaries<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        init,
+        payer = issuer,
+        space = GOAL_BENEFICIARIES_SPACE,
+        seeds = [b"goal-beneficiaries", goals.key().as_ref()],
+        bump
+    )]
+    pub beneficiaries: Account<'info, GoalBeneficiaries>,
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+    // the split redirects the taker's payouts, so the taker signs too
+    pub taker: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts: every wallet named in the split, in any order
+}
+
+// Attaches a beneficiary split right after setup_goal, before anything has
+// been paid out. It is a separate instruction because the taker has to sign,
+// which setup_goal does not require. The split is fixed for the life of the
+// goal.
+//
+// Every wallet must already hold the rent-exempt minimum: a split portion
+// below it could never be paid into an empty wallet.
+pub fn configure_beneficiaries(
+    ctx: Context<ConfigureBeneficiaries>,
+    input: BeneficiaryInput,
+) -> Result<()> {
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    require_keys_eq!(
+        goals.issuer,
+        ctx.accounts.issuer.key(),
+        GluXError::UnauthorizedSigner
+    );
+    require_keys_eq!(
+        goals.taker,
+        ctx.accounts.taker.key(),
+        GluXError::UnauthorizedTaker
+    );
+    require!(
+        !matches!(goals.eventype(), EventType::Streaming)
+            && goals.settled_mask == 0
+            && goals.released_amount == 0
+            && goals.failed == 0
+            && goals.refund_started == 0,
+        GluXError::BeneficiariesInvalid
+    );
+    require!(
+        input.validate(goals.active_sub_goals),
+        GluXError::BeneficiariesInvalid
+    );
+    let rent_exempt = Rent::get()?.minimum_balance(0);
+    let named = input
+        .shares
+        .iter()
+        .chain(input.sub_goal_shares.iter().flat_map(|split| split.shares.iter()));
+    for share in named {
+        let wallet = ctx
+            .remaining_accounts
+            .iter()
+            .find(|wallet| wallet.key() == share.wallet)
+            .ok_or(GluXError::BeneficiaryMismatch)?;
+        require!(
+            wallet.lamports() >= rent_exempt,
+            GluXError::BeneficiaryWalletUnfunded
+        );
+    }
+
+    let beneficiaries = &mut ctx.accounts.beneficiaries;
+    beneficiaries.goal = ctx.accounts.goals.key();
+    beneficiaries.shares = input.shares;
+    beneficiaries.sub_goal_shares = input.sub_goal_shares;
+    beneficiaries.bump = ctx.bumps.beneficiaries;
+    goals.split_payouts = 1;
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use super::matching::{check_issuer_reputation, draw_matched_funds};
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
@@ -8,7 +9,7 @@ pub struct PublishBounty<'info> {
     #[account(
         init,
         payer = issuer,
-        space = 8 + 32 + 8 + 4 + 200 + 4 + 200 + 8 + 8 + 2 + 2 + 1 + 1,
+        space = 8 + 32 + 8 + 4 + 200 + 4 + 200 + 8 + 8 + 2 + 2 + 1 + 1 + 32 + 8 + 1 + 2 + 1,
         seeds = [b"open-bounty", issuer.key().as_ref(), bounty_id.to_le_bytes().as_ref()],
         bump
     )]
@@ -17,6 +18,12 @@ pub struct PublishBounty<'info> {
     pub issuer: Signer<'info>,
     #[account(mut)]
     pub fee_pool: Option<Account<'info, FeePool>>,
+    #[account(mut)]
+    pub matching_pool: Option<Account<'info, MatchingPool>>,
+    // required with a matching pool: the issuer's allowance for that pool
+    #[account(mut)]
+    pub matching_allowance: Option<Account<'info, MatchingAllowance>>,
+    pub issuer_profile: Option<Account<'info, AgentProfile>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -83,6 +90,33 @@ pub fn publish_bounty(
         system_program::transfer(dep_ctx, deposited)?;
     }
 
+    if let Some(pool) = ctx.accounts.matching_pool.as_mut() {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < pool.expires_at, GluXError::MatchingPoolExpired);
+        require!(pool.accepts_bounty(), GluXError::MatchingPoolIneligible);
+        // reclaim_bounty_match returns the unpaid match after the deadline
+        require!(deadline > now, GluXError::MatchingPoolIneligible);
+        check_issuer_reputation(
+            pool,
+            ctx.accounts.issuer_profile.as_ref(),
+            &ctx.accounts.issuer.key(),
+        )?;
+        let per_bounty_cap = pool.per_bounty_cap;
+        let matched = draw_matched_funds(
+            pool,
+            ctx.accounts.matching_allowance.as_mut(),
+            &ctx.accounts.issuer.key(),
+            &bounty.to_account_info(),
+            deposited,
+            per_bounty_cap,
+        )?;
+        if matched > 0 {
+            bounty.incentive_amount = bounty.incentive_amount.saturating_add(matched);
+            bounty.matched_amount = matched;
+            bounty.matching_pool = pool.key();
+        }
+    }
+
     Ok(())
 }
 
@@ -97,7 +131,7 @@ pub struct ClaimBounty<'info> {
     #[account(
         init,
         payer = taker,
-        space = 8 + 32 + 32 + 8 + 1 + 1 + 200 + 1,
+        space = 8 + 32 + 32 + 8 + 1 + 1 + 200 + 1 + 1,
         seeds = [b"bounty-exec", open_bounty.key().as_ref(), taker.key().as_ref()],
         bump
     )]
@@ -129,6 +163,34 @@ pub fn claim_bounty(ctx: Context<ClaimBounty>) -> Result<()> {
     Ok(())
 }
 
+#[derive(Accounts)]
+pub struct SubmitBountyProof<'info> {
+    #[account(
+        seeds = [b"open-bounty", open_bounty.issuer.as_ref(), open_bounty.bounty_id.to_le_bytes().as_ref()],
+        bump = open_bounty.bump
+    )]
+    pub open_bounty: Account<'info, OpenBounty>,
+    #[account(
+        mut,
+        seeds = [b"bounty-exec", open_bounty.key().as_ref(), taker.key().as_ref()],
+        bump = bounty_execution.bump
+    )]
+    pub bounty_execution: Account<'info, BountyExecution>,
+    pub taker: Signer<'info>,
+}
+
+// Records the taker's proof; matched bounties only pay out against one.
+pub fn submit_bounty_proof(ctx: Context<SubmitBountyProof>, proof_uri: String) -> Result<()> {
+    require!(
+        !proof_uri.is_empty() && proof_uri.len() <= MAX_PROOF_URI_LENGTH,
+        GluXError::BountyProofInvalid
+    );
+    let execution = &mut ctx.accounts.bounty_execution;
+    require!(!execution.is_approved && !execution.is_rejected, GluXError::BountyAlreadyFinalized);
+    execution.proof_uri = proof_uri;
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct VerifyAndRewardBounty<'info> {
     #[account(
@@ -166,8 +228,20 @@ pub fn verify_and_reward_bounty(
         return Ok(());
     }
 
+    let bounty = &mut ctx.accounts.open_bounty;
+    if bounty.matched_amount > 0 {
+        require!(!bounty.matching_refunded, GluXError::BountyNotActive);
+        require!(!execution.proof_uri.is_empty(), GluXError::ProofMissing);
+        require_keys_neq!(
+            ctx.accounts.taker_account.key(),
+            bounty.issuer,
+            GluXError::MatchingPoolIneligible
+        );
+    }
+
     execution.is_approved = true;
-    let amount = ctx.accounts.open_bounty.incentive_amount.checked_div(ctx.accounts.open_bounty.max_claims as u64).unwrap_or(0);
+    bounty.approved_claims = bounty.approved_claims.saturating_add(1);
+    let amount = bounty.incentive_amount.checked_div(bounty.max_claims as u64).unwrap_or(0);
 
     // Payout logic
     let bounty_account_info = ctx.accounts.open_bounty.to_account_info();
@@ -196,3 +270,51 @@ pub fn verify_and_reward_bounty(
 
     Ok(())
 }
+
+#[derive(Accounts)]
+pub struct ReclaimBountyMatch<'info> {
+    #[account(
+        mut,
+        seeds = [b"open-bounty", open_bounty.issuer.as_ref(), open_bounty.bounty_id.to_le_bytes().as_ref()],
+        bump = open_bounty.bump
+    )]
+    pub open_bounty: Account<'info, OpenBounty>,
+    #[account(
+        mut,
+        address = open_bounty.matching_pool,
+        seeds = [b"matching-pool", matching_pool.sponsor.as_ref(), matching_pool.pool_id.to_le_bytes().as_ref()],
+        bump = matching_pool.bump
+    )]
+    pub matching_pool: Account<'info, MatchingPool>,
+}
+
+// Permissionless: after the deadline, the matched slice of the claims that
+// were never approved flows back into the pool, and the bounty closes.
+pub fn reclaim_bounty_match(ctx: Context<ReclaimBountyMatch>) -> Result<()> {
+    let bounty = &mut ctx.accounts.open_bounty;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now > bounty.deadline, GluXError::BountyDeadlineNotReached);
+    require!(bounty.matched_amount > 0, GluXError::NoFundsAvailable);
+    require!(!bounty.matching_refunded, GluXError::RefundAlreadyClaimed);
+
+    let max_claims = bounty.max_claims.max(1) as u128;
+    let unpaid_claims = max_claims.saturating_sub(bounty.approved_claims as u128);
+    let share = ((bounty.matched_amount as u128) * unpaid_claims / max_claims) as u64;
+
+    bounty.matching_refunded = true;
+    bounty.is_active = false;
+    if share > 0 {
+        let bounty_info = bounty.to_account_info();
+        let pool_info = ctx.accounts.matching_pool.to_account_info();
+        **bounty_info.try_borrow_mut_lamports()? = bounty_info
+            .lamports()
+            .checked_sub(share)
+            .ok_or(GluXError::NoFundsAvailable)?;
+        **pool_info.try_borrow_mut_lamports()? = pool_info
+            .lamports()
+            .checked_add(share)
+            .ok_or(GluXError::NoFundsAvailable)?;
+        bounty.incentive_amount = bounty.incentive_amount.saturating_sub(share);
+    }
+    Ok(())
+}
@@ -0,0 +1,337 @@
+use super::manage::payout_from_goal;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+#[instruction(challenge_id: u64)]
+pub struct CreateGroupChallenge<'info> {
+    #[account(
+        init,
+        payer = organizer,
+        space = GROUP_CHALLENGE_SPACE,
+        seeds = [b"group-challenge", organizer.key().as_ref(), challenge_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub challenge: Account<'info, GroupChallenge>,
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    // required when the input names a fee pool, so settlement can't pay
+    // its fee to an arbitrary account
+    pub fee_pool: Option<Account<'info, FeePool>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_group_challenge(
+    ctx: Context<CreateGroupChallenge>,
+    challenge_id: u64,
+    input: GroupChallengeInput,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        input.description.len() <= MAXIUMUN_DESCRIPTIONS_LENS && input.validate(now),
+        GluXError::ChallengeInvalid
+    );
+    if input.fee_pool != Pubkey::default() {
+        let fee_pool = ctx.accounts.fee_pool.as_ref().map(|pool| pool.key());
+        require!(
+            fee_pool == Some(input.fee_pool),
+            GluXError::ChallengeInvalid
+        );
+    }
+
+    let challenge = &mut ctx.accounts.challenge;
+    challenge.organizer = ctx.accounts.organizer.key();
+    challenge.challenge_id = challenge_id;
+    challenge.description = input.description;
+    challenge.stake_amount = input.stake_amount;
+    challenge.checkpoints = input.checkpoints;
+    challenge.join_deadline = input.join_deadline;
+    challenge.settle_time = input.settle_time;
+    challenge.max_participants = input.max_participants;
+    challenge.fee_pool = input.fee_pool;
+    challenge.bump = ctx.bumps.challenge;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct JoinGroupChallenge<'info> {
+    #[account(
+        mut,
+        seeds = [b"group-challenge", challenge.organizer.as_ref(), challenge.challenge_id.to_le_bytes().as_ref()],
+        bump = challenge.bump
+    )]
+    pub challenge: Account<'info, GroupChallenge>,
+    #[account(
+        init,
+        payer = participant,
+        space = CHALLENGE_PARTICIPANT_SPACE,
+        seeds = [b"challenge-participant", challenge.key().as_ref(), participant.key().as_ref()],
+        bump
+    )]
+    pub participant_record: Account<'info, ChallengeParticipant>,
+    #[account(mut)]
+    pub participant: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Stakes the challenge amount into the shared pot.
+pub fn join_group_challenge(ctx: Context<JoinGroupChallenge>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let challenge = &mut ctx.accounts.challenge;
+    // the organizer reviews proofs, so they can't also compete
+    require_keys_neq!(
+        ctx.accounts.participant.key(),
+        challenge.organizer,
+        GluXError::UnauthorizedSigner
+    );
+    require!(
+        now < challenge.join_deadline && challenge.participants < challenge.max_participants,
+        GluXError::ChallengeClosed
+    );
+    let stake = challenge.stake_amount;
+    challenge.participants = challenge.participants.saturating_add(1);
+    challenge.pot = challenge.pot.saturating_add(stake);
+
+    let record = &mut ctx.accounts.participant_record;
+    record.challenge = challenge.key();
+    record.participant = ctx.accounts.participant.key();
+    record.stake = stake;
+    record.submitted_mask = 0;
+    record.approved_mask = 0;
+    record.proof_uris = [[0u8; MAX_PROOF_URI_LENGTH]; MAX_CHALLENGE_CHECKPOINTS];
+    record.completed = false;
+    record.bump = ctx.bumps.participant_record;
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.participant.to_account_info(),
+                to: challenge.to_account_info(),
+            },
+        ),
+        stake,
+    )
+}
+
+#[derive(Accounts)]
+pub struct SubmitChallengeProof<'info> {
+    #[account(
+        seeds = [b"group-challenge", challenge.organizer.as_ref(), challenge.challenge_id.to_le_bytes().as_ref()],
+        bump = challenge.bump
+    )]
+    pub challenge: Account<'info, GroupChallenge>,
+    #[account(
+        mut,
+        seeds = [b"challenge-participant", challenge.key().as_ref(), participant.key().as_ref()],
+        bump = participant_record.bump
+    )]
+    pub participant_record: Account<'info, ChallengeParticipant>,
+    pub participant: Signer<'info>,
+}
+
+pub fn submit_challenge_proof(
+    ctx: Context<SubmitChallengeProof>,
+    checkpoint: u8,
+    proof_uri: String,
+) -> Result<()> {
+    let challenge = &ctx.accounts.challenge;
+    let index = checkpoint as usize;
+    require!(
+        index < challenge.checkpoints.len(),
+        GluXError::SubGoalIndexOutOfBounds
+    );
+    require!(
+        proof_uri.len() <= MAX_PROOF_URI_LENGTH,
+        GluXError::ChallengeInvalid
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now <= challenge.checkpoints[index],
+        GluXError::SubmissionWindowClosed
+    );
+
+    let record = &mut ctx.accounts.participant_record;
+    let bit = 1u64 << index;
+    require!(
+        record.approved_mask & bit == 0,
+        GluXError::SubGoalAlreadyFinalized
+    );
+    record.proof_uris[index] = string_to_fixed(&proof_uri);
+    record.submitted_mask |= bit;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReviewChallengeProof<'info> {
+    #[account(
+        mut,
+        seeds = [b"group-challenge", challenge.organizer.as_ref(), challenge.challenge_id.to_le_bytes().as_ref()],
+        bump = challenge.bump
+    )]
+    pub challenge: Account<'info, GroupChallenge>,
+    #[account(
+        mut,
+        seeds = [b"challenge-participant", challenge.key().as_ref(), participant_record.participant.as_ref()],
+        bump = participant_record.bump
+    )]
+    pub participant_record: Account<'info, ChallengeParticipant>,
+    pub organizer: Signer<'info>,
+}
+
+// Approvals are final; a rejected proof can be resubmitted until the
+// checkpoint deadline.
+pub fn review_challenge_proof(
+    ctx: Context<ReviewChallengeProof>,
+    checkpoint: u8,
+    approve: bool,
+) -> Result<()> {
+    let challenge = &mut ctx.accounts.challenge;
+    require_keys_eq!(
+        challenge.organizer,
+        ctx.accounts.organizer.key(),
+        GluXError::UnauthorizedSigner
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        !challenge.settled && now < challenge.settle_time,
+        GluXError::ChallengeClosed
+    );
+    let index = checkpoint as usize;
+    require!(
+        index < challenge.checkpoints.len(),
+        GluXError::SubGoalIndexOutOfBounds
+    );
+
+    let record = &mut ctx.accounts.participant_record;
+    let bit = 1u64 << index;
+    require!(record.submitted_mask & bit != 0, GluXError::ProofMissing);
+    record.submitted_mask &= !bit;
+    if !approve {
+        return Ok(());
+    }
+
+    record.approved_mask |= bit;
+    if record.approved_mask == challenge.all_checkpoints() {
+        record.completed = true;
+        challenge.finishers = challenge.finishers.saturating_add(1);
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleGroupChallenge<'info> {
+    #[account(
+        mut,
+        seeds = [b"group-challenge", challenge.organizer.as_ref(), challenge.challenge_id.to_le_bytes().as_ref()],
+        bump = challenge.bump
+    )]
+    pub challenge: Account<'info, GroupChallenge>,
+    // required when the challenge names a fee pool
+    #[account(mut)]
+    pub fee_pool: Option<Account<'info, FeePool>>,
+}
+
+// Permissionless once reviews close: takes the protocol fee and fixes each
+// finisher's share. Dropouts' stakes stay in the pot and go to finishers.
+pub fn settle_group_challenge(ctx: Context<SettleGroupChallenge>) -> Result<()> {
+    let challenge = &mut ctx.accounts.challenge;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= challenge.settle_time, GluXError::ChallengeClosed);
+    require!(!challenge.settled, GluXError::ChallengeClosed);
+    challenge.settled = true;
+
+    // nobody finished: every participant gets their stake back, fee free
+    if challenge.finishers == 0 {
+        return Ok(());
+    }
+
+    let mut fee = 0;
+    if challenge.fee_pool != Pubkey::default() {
+        let fee_pool = ctx
+            .accounts
+            .fee_pool
+            .as_ref()
+            .ok_or(GluXError::ChallengeInvalid)?;
+        require_keys_eq!(
+            fee_pool.key(),
+            challenge.fee_pool,
+            GluXError::ChallengeInvalid
+        );
+        fee = fee_pool.fee_for(challenge.pot);
+        if fee > 0 {
+            payout_from_goal(&challenge.to_account_info(), fee_pool.to_account_info(), fee)?;
+        }
+    }
+    challenge.pot = challenge.pot.saturating_sub(fee);
+    challenge.payout_per_finisher = challenge.pot / challenge.finishers as u64;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimChallengePayout<'info> {
+    #[account(
+        mut,
+        seeds = [b"group-challenge", challenge.organizer.as_ref(), challenge.challenge_id.to_le_bytes().as_ref()],
+        bump = challenge.bump
+    )]
+    pub challenge: Account<'info, GroupChallenge>,
+    #[account(
+        mut,
+        close = participant,
+        seeds = [b"challenge-participant", challenge.key().as_ref(), participant.key().as_ref()],
+        bump = participant_record.bump
+    )]
+    pub participant_record: Account<'info, ChallengeParticipant>,
+    #[account(mut)]
+    pub participant: Signer<'info>,
+}
+
+// Pays out the participant's share and closes their record. Dropouts only
+// get their record's rent back.
+pub fn claim_challenge_payout(ctx: Context<ClaimChallengePayout>) -> Result<()> {
+    let challenge = &mut ctx.accounts.challenge;
+    require!(challenge.settled, GluXError::ChallengeUnsettled);
+
+    let amount = challenge.payout_for(&ctx.accounts.participant_record);
+    if amount > 0 {
+        payout_from_goal(
+            &challenge.to_account_info(),
+            ctx.accounts.participant.to_account_info(),
+            amount,
+        )?;
+        challenge.pot = challenge.pot.saturating_sub(amount);
+    }
+    challenge.claimed = challenge.claimed.saturating_add(1);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseGroupChallenge<'info> {
+    #[account(
+        mut,
+        close = organizer,
+        has_one = organizer,
+        seeds = [b"group-challenge", organizer.key().as_ref(), challenge.challenge_id.to_le_bytes().as_ref()],
+        bump = challenge.bump
+    )]
+    pub challenge: Account<'info, GroupChallenge>,
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+}
+
+// Closes a settled challenge once every participant has claimed, or once
+// the claim period after settle_time has passed. Rounding dust, and the
+// shares of anyone who never claimed, go back to the organizer with the rent.
+pub fn close_group_challenge(ctx: Context<CloseGroupChallenge>) -> Result<()> {
+    let challenge = &ctx.accounts.challenge;
+    require!(challenge.settled, GluXError::ChallengeUnsettled);
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        challenge.claimed == challenge.participants
+            || now >= challenge.settle_time.saturating_add(CHALLENGE_CLAIM_PERIOD_SECONDS),
+        GluXError::ChallengeClosed
+    );
+    Ok(())
+}
@@ -0,0 +1,374 @@
+use super::manage::payout_from_goal;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+#[instruction(child: Pubkey)]
+pub struct CreateChoreBoard<'info> {
+    #[account(
+        init,
+        payer = parent,
+        space = CHORE_BOARD_SPACE,
+        seeds = [b"chore-board", parent.key().as_ref(), child.as_ref()],
+        bump
+    )]
+    pub board: Account<'info, ChoreBoard>,
+    #[account(mut)]
+    pub parent: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_chore_board(
+    ctx: Context<CreateChoreBoard>,
+    child: Pubkey,
+    auto_approve_after: i64,
+    funding: u64,
+) -> Result<()> {
+    require!(
+        auto_approve_after > 0 && child != ctx.accounts.parent.key(),
+        GluXError::ChoreInvalid
+    );
+    let board = &mut ctx.accounts.board;
+    board.parent = ctx.accounts.parent.key();
+    board.child = child;
+    board.created_at = Clock::get()?.unix_timestamp;
+    board.auto_approve_after = auto_approve_after;
+    board.bump = ctx.bumps.board;
+
+    if funding > 0 {
+        deposit_allowance(
+            board,
+            ctx.accounts.parent.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            funding,
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundChoreBoard<'info> {
+    #[account(
+        mut,
+        has_one = parent,
+        seeds = [b"chore-board", parent.key().as_ref(), board.child.as_ref()],
+        bump = board.bump
+    )]
+    pub board: Account<'info, ChoreBoard>,
+    #[account(mut)]
+    pub parent: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn fund_chore_board(ctx: Context<FundChoreBoard>, amount: u64) -> Result<()> {
+    require!(amount > 0, GluXError::PayerAccountInsufficient);
+    deposit_allowance(
+        &mut ctx.accounts.board,
+        ctx.accounts.parent.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+pub struct WithdrawChoreFunds<'info> {
+    #[account(
+        mut,
+        has_one = parent,
+        seeds = [b"chore-board", parent.key().as_ref(), board.child.as_ref()],
+        bump = board.bump
+    )]
+    pub board: Account<'info, ChoreBoard>,
+    #[account(mut)]
+    pub parent: Signer<'info>,
+}
+
+// Takes back allowance not promised to check-ins awaiting review.
+pub fn withdraw_chore_funds(ctx: Context<WithdrawChoreFunds>, amount: u64) -> Result<()> {
+    let board = &mut ctx.accounts.board;
+    require!(
+        amount > 0 && amount <= board.available(),
+        GluXError::NoFundsAvailable
+    );
+    payout_from_goal(&board.to_account_info(), ctx.accounts.parent.to_account_info(), amount)?;
+    board.released = board.released.saturating_add(amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddChore<'info> {
+    #[account(
+        mut,
+        has_one = parent,
+        seeds = [b"chore-board", parent.key().as_ref(), board.child.as_ref()],
+        bump = board.bump
+    )]
+    pub board: Account<'info, ChoreBoard>,
+    #[account(
+        init,
+        payer = parent,
+        space = CHORE_SPACE,
+        seeds = [b"chore", board.key().as_ref(), &[board.chore_count]],
+        bump
+    )]
+    pub chore: Account<'info, Chore>,
+    #[account(mut)]
+    pub parent: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_chore(ctx: Context<AddChore>, title: String, reward: u64, weekly_cap: u8) -> Result<()> {
+    require!(
+        title.len() <= MAX_SUBGOAL_TITLE_LENGTH && reward > 0 && weekly_cap > 0,
+        GluXError::ChoreInvalid
+    );
+    let board = &mut ctx.accounts.board;
+    let chore = &mut ctx.accounts.chore;
+    chore.board = board.key();
+    chore.index = board.chore_count;
+    chore.title = string_to_fixed(&title);
+    chore.reward = reward;
+    chore.weekly_cap = weekly_cap;
+    chore.week = 0;
+    chore.week_count = 0;
+    chore.completions = 0;
+    chore.is_active = true;
+    chore.bump = ctx.bumps.chore;
+    board.chore_count = board.chore_count.checked_add(1).ok_or(GluXError::ChoreInvalid)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateChore<'info> {
+    #[account(
+        has_one = parent,
+        seeds = [b"chore-board", parent.key().as_ref(), board.child.as_ref()],
+        bump = board.bump
+    )]
+    pub board: Account<'info, ChoreBoard>,
+    #[account(
+        mut,
+        has_one = board,
+        seeds = [b"chore", board.key().as_ref(), &[chore.index]],
+        bump = chore.bump
+    )]
+    pub chore: Account<'info, Chore>,
+    pub parent: Signer<'info>,
+}
+
+// Changes apply to later check-ins; pending ones keep their reward.
+pub fn update_chore(
+    ctx: Context<UpdateChore>,
+    reward: u64,
+    weekly_cap: u8,
+    is_active: bool,
+) -> Result<()> {
+    require!(reward > 0 && weekly_cap > 0, GluXError::ChoreInvalid);
+    let chore = &mut ctx.accounts.chore;
+    chore.reward = reward;
+    chore.weekly_cap = weekly_cap;
+    chore.is_active = is_active;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CheckInChore<'info> {
+    #[account(
+        mut,
+        has_one = child,
+        seeds = [b"chore-board", board.parent.as_ref(), child.key().as_ref()],
+        bump = board.bump
+    )]
+    pub board: Account<'info, ChoreBoard>,
+    #[account(
+        mut,
+        has_one = board,
+        seeds = [b"chore", board.key().as_ref(), &[chore.index]],
+        bump = chore.bump
+    )]
+    pub chore: Account<'info, Chore>,
+    #[account(
+        init,
+        payer = child,
+        space = CHORE_COMPLETION_SPACE,
+        seeds = [b"chore-completion", chore.key().as_ref(), chore.completions.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub completion: Account<'info, ChoreCompletion>,
+    #[account(mut)]
+    pub child: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Records a completed chore, optionally with a photo proof URI. The reward
+// is reserved from the allowance until the check-in is reviewed.
+pub fn check_in_chore(ctx: Context<CheckInChore>, proof_uri: String) -> Result<()> {
+    require!(
+        proof_uri.len() <= MAX_PROOF_URI_LENGTH,
+        GluXError::ChoreInvalid
+    );
+    let board = &mut ctx.accounts.board;
+    let chore = &mut ctx.accounts.chore;
+    require!(chore.is_active, GluXError::ChoreInvalid);
+
+    let now = Clock::get()?.unix_timestamp;
+    let week = board.week_at(now);
+    if chore.week != week {
+        chore.week = week;
+        chore.week_count = 0;
+    }
+    require!(chore.week_count < chore.weekly_cap, GluXError::ChoreCapReached);
+    require!(board.available() >= chore.reward, GluXError::ChoreUnderfunded);
+
+    let completion = &mut ctx.accounts.completion;
+    completion.chore = chore.key();
+    completion.id = chore.completions;
+    completion.week = week;
+    completion.reward = chore.reward;
+    completion.status = SubGoalStatus::ProofSubmitted;
+    completion.proof_uri = string_to_fixed(&proof_uri);
+    completion.submitted_at = now;
+    completion.bump = ctx.bumps.completion;
+
+    chore.week_count = chore.week_count.saturating_add(1);
+    chore.completions = chore.completions.saturating_add(1);
+    board.pending_rewards = board.pending_rewards.saturating_add(chore.reward);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReviewChoreCompletions<'info> {
+    #[account(
+        mut,
+        has_one = parent,
+        has_one = child,
+        seeds = [b"chore-board", parent.key().as_ref(), child.key().as_ref()],
+        bump = board.bump
+    )]
+    pub board: Account<'info, ChoreBoard>,
+    pub parent: Signer<'info>,
+    /// CHECK: payout destination, matched by has_one
+    #[account(mut)]
+    pub child: AccountInfo<'info>,
+    // remaining accounts, per check-in: the ChoreCompletion, then its Chore
+}
+
+// Week-end review of many check-ins at once. Reviewed check-ins are closed
+// and their rent returned to the child.
+pub fn review_chore_completions<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ReviewChoreCompletions<'info>>,
+    approvals: Vec<bool>,
+) -> Result<()> {
+    let accounts = ctx.remaining_accounts;
+    require!(
+        !approvals.is_empty()
+            && approvals.len() <= MAX_CHORE_REVIEWS
+            && accounts.len() == approvals.len() * 2,
+        GluXError::ChoreInvalid
+    );
+    let board = &mut ctx.accounts.board;
+    let child_info = ctx.accounts.child.to_account_info();
+
+    for (entry, approve) in accounts.chunks(2).zip(approvals.iter()) {
+        let completion = Account::<ChoreCompletion>::try_from(&entry[0])?;
+        let mut chore = Account::<Chore>::try_from(&entry[1])?;
+        require!(
+            completion.chore == chore.key() && chore.board == board.key(),
+            GluXError::ChoreInvalid
+        );
+        require!(
+            matches!(completion.status, SubGoalStatus::ProofSubmitted),
+            GluXError::SubGoalAlreadyFinalized
+        );
+
+        if *approve {
+            pay_completion(board, &child_info, &completion)?;
+        } else {
+            board.pending_rewards = board.pending_rewards.saturating_sub(completion.reward);
+            // a rejected check-in frees its slot for the week
+            if completion.week == chore.week {
+                chore.week_count = chore.week_count.saturating_sub(1);
+                chore.exit(ctx.program_id)?;
+            }
+        }
+        completion.close(child_info.clone())?;
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AutoApproveChore<'info> {
+    #[account(
+        mut,
+        has_one = child,
+        seeds = [b"chore-board", board.parent.as_ref(), child.key().as_ref()],
+        bump = board.bump
+    )]
+    pub board: Account<'info, ChoreBoard>,
+    #[account(
+        has_one = board,
+        seeds = [b"chore", board.key().as_ref(), &[chore.index]],
+        bump = chore.bump
+    )]
+    pub chore: Account<'info, Chore>,
+    #[account(
+        mut,
+        close = child,
+        constraint = completion.chore == chore.key() @ GluXError::ChoreInvalid,
+        seeds = [b"chore-completion", chore.key().as_ref(), completion.id.to_le_bytes().as_ref()],
+        bump = completion.bump
+    )]
+    pub completion: Account<'info, ChoreCompletion>,
+    /// CHECK: payout destination, matched by has_one
+    #[account(mut)]
+    pub child: AccountInfo<'info>,
+}
+
+// Permissionless: pays a check-in the parent left unreviewed past the
+// board's auto-approve timeout.
+pub fn auto_approve_chore(ctx: Context<AutoApproveChore>) -> Result<()> {
+    let board = &mut ctx.accounts.board;
+    let completion = &ctx.accounts.completion;
+    require!(
+        matches!(completion.status, SubGoalStatus::ProofSubmitted),
+        GluXError::SubGoalAlreadyFinalized
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= completion.submitted_at.saturating_add(board.auto_approve_after),
+        GluXError::ChoreReviewPending
+    );
+    pay_completion(board, &ctx.accounts.child.to_account_info(), completion)
+}
+
+fn pay_completion(
+    board: &mut Account<ChoreBoard>,
+    child: &AccountInfo,
+    completion: &ChoreCompletion,
+) -> Result<()> {
+    payout_from_goal(&board.to_account_info(), child.clone(), completion.reward)?;
+    board.pending_rewards = board.pending_rewards.saturating_sub(completion.reward);
+    board.released = board.released.saturating_add(completion.reward);
+    Ok(())
+}
+
+fn deposit_allowance<'info>(
+    board: &mut Account<'info, ChoreBoard>,
+    parent: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            system_program,
+            system_program::Transfer {
+                from: parent,
+                to: board.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    board.deposited = board.deposited.saturating_add(amount);
+    Ok(())
+}
@@ -0,0 +1,296 @@
+use super::manage::payout_from_goal;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+#[instruction(circle_id: u64)]
+pub struct CreateSavingsCircle<'info> {
+    #[account(
+        init,
+        payer = organizer,
+        space = SAVINGS_CIRCLE_SPACE,
+        seeds = [b"savings-circle", organizer.key().as_ref(), circle_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub circle: Account<'info, SavingsCircle>,
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_savings_circle(
+    ctx: Context<CreateSavingsCircle>,
+    circle_id: u64,
+    input: SavingsCircleInput,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(input.validate(now), GluXError::CircleInvalid);
+
+    let count = input.members.len();
+    let circle = &mut ctx.accounts.circle;
+    circle.organizer = ctx.accounts.organizer.key();
+    circle.circle_id = circle_id;
+    circle.relations = input.relations;
+    circle.members = input.members;
+    circle.payout_order = input.payout_order;
+    circle.collateral = vec![0; count];
+    circle.missed = vec![0; count];
+    circle.contributions = vec![0; count];
+    circle.contribution_amount = input.contribution_amount;
+    circle.collateral_amount = input.collateral_amount;
+    circle.period = input.period;
+    circle.start_time = input.start_time;
+    circle.bump = ctx.bumps.circle;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct JoinSavingsCircle<'info> {
+    #[account(
+        mut,
+        seeds = [b"savings-circle", circle.organizer.as_ref(), circle.circle_id.to_le_bytes().as_ref()],
+        bump = circle.bump
+    )]
+    pub circle: Account<'info, SavingsCircle>,
+    #[account(mut)]
+    pub member: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Posts the member's collateral. Every member must join before the start.
+// The last join fixes the slot a random payout order is drawn from.
+pub fn join_savings_circle(ctx: Context<JoinSavingsCircle>) -> Result<()> {
+    let circle = &mut ctx.accounts.circle;
+    let index = circle
+        .member_index(&ctx.accounts.member.key())
+        .ok_or(GluXError::CircleNotMember)?;
+    let now = Clock::get()?.unix_timestamp;
+    let bit = 1u16 << index;
+    require!(
+        now < circle.start_time && circle.joined_mask & bit == 0,
+        GluXError::CircleInvalid
+    );
+
+    let amount = circle.collateral_amount;
+    circle.collateral[index] = amount;
+    circle.joined_mask |= bit;
+    if circle.joined_mask == circle.all_members() {
+        circle.draw_slot = Clock::get()?.slot.saturating_add(CIRCLE_DRAW_SLOT_DELAY);
+    }
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.member.to_account_info(),
+                to: circle.to_account_info(),
+            },
+        ),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+pub struct StartSavingsCircle<'info> {
+    #[account(
+        mut,
+        seeds = [b"savings-circle", circle.organizer.as_ref(), circle.circle_id.to_le_bytes().as_ref()],
+        bump = circle.bump
+    )]
+    pub circle: Account<'info, SavingsCircle>,
+    pub member: Signer<'info>,
+    /// CHECK: address pinned to the slot hashes sysvar, read as raw bytes
+    #[account(address = SLOT_HASHES_SYSVAR)]
+    pub recent_slothashes: AccountInfo<'info>,
+}
+
+// Locks in the payout order once every member has joined. Circles created
+// without an order get one drawn from the hash of the draw slot fixed by the
+// last join, so nobody can pick the hash by choosing when to start. If that
+// hash has aged out of the sysvar the draw slot is pushed forward instead;
+// any member can start once it passes, so a member holding out for a redraw
+// has to outwait the others.
+pub fn start_savings_circle(ctx: Context<StartSavingsCircle>) -> Result<()> {
+    let circle_key = ctx.accounts.circle.key();
+    let circle = &mut ctx.accounts.circle;
+    require!(
+        circle.member_index(&ctx.accounts.member.key()).is_some(),
+        GluXError::CircleNotMember
+    );
+    let clock = Clock::get()?;
+    require!(
+        !circle.started && clock.unix_timestamp < circle.start_time,
+        GluXError::CircleInvalid
+    );
+    require!(
+        circle.joined_mask == circle.all_members(),
+        GluXError::CircleNotReady
+    );
+
+    if circle.payout_order.is_empty() {
+        require!(clock.slot > circle.draw_slot, GluXError::CircleNotReady);
+        let slot_hashes = ctx.accounts.recent_slothashes.try_borrow_data()?;
+        let Some(slot_hash) = slot_hash_at(&slot_hashes, circle.draw_slot) else {
+            circle.draw_slot = clock.slot.saturating_add(CIRCLE_DRAW_SLOT_DELAY);
+            return Ok(());
+        };
+        circle.payout_order = shuffled_order(circle.members.len(), &slot_hash, &circle_key);
+    }
+    circle.started = true;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ContributeSavingsCircle<'info> {
+    #[account(
+        mut,
+        seeds = [b"savings-circle", circle.organizer.as_ref(), circle.circle_id.to_le_bytes().as_ref()],
+        bump = circle.bump
+    )]
+    pub circle: Account<'info, SavingsCircle>,
+    #[account(mut)]
+    pub member: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Pays the member's contribution into the round open at the current time, or
+// the next unpaid round when everyone already paid into the open one. Rounds
+// are keyed by time so a late payout never closes the next round's window.
+pub fn contribute_savings_circle(ctx: Context<ContributeSavingsCircle>) -> Result<()> {
+    let circle = &mut ctx.accounts.circle;
+    let index = circle
+        .member_index(&ctx.accounts.member.key())
+        .ok_or(GluXError::CircleNotMember)?;
+    require!(
+        circle.started && !circle.is_finished(),
+        GluXError::CircleNotReady
+    );
+    let now = Clock::get()?.unix_timestamp;
+    let round = circle.round_at(now).max(circle.current_round) as usize;
+    let bit = 1u16 << index;
+    require!(
+        round < circle.contributions.len() && circle.contributions[round] & bit == 0,
+        GluXError::CircleContributionClosed
+    );
+
+    let amount = circle.contribution_amount;
+    circle.contributions[round] |= bit;
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.member.to_account_info(),
+                to: circle.to_account_info(),
+            },
+        ),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+pub struct PayoutSavingsRound<'info> {
+    #[account(
+        mut,
+        seeds = [b"savings-circle", circle.organizer.as_ref(), circle.circle_id.to_le_bytes().as_ref()],
+        bump = circle.bump
+    )]
+    pub circle: Account<'info, SavingsCircle>,
+    /// CHECK: validated against the member whose turn it is
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+}
+
+// Permissionless once the round ends, or as soon as everyone has paid.
+// Missed contributions are taken from the defaulting member's collateral
+// before the pot goes to this round's recipient.
+pub fn payout_savings_round(ctx: Context<PayoutSavingsRound>) -> Result<()> {
+    let circle = &mut ctx.accounts.circle;
+    require!(
+        circle.started && !circle.is_finished(),
+        GluXError::CircleNotReady
+    );
+    let round = circle.current_round;
+    let now = Clock::get()?.unix_timestamp;
+    let contributed = circle.contributions[round as usize];
+    require!(
+        now >= circle.round_end(round) || contributed == circle.all_members(),
+        GluXError::CircleNotReady
+    );
+    let recipient = circle.members[circle.payout_order[round as usize] as usize];
+    require_keys_eq!(
+        recipient,
+        ctx.accounts.recipient.key(),
+        GluXError::CircleNotMember
+    );
+
+    let contribution = circle.contribution_amount;
+    let mut pot = contribution.saturating_mul(contributed.count_ones() as u64);
+    for index in 0..circle.members.len() {
+        if contributed & (1u16 << index) != 0 {
+            continue;
+        }
+        let penalty = contribution.min(circle.collateral[index]);
+        circle.collateral[index] = circle.collateral[index].saturating_sub(penalty);
+        circle.missed[index] = circle.missed[index].saturating_add(1);
+        pot = pot.saturating_add(penalty);
+    }
+
+    if pot > 0 {
+        payout_from_goal(
+            &circle.to_account_info(),
+            ctx.accounts.recipient.to_account_info(),
+            pot,
+        )?;
+    }
+    circle.current_round = round.saturating_add(1);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseSavingsCircle<'info> {
+    #[account(
+        mut,
+        close = organizer,
+        has_one = organizer,
+        seeds = [b"savings-circle", organizer.key().as_ref(), circle.circle_id.to_le_bytes().as_ref()],
+        bump = circle.bump
+    )]
+    pub circle: Account<'info, SavingsCircle>,
+    /// CHECK: receives the account rent, matched by has_one
+    #[account(mut)]
+    pub organizer: AccountInfo<'info>,
+    // remaining accounts: every member wallet, in member order
+}
+
+// Permissionless after the last round, or once the start passes without the
+// circle starting. Returns each member's remaining collateral.
+pub fn close_savings_circle<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseSavingsCircle<'info>>,
+) -> Result<()> {
+    let circle = &ctx.accounts.circle;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        circle.is_finished() || (!circle.started && now >= circle.start_time),
+        GluXError::CircleNotReady
+    );
+    require!(
+        ctx.remaining_accounts.len() == circle.members.len(),
+        GluXError::CircleInvalid
+    );
+
+    let circle_info = circle.to_account_info();
+    for (index, member_info) in ctx.remaining_accounts.iter().enumerate() {
+        require_keys_eq!(
+            circle.members[index],
+            member_info.key(),
+            GluXError::CircleNotMember
+        );
+        let refund = circle.collateral[index];
+        if refund > 0 {
+            payout_from_goal(&circle_info, member_info.clone(), refund)?;
+        }
+    }
+    Ok(())
+}
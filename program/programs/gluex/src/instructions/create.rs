@@ -1,10 +1,13 @@
+use super::matching::{check_issuer_reputation, draw_matched_funds};
+use super::registry::claim_goal_id;
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{program::invoke, system_instruction};
+use anchor_lang::system_program;
 use solana_program::hash::hash;
 
-pub fn setup_goal(
-    ctx: Context<SetupGoal>,
+pub fn setup_goal<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetupGoal<'info>>,
     taker: Pubkey,
     goal_id: i64,
     description: String,
@@ -19,7 +22,7 @@ pub fn setup_goal(
     config: GoalConfigInput,
 ) -> Result<()> {
     param_check(
-        &ctx,
+        &ctx.accounts.payer,
         &description,
         &eventype,
         &sub_goals,
@@ -31,7 +34,7 @@ pub fn setup_goal(
     )?;
 
     let now = Clock::get()?.unix_timestamp;
-    let (normalized_sub_goals, active_sub_goals, start_time, surprise_ts, checkpoint_interval) =
+    let (mut normalized_sub_goals, active_sub_goals, start_time, surprise_ts, checkpoint_interval) =
         prepare_sub_goals(
             &eventype,
             &sub_goals,
@@ -41,45 +44,59 @@ pub fn setup_goal(
             now,
         )?;
 
-    let new_goals = &mut ctx.accounts.goals;
-    new_goals.issuer = ctx.accounts.payer.key();
-    new_goals.taker = taker;
-    new_goals.id = goal_id;
-    new_goals.description = description;
-    new_goals.room = room;
-    new_goals.relations = relations;
-    new_goals.eventype = eventype;
-    new_goals.sub_goals = normalized_sub_goals;
-    new_goals.active_sub_goals = active_sub_goals;
-    new_goals.total_incentive_amount = total_incentive_amount;
-    // calculate fee using global FeePool params if provided
-    let mut fee: u64 = 0;
-    if let Some(fee_pool_acct) = ctx.accounts.fee_pool.as_ref() {
-        // if fee pool exists, read params
-        let num = fee_pool_acct.protocol_fee_numerator;
-        let den = fee_pool_acct.protocol_fee_denominator;
-        if den > 0 && num > 0 {
-            let calc = (total_incentive_amount as u128)
-                .checked_mul(num as u128)
-                .ok_or(GluXError::PayerAccountInsufficient)?
-                .checked_div(den as u128)
-                .ok_or(GluXError::PayerAccountInsufficient)?;
-            fee = calc as u64;
+    let prerequisites = match eventype {
+        EventType::TargetAchieve => prerequisite_masks(0, &sub_goals)?,
+        _ => vec![0u64; normalized_sub_goals.len()],
+    };
+
+    // templates are private to their creator and may delegate reviews
+    if let Some(template) = ctx.accounts.template.as_ref() {
+        require_keys_eq!(
+            template.creator,
+            ctx.accounts.payer.key(),
+            GluXError::TemplateMismatch
+        );
+        for goal in normalized_sub_goals.iter_mut() {
+            goal.verifier = template.verifier;
         }
     }
 
+    claim_goal_id(&ctx.accounts.issuer_index, goal_id)?;
+
+    // calculate fee using global FeePool params if provided
+    let fee = ctx
+        .accounts
+        .fee_pool
+        .as_ref()
+        .map(|pool| pool.fee_for(total_incentive_amount))
+        .unwrap_or(0);
     let deposited = total_incentive_amount.saturating_sub(fee);
-    new_goals.deposited_amount = deposited;
-    new_goals.released_amount = 0;
-    new_goals.completion_time = completion_time;
-    new_goals.locked_amount = locked_amount;
-    new_goals.unlock_time = unlock_time;
-    new_goals.start_time = start_time;
-    new_goals.surprise_trigger_ts = surprise_ts;
-    new_goals.checkpoint_interval = checkpoint_interval;
-    new_goals.completed_count = 0;
-    new_goals.failed = false;
-    new_goals.bump = ctx.bumps.goals;
+
+    let spec = GoalSpec {
+        description: &description,
+        room,
+        relations,
+        eventype,
+        active_sub_goals,
+        total_incentive_amount,
+        deposited_amount: deposited,
+        completion_time,
+        locked_amount,
+        unlock_time,
+        start_time,
+        surprise_ts,
+        checkpoint_interval,
+        slot: Clock::get()?.slot,
+        config: &config,
+    };
+    // goal data must be released before the transfers below
+    spec.write(
+        &mut *ctx.accounts.goals.load_init()?,
+        ctx.accounts.payer.key(),
+        taker,
+        goal_id,
+        ctx.bumps.goals,
+    );
 
     // transfer fee (if any) to fee pool, then deposit remaining to goal account
     if fee > 0 {
@@ -100,25 +117,317 @@ pub fn setup_goal(
         }
     }
 
+    let goal_key = ctx.accounts.goals.key();
+    let goal_info = ctx.accounts.goals.to_account_info();
     if deposited > 0 {
         let transfer_ix = system_instruction::transfer(
             &ctx.accounts.payer.key(),
-            &new_goals.key(),
+            &goal_key,
             deposited,
         );
         invoke(
             &transfer_ix,
             &[
                 ctx.accounts.payer.to_account_info(),
-                new_goals.to_account_info(),
+                goal_info.clone(),
                 ctx.accounts.system_program.to_account_info(),
             ],
         )?;
     }
 
+    let mut new_goals = ctx.accounts.goals.load_mut()?;
+    // optional matching pool tops up the escrow from a sponsor's funds
+    if let Some(pool) = ctx.accounts.matching_pool.as_mut() {
+        require!(now < pool.expires_at, GluXError::MatchingPoolExpired);
+        require!(
+            pool.accepts_goal(relations, eventype),
+            GluXError::MatchingPoolIneligible
+        );
+        // an issuer matching their own goal could approve it and keep the match
+        require_keys_neq!(taker, ctx.accounts.payer.key(), GluXError::MatchingPoolIneligible);
+        check_issuer_reputation(
+            pool,
+            ctx.accounts.issuer_profile.as_ref(),
+            &ctx.accounts.payer.key(),
+        )?;
+        let per_goal_cap = pool.per_goal_cap;
+        let matched = draw_matched_funds(
+            pool,
+            ctx.accounts.matching_allowance.as_mut(),
+            &ctx.accounts.payer.key(),
+            &goal_info,
+            deposited,
+            per_goal_cap,
+        )?;
+        if matched > 0 {
+            new_goals.total_incentive_amount =
+                new_goals.total_incentive_amount.saturating_add(matched);
+            new_goals.deposited_amount = new_goals.deposited_amount.saturating_add(matched);
+            new_goals.sponsored_amount = new_goals.sponsored_amount.saturating_add(matched);
+            new_goals.matched_amount = matched;
+            new_goals.matching_pool = pool.key();
+            distribute_bonus(&mut normalized_sub_goals, matched);
+        }
+    }
+
+    new_goals.allocated_amount = normalized_sub_goals
+        .iter()
+        .map(|goal| goal.incentive_amount)
+        .sum();
+    drop(new_goals);
+    create_sub_goal_accounts(
+        ctx.program_id,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        goal_key,
+        0,
+        &normalized_sub_goals,
+        &prerequisites,
+        ctx.remaining_accounts,
+    )
+}
+
+// Goal fields derived from the setup arguments, shared by setup_goal and
+// batch_setup_goals so every goal in a batch is written identically.
+pub(crate) struct GoalSpec<'a> {
+    pub description: &'a str,
+    pub room: Roomspace,
+    pub relations: Relations,
+    pub eventype: EventType,
+    pub active_sub_goals: u8,
+    pub total_incentive_amount: u64,
+    pub deposited_amount: u64,
+    pub completion_time: i64,
+    pub locked_amount: u64,
+    pub unlock_time: i64,
+    pub start_time: i64,
+    pub surprise_ts: i64,
+    pub checkpoint_interval: i64,
+    pub slot: u64, // current slot, anchors the surprise reveal slot
+    pub config: &'a GoalConfigInput,
+}
+
+impl GoalSpec<'_> {
+    pub fn write(&self, goal: &mut TotalGoal, issuer: Pubkey, taker: Pubkey, goal_id: i64, bump: u8) {
+        goal.issuer = issuer;
+        goal.taker = taker;
+        goal.pin_address_keys();
+        goal.id = goal_id;
+        goal.set_description(self.description);
+        goal.room = self.room as u8;
+        goal.relations = self.relations as u8;
+        goal.eventype = self.eventype as u8;
+        goal.active_sub_goals = self.active_sub_goals;
+        goal.sequential = self.config.sequential as u8;
+        goal.late_penalty = self.config.late_penalty.unwrap_or_default().into();
+        if let Some(switch) = self.config.dead_man_switch {
+            goal.dead_man_switch = switch.into();
+        }
+        goal.total_incentive_amount = self.total_incentive_amount;
+        goal.deposited_amount = self.deposited_amount;
+        goal.completion_time = self.completion_time;
+        goal.locked_amount = self.locked_amount;
+        goal.unlock_time = self.unlock_time;
+        goal.start_time = self.start_time;
+        goal.surprise_trigger_ts = self.surprise_ts;
+        goal.checkpoint_interval = self.checkpoint_interval;
+        goal.bump = bump;
+        if let (EventType::SurpriseTime, Some(window)) =
+            (&self.eventype, self.config.surprise_window.as_ref())
+        {
+            goal.surprise_window_end = window.window_end;
+            goal.surprise_commitment = window.commitment;
+            goal.surprise_reveal_slot = self.slot.saturating_add(SURPRISE_REVEAL_SLOT_DELAY);
+        }
+        if let Some(message) = self.config.sealed_message.as_ref() {
+            goal.sealed_message.uri = string_to_fixed(&message.uri);
+            goal.sealed_message.commitment = message.commitment;
+        }
+    }
+}
+
+// Appends milestones to a TargetAchieve goal, e.g. when a long plan does not
+// fit into the setup_goal transaction. New subgoals share the unreleased
+// balance not yet allocated to open subgoals and may depend on any existing
+// subgoal.
+pub fn add_subgoals<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AddSubGoals<'info>>,
+    sub_goals: Vec<SubGoalInput>,
+) -> Result<()> {
+    let goal_key = ctx.accounts.goals.key();
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    require_keys_eq!(
+        goals.issuer,
+        ctx.accounts.issuer.key(),
+        GluXError::UnauthorizedSigner
+    );
+    require!(
+        matches!(goals.eventype(), EventType::TargetAchieve),
+        GluXError::EventTypeNotSupport
+    );
+    require!(
+        goals.failed == 0 && goals.refund_started == 0,
+        GluXError::FundingClosed
+    );
+    require!(!sub_goals.is_empty(), GluXError::MissingSubGoals);
+
+    let existing = goals.active_sub_goals as usize;
+    require!(
+        existing + sub_goals.len() <= MAXIUMUN_SUBGOALS,
+        GluXError::SubGoalNumExceed
+    );
+    let added: u64 = sub_goals.iter().map(|goal| goal.incentive_amount).sum();
+    let allocated = goals.allocated_amount.saturating_add(added);
+    require!(
+        allocated <= goals.unreleased_amount(),
+        GluXError::SumOfSubgoalAmountInvalid
+    );
+
+    let prerequisites = prerequisite_masks(existing, &sub_goals)?;
+    let normalized: Vec<SubGoal> = sub_goals.iter().map(SubGoal::from_input).collect();
+
+    goals.active_sub_goals = (existing + sub_goals.len()) as u8;
+    goals.allocated_amount = allocated;
+    drop(goals);
+    create_sub_goal_accounts(
+        ctx.program_id,
+        &ctx.accounts.issuer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        goal_key,
+        existing as u8,
+        &normalized,
+        &prerequisites,
+        ctx.remaining_accounts,
+    )
+}
+
+// Creates one SubGoalAccount per entry of `sub_goals`, starting at index
+// `first_index`. `targets` are the matching PDAs in index order.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_sub_goal_accounts<'info>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    goal: Pubkey,
+    first_index: u8,
+    sub_goals: &[SubGoal],
+    prerequisites: &[u64],
+    targets: &[AccountInfo<'info>],
+) -> Result<()> {
+    require!(
+        targets.len() == sub_goals.len(),
+        GluXError::SubGoalAccountMismatch
+    );
+    for (offset, (sub_goal, target)) in sub_goals.iter().zip(targets.iter()).enumerate() {
+        let index = first_index + offset as u8;
+        let record = SubGoalAccount::from_sub_goal(goal, index, sub_goal, prerequisites[offset]);
+        init_sub_goal_account(program_id, payer, system_program, target, record)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn init_sub_goal_account<'info>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    target: &AccountInfo<'info>,
+    mut record: SubGoalAccount,
+) -> Result<()> {
+    let index_seed = [record.index];
+    let (expected, bump) = Pubkey::find_program_address(
+        &[b"gluex-subgoal", record.goal.as_ref(), &index_seed],
+        program_id,
+    );
+    require_keys_eq!(expected, target.key(), GluXError::SubGoalAccountMismatch);
+
+    let bump_seed = [bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"gluex-subgoal",
+        record.goal.as_ref(),
+        &index_seed,
+        &bump_seed,
+    ]];
+    create_pda_account(
+        payer,
+        system_program,
+        target,
+        Rent::get()?.minimum_balance(SUB_GOAL_ACCOUNT_SPACE),
+        SUB_GOAL_ACCOUNT_SPACE,
+        program_id,
+        signer_seeds,
+    )?;
+
+    record.bump = bump;
+    let mut data = target.try_borrow_mut_data()?;
+    record.try_serialize(&mut &mut data[..])?;
     Ok(())
 }
 
+// Creates a program-owned PDA holding `lamports`. An address that was already
+// funded (anyone can send lamports to it) would make create_account fail, so
+// in that case the shortfall is topped up and the account allocated and
+// assigned separately.
+pub(crate) fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    target: &AccountInfo<'info>,
+    lamports: u64,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let current = target.lamports();
+    if current == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: target.clone(),
+                },
+                signer_seeds,
+            ),
+            lamports,
+            space as u64,
+            owner,
+        );
+    }
+
+    let shortfall = lamports.saturating_sub(current);
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: target.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate {
+                account_to_allocate: target.clone(),
+            },
+            signer_seeds,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Assign {
+                account_to_assign: target.clone(),
+            },
+            signer_seeds,
+        ),
+        owner,
+    )
+}
+
 #[derive(Accounts)]
 #[instruction(taker: Pubkey, goal_id: i64)]
 pub struct SetupGoal<'info> {
@@ -126,7 +435,7 @@ pub struct SetupGoal<'info> {
         init, payer = payer, space = GOAL_ACCOUNT_SPACE, 
         seeds = [b"gluex-goals", payer.key().as_ref(), taker.as_ref(), goal_id.to_le_bytes().as_ref()], bump
     )]
-    pub goals: Account<'info, TotalGoal>,
+    pub goals: AccountLoader<'info, TotalGoal>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -135,109 +444,186 @@ pub struct SetupGoal<'info> {
     #[account(mut)]
     pub fee_pool: Option<Account<'info, FeePool>>,
 
+    // optional matching pool: if present, matched funds are drawn into the escrow
+    #[account(mut)]
+    pub matching_pool: Option<Account<'info, MatchingPool>>,
+    // required with a matching pool: the payer's allowance for that pool
+    #[account(mut)]
+    pub matching_allowance: Option<Account<'info, MatchingAllowance>>,
+    pub issuer_profile: Option<Account<'info, AgentProfile>>,
+
+    /// CHECK: the payer's UserGoalIndex PDA, empty when they have none; see claim_goal_id
+    #[account(mut, seeds = [b"user-goal-index", payer.key().as_ref()], bump)]
+    pub issuer_index: UncheckedAccount<'info>,
+
+    // optional template: set by setup_goal_from_template, grants its verifier reviews
+    #[account(mut)]
+    pub template: Option<Account<'info, GoalTemplate>>,
+
     pub system_program: Program<'info, System>,
+    // remaining accounts: one SubGoalAccount PDA per subgoal, in index order
 }
 
 #[derive(Accounts)]
-pub struct MigrateTotalGoal<'info> {
-    /// The existing goals account (PDA) to overwrite. Must be owned by this program.
-    /// CHECK: left as AccountInfo because we write raw bytes into it during migration.
+pub struct AddSubGoals<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts: the new SubGoalAccount PDAs, in index order
+}
+
+#[derive(Accounts)]
+pub struct MigrateGoalSubGoals<'info> {
+    /// The legacy goals account (PDA) to rewrite. Must be owned by this program.
+    /// CHECK: left as AccountInfo because the legacy layout no longer decodes as TotalGoal.
     #[account(mut)]
     pub goals: AccountInfo<'info>,
-    #[account(signer)]
-    pub initiator: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts: one SubGoalAccount PDA per legacy subgoal, in index order
 }
 
-pub fn migrate_total_goal(
-    ctx: Context<MigrateTotalGoal>,
-    issuer: Pubkey,
-    taker: Pubkey,
-    goal_id: i64,
-    bump: u8,
+// Moves the inline subgoals of a legacy goal into SubGoalAccount PDAs and
+// rewrites the goal in the current layout. Anyone may pay for the migration;
+// the result only depends on the stored legacy data.
+pub fn migrate_goal_subgoals<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MigrateGoalSubGoals<'info>>,
 ) -> Result<()> {
     let acct_info = &ctx.accounts.goals;
-
-    // Ensure account is owned by this program
     if acct_info.owner != ctx.program_id {
         return err!(GluXError::UnauthorizedSigner);
     }
 
-    // Validate provided bump and seeds derive to the provided account
-    let id_bytes = goal_id.to_le_bytes();
-    let bump_seed = [bump];
+    let legacy = {
+        let data = acct_info.try_borrow_data()?;
+        let legacy_disc = hash(b"account:TotalGoal").to_bytes();
+        require!(
+            data.len() > 8 && data[..8] == legacy_disc[..8],
+            GluXError::LegacyGoalLayoutExpected
+        );
+        LegacyTotalGoal::deserialize(&mut &data[8..])
+            .map_err(|_| error!(GluXError::LegacyGoalLayoutExpected))?
+    };
+
+    let id_bytes = legacy.id.to_le_bytes();
     let expected = Pubkey::create_program_address(
-        &[b"gluex-goals", issuer.as_ref(), taker.as_ref(), &id_bytes, &bump_seed],
+        &[b"gluex-goals", legacy.issuer.as_ref(), legacy.taker.as_ref(), &id_bytes, &[legacy.bump]],
         ctx.program_id,
     )
     .map_err(|_| error!(GluXError::ParsePubkeyError))?;
+    require_keys_eq!(expected, acct_info.key(), GluXError::ParsePubkeyError);
 
-    if expected != *acct_info.key {
-        return err!(GluXError::ParsePubkeyError);
-    }
-
-    // Build a fresh default TotalGoal and write it into the account (preserve lamports)
-    let new_goal = TotalGoal {
-        issuer,
-        taker,
-        id: goal_id,
-        description: String::new(),
-        room: Roomspace::default(),
-        relations: Relations::default(),
-        eventype: EventType::default(),
-        sub_goals: [SubGoal::default(); MAXIUMUN_SUBGOALS],
-        active_sub_goals: 0,
-        total_incentive_amount: 0,
-        deposited_amount: 0,
-        released_amount: 0,
-        completion_time: 0,
-        locked_amount: 0,
-        unlock_time: 0,
-        start_time: 0,
-        surprise_trigger_ts: 0,
-        checkpoint_interval: 0,
-        completed_count: 0,
-        failed: false,
-        version: 1,
-        bump,
-    };
+    let active = (legacy.active_sub_goals as usize).min(LEGACY_SUBGOAL_SLOTS);
+    let sub_goals = &legacy.sub_goals[..active];
+    let settled_mask = sub_goals
+        .iter()
+        .enumerate()
+        .filter(|(_, goal)| !goal.is_active || matches!(goal.status, SubGoalStatus::Paid))
+        .fold(0u64, |mask, (idx, _)| mask | (1u64 << idx));
 
-    // Serialize to bytes
-    let data = new_goal.try_to_vec().map_err(|_| error!(GluXError::None))?;
+    create_sub_goal_accounts(
+        ctx.program_id,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        acct_info.key(),
+        0,
+        sub_goals,
+        &[0; LEGACY_SUBGOAL_SLOTS][..active],
+        ctx.remaining_accounts,
+    )?;
 
-    // Prepend Anchor account discriminator (first 8 bytes of sha256("account:TotalGoal"))
-    let disc_bytes = hash(b"account:TotalGoal").to_bytes();
-    let disc = &disc_bytes[..8];
+    grow_goal_account(
+        acct_info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+    let mut acct_data = acct_info.try_borrow_mut_data()?;
+    let new_goal = zero_copy_goal(&mut acct_data)?;
+    new_goal.issuer = legacy.issuer;
+    new_goal.taker = legacy.taker;
+    new_goal.id = legacy.id;
+    new_goal.set_description(&legacy.description);
+    new_goal.room = legacy.room as u8;
+    new_goal.relations = legacy.relations as u8;
+    new_goal.eventype = legacy.eventype as u8;
+    new_goal.active_sub_goals = active as u8;
+    new_goal.total_incentive_amount = legacy.total_incentive_amount;
+    new_goal.deposited_amount = legacy.deposited_amount;
+    new_goal.released_amount = legacy.released_amount;
+    new_goal.completion_time = legacy.completion_time;
+    new_goal.locked_amount = legacy.locked_amount;
+    new_goal.unlock_time = legacy.unlock_time;
+    new_goal.start_time = legacy.start_time;
+    new_goal.surprise_trigger_ts = legacy.surprise_trigger_ts;
+    new_goal.checkpoint_interval = legacy.checkpoint_interval;
+    new_goal.completed_count = legacy.completed_count;
+    new_goal.failed = legacy.failed as u8;
+    new_goal.version = legacy.version;
+    new_goal.bump = legacy.bump;
+    new_goal.settled_mask = settled_mask;
+    new_goal.allocated_amount = sub_goals
+        .iter()
+        .filter(|goal| goal.is_active && !matches!(goal.status, SubGoalStatus::Paid))
+        .map(|goal| goal.incentive_amount)
+        .sum();
+    new_goal.seed_issuer = legacy.issuer;
+    new_goal.seed_taker = legacy.taker;
+    Ok(())
+}
 
-    // Combined output = discriminator + serialized struct
-    let mut out: Vec<u8> = Vec::with_capacity(disc.len() + data.len());
-    out.extend_from_slice(disc);
-    out.extend_from_slice(&data);
+// Clears `data` and returns it as an empty zero-copy goal for the caller to
+// fill in. The account must already hold GOAL_ACCOUNT_SPACE bytes.
+pub(crate) fn zero_copy_goal(data: &mut [u8]) -> Result<&mut TotalGoal> {
+    require!(data.len() >= GOAL_ACCOUNT_SPACE, GluXError::GoalAccountTooSmall);
+    data.fill(0);
+    data[..8].copy_from_slice(TotalGoal::DISCRIMINATOR);
+    Ok(bytemuck::from_bytes_mut(&mut data[8..GOAL_ACCOUNT_SPACE]))
+}
 
-    // Ensure target account has sufficient data length
-    let acct_len = acct_info.try_borrow_data()?.len();
-    if out.len() > acct_len {
-        return err!(GluXError::NoFundsAvailable);
+// Grows a goal account written with an older, smaller layout to
+// GOAL_ACCOUNT_SPACE, with `payer` covering the extra rent.
+fn grow_goal_account<'info>(
+    goal: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if goal.data_len() >= GOAL_ACCOUNT_SPACE {
+        return Ok(());
     }
-
-    // Write discriminator+serialized bytes into account data, zero remaining bytes
-    let mut acct_data = acct_info.try_borrow_mut_data()?;
-    acct_data[..out.len()].copy_from_slice(&out);
-    for i in out.len()..acct_len {
-        acct_data[i] = 0;
+    let required = Rent::get()?.minimum_balance(GOAL_ACCOUNT_SPACE);
+    let shortfall = required.saturating_sub(goal.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: goal.clone(),
+                },
+            ),
+            shortfall,
+        )?;
     }
-
+    goal.resize(GOAL_ACCOUNT_SPACE)?;
     Ok(())
 }
 
-fn prepare_sub_goals(
+pub(crate) fn prepare_sub_goals(
     eventype: &EventType,
     sub_goals: &[SubGoalInput],
     total_incentive_amount: u64,
     completion_time: i64,
     config: &GoalConfigInput,
     current_time: i64,
-) -> Result<([SubGoal; MAXIUMUN_SUBGOALS], u8, i64, i64, i64)> {
-    let mut storage = [SubGoal::default(); MAXIUMUN_SUBGOALS];
+) -> Result<(Vec<SubGoal>, u8, i64, i64, i64)> {
+    let mut storage: Vec<SubGoal> = Vec::new();
     let mut surprise_ts: i64 = -1;
     let mut checkpoint_interval = config.checkpoint_interval;
     let mut active: u8 = 0;
@@ -256,38 +642,58 @@ fn prepare_sub_goals(
                 HABIT_INTERVAL_SECONDS
             };
             let checkpoints = habit_amounts(total_incentive_amount);
-            for week in 0..HABIT_CHECKPOINTS {
+            for (week, amount) in checkpoints.iter().enumerate() {
                 let title = format!("21d habit checkpoint {}", week + 1);
                 let mut goal = SubGoal::from_input(&SubGoalInput {
                     title,
                     deadline: config.start_time + ((week as i64 + 1) * checkpoint_interval),
-                    incentive_amount: checkpoints[week],
+                    incentive_amount: *amount,
                     auto_release_at: 0,
+                    prerequisites: Vec::new(),
                 });
                 goal.verifier = Pubkey::default();
-                storage[week] = goal;
+                storage.push(goal);
             }
             active = HABIT_CHECKPOINTS as u8;
         }
         EventType::TargetAchieve => {
             require!(!sub_goals.is_empty(), GluXError::MissingSubGoals);
-            for (idx, input) in sub_goals.iter().enumerate().take(MAXIUMUN_SUBGOALS) {
-                storage[idx] = SubGoal::from_input(input);
+            for input in sub_goals.iter().take(MAXIUMUN_SUBGOALS) {
+                storage.push(SubGoal::from_input(input));
                 active += 1;
             }
         }
         EventType::SurpriseTime => {
             require!(config.surprise_time > 0, GluXError::HabitConfigInvalid);
-            surprise_ts = config.surprise_time;
+            // a randomized surprise stays at the window end until the issuer reveals
+            surprise_ts = match config.surprise_window.as_ref() {
+                Some(window) => window.window_end,
+                None => config.surprise_time,
+            };
             start_time = config.surprise_time;
             let mut goal = SubGoal::from_input(&SubGoalInput {
                 title: "Surprise Moment".to_string(),
-                deadline: config.surprise_time,
+                deadline: surprise_ts,
                 incentive_amount: total_incentive_amount,
-                auto_release_at: config.surprise_time,
+                auto_release_at: surprise_ts,
+                prerequisites: Vec::new(),
             });
             goal.is_active = true;
-            storage[0] = goal;
+            storage.push(goal);
+            active = 1;
+        }
+        EventType::Streaming => {
+            // a single subgoal tracks the stream; it is paid out by
+            // withdraw_streamed rather than reviewed
+            require!(completion_time > start_time, GluXError::StreamInvalid);
+            let goal = SubGoal::from_input(&SubGoalInput {
+                title: "Linear stream".to_string(),
+                deadline: completion_time,
+                incentive_amount: total_incentive_amount,
+                auto_release_at: completion_time,
+                prerequisites: Vec::new(),
+            });
+            storage.push(goal);
             active = 1;
         }
     };
@@ -298,8 +704,8 @@ fn prepare_sub_goals(
     Ok((storage, active, start_time, surprise_ts, checkpoint_interval))
 }
 
-fn param_check(
-    ctx: &Context<SetupGoal>,
+pub(crate) fn param_check(
+    payer: &AccountInfo,
     description: &String,
     eventype: &EventType,
     sub_goals: &[SubGoalInput], 
@@ -310,9 +716,9 @@ fn param_check(
     config: &GoalConfigInput,
 ) -> Result<()> {
     let clock = Clock::get()?.unix_timestamp;
-    require!(description.len() <= MAXIUMUN_DESCRIPTIONS_LENS, GluXError::DescExceedMaxChars);
+    require!(description.len() <= MAX_DESCRIPTION_BYTES, GluXError::DescExceedMaxChars);
     require!(total_incentive_amount > 0, GluXError::PayerAccountInsufficient);
-    require!(total_incentive_amount <= ctx.accounts.payer.lamports(), GluXError::PayerAccountInsufficient);
+    require!(total_incentive_amount <= payer.lamports(), GluXError::PayerAccountInsufficient);
     require!(locked_amount <= total_incentive_amount, GluXError::LockedAmountInvalid);
     require!(unlock_time >= completion_time, GluXError::UnLockedTimeInvalid);
     require!(completion_time >= clock, GluXError::HabitConfigInvalid);
@@ -330,7 +736,33 @@ fn param_check(
         }
         EventType::SurpriseTime => {
             require!(config.surprise_time > clock, GluXError::HabitConfigInvalid);
+            if let Some(window) = config.surprise_window.as_ref() {
+                require!(window.window_end > config.surprise_time, GluXError::SurpriseWindowInvalid);
+                require!(completion_time >= window.window_end, GluXError::SurpriseWindowInvalid);
+                require!(window.commitment != [0u8; 32], GluXError::SurpriseWindowInvalid);
+            }
+        }
+        EventType::Streaming => {
+            require!(sub_goals.is_empty(), GluXError::StreamInvalid);
+            require!(config.start_time >= 0, GluXError::StreamInvalid);
         }
     };
+
+    if let Some(penalty) = config.late_penalty.as_ref() {
+        require!(penalty.validate(), GluXError::LatePenaltyInvalid);
+    }
+
+    if let Some(switch) = config.dead_man_switch.as_ref() {
+        require!(switch.validate(), GluXError::DeadManSwitchInvalid);
+    }
+
+    if let Some(message) = config.sealed_message.as_ref() {
+        require!(
+            matches!(eventype, EventType::SurpriseTime),
+            GluXError::SealedMessageInvalid
+        );
+        require!(message.uri.len() <= MAX_PROOF_URI_LENGTH, GluXError::SealedMessageInvalid);
+        require!(message.commitment != [0u8; 32], GluXError::SealedMessageInvalid);
+    }
     Ok(())
 }
\ No newline at end of file
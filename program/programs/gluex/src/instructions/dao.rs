@@ -0,0 +1,458 @@
+use super::create::create_pda_account;
+use super::manage::{release_sub_goal, Payees};
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+
+#[derive(Accounts)]
+pub struct ConfigureDaoGovernance<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        init,
+        payer = issuer,
+        space = DAO_GOVERNANCE_SPACE,
+        seeds = [b"dao-governance", goals.key().as_ref()],
+        bump
+    )]
+    pub governance: Account<'info, DaoGovernance>,
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Hands subgoal approval of a Relations::Dao goal over to member votes. The
+// issuer can no longer review once governance is attached.
+pub fn configure_dao_governance(
+    ctx: Context<ConfigureDaoGovernance>,
+    input: DaoGovernanceInput,
+) -> Result<()> {
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    require_keys_eq!(
+        goals.issuer,
+        ctx.accounts.issuer.key(),
+        GluXError::UnauthorizedSigner
+    );
+    require!(
+        matches!(goals.relations(), Relations::Dao)
+            && !matches!(goals.eventype(), EventType::Streaming)
+            && goals.failed == 0
+            && goals.refund_started == 0,
+        GluXError::DaoConfigInvalid
+    );
+    require!(input.validate(), GluXError::DaoConfigInvalid);
+
+    let governance = &mut ctx.accounts.governance;
+    governance.goal = ctx.accounts.goals.key();
+    governance.mode = input.mode;
+    governance.membership_mint = input.membership_mint;
+    governance.members = input.members;
+    governance.quorum = input.quorum;
+    governance.approval_bps = input.approval_bps;
+    governance.voting_period = input.voting_period;
+    governance.bump = ctx.bumps.governance;
+    goals.dao_governed = 1;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(subgoal_index: u8)]
+pub struct CastDaoVote<'info> {
+    #[account(
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        seeds = [b"gluex-subgoal", goals.key().as_ref(), &[subgoal_index]],
+        bump = sub_goal.bump
+    )]
+    pub sub_goal: Account<'info, SubGoalAccount>,
+    #[account(
+        seeds = [b"dao-governance", goals.key().as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, DaoGovernance>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = DAO_TALLY_SPACE,
+        seeds = [b"dao-tally", sub_goal.key().as_ref()],
+        bump
+    )]
+    pub tally: Account<'info, DaoTally>,
+    #[account(
+        init,
+        payer = voter,
+        space = DAO_VOTE_RECORD_SPACE,
+        seeds = [b"dao-vote", sub_goal.key().as_ref(), voter.key().as_ref(), sub_goal.submitted_at.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, DaoVoteRecord>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    /// CHECK: TokenWeighted only: the voter's SPL token account for the
+    /// membership mint, owner checked and read as raw bytes
+    #[account(mut, owner = SPL_TOKEN_PROGRAM_ID)]
+    pub voter_token: Option<AccountInfo<'info>>,
+    /// CHECK: TokenWeighted only: the governance's membership mint
+    #[account(address = governance.membership_mint)]
+    pub membership_mint: Option<AccountInfo<'info>>,
+    /// CHECK: TokenWeighted only: token account created here at
+    /// [b"dao-vote-escrow", vote_record] that holds the voter's tokens
+    #[account(
+        mut,
+        seeds = [b"dao-vote-escrow", vote_record.key().as_ref()],
+        bump
+    )]
+    pub vote_escrow: Option<AccountInfo<'info>>,
+    /// CHECK: TokenWeighted only: the SPL token program
+    #[account(address = SPL_TOKEN_PROGRAM_ID)]
+    pub token_program: Option<AccountInfo<'info>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Votes on the proof currently submitted for a subgoal. Token-weighted votes
+// move the voter's whole balance into an escrow until the round closes, so
+// the same tokens cannot be moved to another wallet and counted again.
+pub fn cast_dao_vote(ctx: Context<CastDaoVote>, _subgoal_index: u8, approve: bool) -> Result<()> {
+    let sub_goal = &ctx.accounts.sub_goal;
+    require!(
+        matches!(sub_goal.status, SubGoalStatus::ProofSubmitted),
+        GluXError::ProofMissing
+    );
+    let governance = &ctx.accounts.governance;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now < sub_goal.submitted_at.saturating_add(governance.voting_period),
+        GluXError::DaoVotingClosed
+    );
+
+    let voter = ctx.accounts.voter.key();
+    let weight = match governance.mode {
+        DaoVoteMode::MemberList => {
+            require!(
+                governance.members.contains(&voter),
+                GluXError::DaoVoterIneligible
+            );
+            1
+        }
+        DaoVoteMode::TokenWeighted => {
+            let token = ctx
+                .accounts
+                .voter_token
+                .as_ref()
+                .ok_or(GluXError::DaoVoterIneligible)?;
+            let (mint, owner, amount) = token_balance(token)?;
+            require!(
+                mint == governance.membership_mint && owner == voter && amount > 0,
+                GluXError::DaoVoterIneligible
+            );
+            escrow_vote_tokens(&ctx, amount)?;
+            amount
+        }
+    };
+
+    let tally = &mut ctx.accounts.tally;
+    if tally.sub_goal == Pubkey::default() || tally.round != sub_goal.submitted_at {
+        tally.sub_goal = sub_goal.key();
+        tally.round = sub_goal.submitted_at;
+        tally.yes_weight = 0;
+        tally.no_weight = 0;
+        tally.voters = 0;
+        tally.bump = ctx.bumps.tally;
+    }
+    if approve {
+        tally.yes_weight = tally.yes_weight.saturating_add(weight);
+    } else {
+        tally.no_weight = tally.no_weight.saturating_add(weight);
+    }
+    tally.voters = tally.voters.saturating_add(1);
+
+    let record = &mut ctx.accounts.vote_record;
+    record.sub_goal = sub_goal.key();
+    record.voter = voter;
+    record.round = sub_goal.submitted_at;
+    record.approve = approve;
+    record.weight = weight;
+    record.bump = ctx.bumps.vote_record;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(subgoal_index: u8)]
+pub struct FinalizeDaoVote<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        mut,
+        seeds = [b"gluex-subgoal", goals.key().as_ref(), &[subgoal_index]],
+        bump = sub_goal.bump
+    )]
+    pub sub_goal: Account<'info, SubGoalAccount>,
+    #[account(
+        seeds = [b"dao-governance", goals.key().as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, DaoGovernance>,
+    /// CHECK: the subgoal's tally PDA, read in finalize_dao_vote; it holds
+    /// no data until the first vote is cast
+    #[account(
+        seeds = [b"dao-tally", sub_goal.key().as_ref()],
+        bump
+    )]
+    pub tally: UncheckedAccount<'info>,
+    /// CHECK: destination validated via key comparison
+    #[account(mut)]
+    pub taker_account: AccountInfo<'info>,
+    // required when the goal splits payouts
+    #[account(
+        seeds = [b"goal-beneficiaries", goals.key().as_ref()],
+        bump = beneficiaries.bump
+    )]
+    pub beneficiaries: Option<Account<'info, GoalBeneficiaries>>,
+    // remaining accounts: the beneficiary wallets of the subgoal's split, in
+    // stored order
+}
+
+// Permissionless once the voting window ends. An approved proof pays out
+// like an issuer approval; otherwise the subgoal is Rejected and the taker
+// may submit a new proof, which opens a new round.
+pub fn finalize_dao_vote<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FinalizeDaoVote<'info>>,
+    subgoal_index: u8,
+) -> Result<()> {
+    let goal_info = ctx.accounts.goals.to_account_info();
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    require_keys_eq!(
+        goals.taker,
+        ctx.accounts.taker_account.key(),
+        GluXError::UnauthorizedTaker
+    );
+    let sub_goal = &mut ctx.accounts.sub_goal;
+    require!(sub_goal.is_active, GluXError::SubGoalIndexOutOfBounds);
+    require!(
+        matches!(sub_goal.status, SubGoalStatus::ProofSubmitted),
+        GluXError::ProofMissing
+    );
+    let governance = &ctx.accounts.governance;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= sub_goal.submitted_at.saturating_add(governance.voting_period),
+        GluXError::DaoVotingOpen
+    );
+
+    // an empty tally, or one left from an earlier round, means nobody voted
+    let tally_info = &ctx.accounts.tally;
+    let tally = if tally_info.data_is_empty() {
+        None
+    } else {
+        require_keys_eq!(*tally_info.owner, crate::ID, GluXError::DaoVoterIneligible);
+        Some(DaoTally::try_deserialize(&mut &tally_info.try_borrow_data()?[..])?)
+    };
+    let approved = tally
+        .filter(|tally| tally.round == sub_goal.submitted_at)
+        .is_some_and(|tally| tally.approved(governance));
+    if !approved {
+        sub_goal.status = SubGoalStatus::Rejected;
+        return Ok(());
+    }
+
+    require!(
+        goals.prerequisites_met(sub_goal),
+        GluXError::SubGoalPrerequisitesPending
+    );
+    let taker_info = ctx.accounts.taker_account.to_account_info();
+    let payees = Payees {
+        taker: &taker_info,
+        beneficiaries: ctx.accounts.beneficiaries.as_deref(),
+        wallets: ctx.remaining_accounts,
+    };
+    release_sub_goal(&mut goals, &goal_info, sub_goal, &payees, subgoal_index)
+}
+
+// Creates the vote's escrow token account, owned by the vote record PDA, and
+// moves `amount` of the voter's tokens into it.
+fn escrow_vote_tokens(ctx: &Context<CastDaoVote>, amount: u64) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let (Some(voter_token), Some(mint), Some(escrow), Some(token_program)) = (
+        accounts.voter_token.as_ref(),
+        accounts.membership_mint.as_ref(),
+        accounts.vote_escrow.as_ref(),
+        accounts.token_program.as_ref(),
+    ) else {
+        return err!(GluXError::DaoVoterIneligible);
+    };
+
+    let record_key = accounts.vote_record.key();
+    let bump_seed = [ctx.bumps.vote_escrow.ok_or(GluXError::DaoVoterIneligible)?];
+    let signer_seeds: &[&[&[u8]]] = &[&[b"dao-vote-escrow", record_key.as_ref(), &bump_seed]];
+    create_pda_account(
+        &accounts.voter.to_account_info(),
+        &accounts.system_program.to_account_info(),
+        escrow,
+        Rent::get()?.minimum_balance(SPL_TOKEN_ACCOUNT_LEN),
+        SPL_TOKEN_ACCOUNT_LEN,
+        &SPL_TOKEN_PROGRAM_ID,
+        signer_seeds,
+    )?;
+
+    // InitializeAccount3 { owner }
+    let mut data = vec![18u8];
+    data.extend_from_slice(record_key.as_ref());
+    invoke(
+        &Instruction {
+            program_id: SPL_TOKEN_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(escrow.key(), false),
+                AccountMeta::new_readonly(mint.key(), false),
+            ],
+            data,
+        },
+        &[escrow.clone(), mint.clone(), token_program.clone()],
+    )?;
+
+    invoke(
+        &token_transfer(voter_token.key(), escrow.key(), accounts.voter.key(), amount),
+        &[
+            voter_token.clone(),
+            escrow.clone(),
+            accounts.voter.to_account_info(),
+            token_program.clone(),
+        ],
+    )?;
+    Ok(())
+}
+
+fn token_transfer(source: Pubkey, destination: Pubkey, authority: Pubkey, amount: u64) -> Instruction {
+    // Transfer { amount }
+    let mut data = vec![3u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+    Instruction {
+        program_id: SPL_TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(source, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data,
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawDaoVoteTokens<'info> {
+    #[account(
+        seeds = [b"dao-governance", sub_goal.goal.as_ref()],
+        bump = governance.bump,
+        constraint = governance.goal == sub_goal.goal @ GluXError::DaoVoterIneligible
+    )]
+    pub governance: Account<'info, DaoGovernance>,
+    #[account(
+        seeds = [b"gluex-subgoal", sub_goal.goal.as_ref(), &[sub_goal.index]],
+        bump = sub_goal.bump
+    )]
+    pub sub_goal: Account<'info, SubGoalAccount>,
+    #[account(
+        seeds = [b"dao-vote", vote_record.sub_goal.as_ref(), voter.key().as_ref(), vote_record.round.to_le_bytes().as_ref()],
+        bump = vote_record.bump,
+        constraint = vote_record.sub_goal == sub_goal.key() @ GluXError::DaoVoterIneligible
+    )]
+    pub vote_record: Account<'info, DaoVoteRecord>,
+    /// CHECK: token account created by cast_dao_vote, closed here
+    #[account(
+        mut,
+        seeds = [b"dao-vote-escrow", vote_record.key().as_ref()],
+        bump,
+        owner = SPL_TOKEN_PROGRAM_ID
+    )]
+    pub vote_escrow: AccountInfo<'info>,
+    /// CHECK: any token account of the membership mint; the token program
+    /// rejects a mismatched mint
+    #[account(mut, owner = SPL_TOKEN_PROGRAM_ID)]
+    pub voter_token: AccountInfo<'info>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    /// CHECK: the SPL token program
+    #[account(address = SPL_TOKEN_PROGRAM_ID)]
+    pub token_program: AccountInfo<'info>,
+}
+
+// Returns escrowed vote tokens once the voting window of their round has
+// closed, and closes the escrow account back to the voter.
+pub fn withdraw_dao_vote_tokens(ctx: Context<WithdrawDaoVoteTokens>) -> Result<()> {
+    let record = &ctx.accounts.vote_record;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= record.round.saturating_add(ctx.accounts.governance.voting_period),
+        GluXError::DaoVotingOpen
+    );
+
+    let voter = ctx.accounts.voter.key();
+    let round_bytes = record.round.to_le_bytes();
+    let bump_seed = [record.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"dao-vote",
+        record.sub_goal.as_ref(),
+        voter.as_ref(),
+        &round_bytes,
+        &bump_seed,
+    ]];
+    let escrow = &ctx.accounts.vote_escrow;
+    let record_info = record.to_account_info();
+    let token_program = &ctx.accounts.token_program;
+
+    invoke_signed(
+        &token_transfer(escrow.key(), ctx.accounts.voter_token.key(), record.key(), record.weight),
+        &[
+            escrow.clone(),
+            ctx.accounts.voter_token.clone(),
+            record_info.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    // CloseAccount: the rent goes back to the voter who paid it
+    invoke_signed(
+        &Instruction {
+            program_id: SPL_TOKEN_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(escrow.key(), false),
+                AccountMeta::new(voter, false),
+                AccountMeta::new_readonly(record.key(), true),
+            ],
+            data: vec![9u8],
+        },
+        &[
+            escrow.clone(),
+            ctx.accounts.voter.to_account_info(),
+            record_info,
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+// SPL token account layout: mint (32), owner (32), amount (u64 le), ...
+fn token_balance(token: &AccountInfo) -> Result<(Pubkey, Pubkey, u64)> {
+    let data = token.try_borrow_data()?;
+    require!(
+        data.len() == SPL_TOKEN_ACCOUNT_LEN,
+        GluXError::DaoVoterIneligible
+    );
+    let mint = Pubkey::try_from(&data[0..32]).map_err(|_| GluXError::DaoVoterIneligible)?;
+    let owner = Pubkey::try_from(&data[32..64]).map_err(|_| GluXError::DaoVoterIneligible)?;
+    let mut amount = [0u8; 8];
+    amount.copy_from_slice(&data[64..72]);
+    Ok((mint, owner, u64::from_le_bytes(amount)))
+}
@@ -0,0 +1,56 @@
+use super::manage::{payout_from_goal, take_unused};
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct TriggerDeadManSwitch<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    /// CHECK: validated against the fallback recipient configured at setup
+    #[account(mut)]
+    pub fallback: AccountInfo<'info>,
+    // anyone; receives the keeper tip
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+}
+
+// Releases what the issuer left unclaimed once `unlock_time + grace_period`
+// has passed. The issuer can still claim_unused until someone triggers this.
+pub fn trigger_dead_man_switch(ctx: Context<TriggerDeadManSwitch>) -> Result<()> {
+    let goal_info = ctx.accounts.goals.to_account_info();
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    let switch = goals.dead_man_switch;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        goals.has_dead_man_switch()
+            && now >= goals.unlock_time.saturating_add(switch.grace_period),
+        GluXError::DeadManSwitchNotReady
+    );
+
+    let fallback = &ctx.accounts.fallback;
+    let recipient = FallbackRecipient::from_u8(switch.recipient);
+    let expected = match recipient {
+        FallbackRecipient::Taker => goals.taker,
+        FallbackRecipient::Wallet | FallbackRecipient::FeePool => switch.wallet,
+    };
+    require_keys_eq!(expected, fallback.key(), GluXError::DeadManSwitchInvalid);
+    if matches!(recipient, FallbackRecipient::FeePool) {
+        require_keys_eq!(*fallback.owner, crate::ID, GluXError::DeadManSwitchInvalid);
+        FeePool::try_deserialize(&mut &fallback.try_borrow_data()?[..])?;
+    }
+
+    let amount = take_unused(&mut goals, now)?;
+    let tip = ((amount as u128) * (switch.keeper_tip_bps as u128) / (BASIS_POINTS as u128)) as u64;
+    if tip > 0 {
+        payout_from_goal(&goal_info, ctx.accounts.keeper.to_account_info(), tip)?;
+    }
+    let released = amount.saturating_sub(tip);
+    if released > 0 {
+        payout_from_goal(&goal_info, fallback.to_account_info(), released)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use super::manage::{book_unused, payout_from_goal};
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+    #[account(mut)]
+    pub taker: Signer<'info>,
+}
+
+// Escape hatch when reviews are stuck off-chain: with both signatures the
+// goal ends immediately, ignoring deadlines and unlock_time. `taker_amount`
+// of the remaining funds goes to the taker and the rest back to the issuer.
+// Sponsors keep their pro-rata share and may claim it right away.
+pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, taker_amount: u64) -> Result<()> {
+    let goal_info = ctx.accounts.goals.to_account_info();
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    require_keys_eq!(
+        goals.issuer,
+        ctx.accounts.issuer.key(),
+        GluXError::UnauthorizedSigner
+    );
+    require_keys_eq!(
+        goals.taker,
+        ctx.accounts.taker.key(),
+        GluXError::UnauthorizedTaker
+    );
+    require!(!goals.is_emergency_exited(), GluXError::GoalEmergencyExited);
+
+    // both parties sign, so a stream's unwithdrawn funds are part of the split
+    goals.allocated_amount = 0;
+    let now = Clock::get()?.unix_timestamp;
+    goals.start_refund(now);
+    // an empty goal still exits, so the terminal status is recorded
+    let remaining = book_unused(&mut goals, now);
+    require!(taker_amount <= remaining, GluXError::EmergencySplitInvalid);
+    goals.emergency_exited = 1;
+
+    if taker_amount > 0 {
+        payout_from_goal(&goal_info, ctx.accounts.taker.to_account_info(), taker_amount)?;
+    }
+    let issuer_amount = remaining.saturating_sub(taker_amount);
+    if issuer_amount > 0 {
+        payout_from_goal(&goal_info, ctx.accounts.issuer.to_account_info(), issuer_amount)?;
+    }
+    Ok(())
+}
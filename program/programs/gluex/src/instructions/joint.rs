@@ -0,0 +1,224 @@
+use super::manage::payout_from_goal;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+#[instruction(savings_id: u64)]
+pub struct CreateJointSavings<'info> {
+    #[account(
+        init,
+        payer = initiator,
+        space = JOINT_SAVINGS_SPACE,
+        seeds = [b"joint-savings", initiator.key().as_ref(), partner.key().as_ref(), savings_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub savings: Account<'info, JointSavings>,
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+    pub partner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Both partners sign, so the target and the destination are agreed jointly.
+pub fn create_joint_savings(
+    ctx: Context<CreateJointSavings>,
+    savings_id: u64,
+    input: JointSavingsInput,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.initiator.key() != ctx.accounts.partner.key() && input.validate(now),
+        GluXError::JointSavingsInvalid
+    );
+
+    let savings = &mut ctx.accounts.savings;
+    savings.initiator = ctx.accounts.initiator.key();
+    savings.partner = ctx.accounts.partner.key();
+    savings.savings_id = savings_id;
+    savings.relations = input.relations;
+    savings.destination = input.destination;
+    savings.target_amount = input.target_amount;
+    savings.target_date = input.target_date;
+    savings.contribution_amount = input.contribution_amount;
+    savings.period = input.period;
+    savings.start_time = input.start_time;
+    savings.contributed = [0; 2];
+    savings.bump = ctx.bumps.savings;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ContributeJointSavings<'info> {
+    #[account(
+        mut,
+        seeds = [b"joint-savings", savings.initiator.as_ref(), savings.partner.as_ref(), savings.savings_id.to_le_bytes().as_ref()],
+        bump = savings.bump
+    )]
+    pub savings: Account<'info, JointSavings>,
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Deposits are credited to the contributing partner. A partner who is
+// behind the schedule must catch up in full with this deposit; saving ahead
+// of the schedule is always allowed.
+pub fn contribute_joint_savings(ctx: Context<ContributeJointSavings>, amount: u64) -> Result<()> {
+    let savings = &mut ctx.accounts.savings;
+    let index = savings
+        .partner_index(&ctx.accounts.contributor.key())
+        .ok_or(GluXError::JointSavingsNotPartner)?;
+    require!(amount > 0, GluXError::PayerAccountInsufficient);
+    let now = Clock::get()?.unix_timestamp;
+    require!(now < savings.target_date, GluXError::JointSavingsClosed);
+    require!(
+        amount >= savings.shortfall(index, now),
+        GluXError::JointContributionBelowSchedule
+    );
+
+    savings.contributed[index] = savings.contributed[index].saturating_add(amount);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.contributor.to_account_info(),
+                to: savings.to_account_info(),
+            },
+        ),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+pub struct UpdateJointDestination<'info> {
+    #[account(
+        mut,
+        has_one = initiator,
+        has_one = partner,
+        seeds = [b"joint-savings", initiator.key().as_ref(), partner.key().as_ref(), savings.savings_id.to_le_bytes().as_ref()],
+        bump = savings.bump
+    )]
+    pub savings: Account<'info, JointSavings>,
+    pub initiator: Signer<'info>,
+    pub partner: Signer<'info>,
+}
+
+pub fn update_joint_destination(
+    ctx: Context<UpdateJointDestination>,
+    destination: Pubkey,
+) -> Result<()> {
+    require!(
+        destination != Pubkey::default(),
+        GluXError::JointSavingsInvalid
+    );
+    ctx.accounts.savings.destination = destination;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReleaseJointSavings<'info> {
+    #[account(
+        mut,
+        close = initiator,
+        has_one = initiator,
+        has_one = destination,
+        seeds = [b"joint-savings", initiator.key().as_ref(), savings.partner.as_ref(), savings.savings_id.to_le_bytes().as_ref()],
+        bump = savings.bump
+    )]
+    pub savings: Account<'info, JointSavings>,
+    /// CHECK: receives the savings, matched by has_one
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+    /// CHECK: receives the account rent, matched by has_one
+    #[account(mut)]
+    pub initiator: AccountInfo<'info>,
+}
+
+// Permissionless once the target is met: sends everything saved to the
+// agreed destination.
+pub fn release_joint_savings(ctx: Context<ReleaseJointSavings>) -> Result<()> {
+    let savings = &ctx.accounts.savings;
+    require!(savings.is_reached(), GluXError::JointSavingsTargetPending);
+    payout_from_goal(
+        &savings.to_account_info(),
+        ctx.accounts.destination.to_account_info(),
+        savings.total(),
+    )
+}
+
+#[derive(Accounts)]
+pub struct WithdrawJointSavings<'info> {
+    #[account(
+        mut,
+        close = initiator,
+        has_one = initiator,
+        has_one = partner,
+        seeds = [b"joint-savings", initiator.key().as_ref(), partner.key().as_ref(), savings.savings_id.to_le_bytes().as_ref()],
+        bump = savings.bump
+    )]
+    pub savings: Account<'info, JointSavings>,
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+    #[account(mut)]
+    pub partner: Signer<'info>,
+}
+
+// Early exit agreed by both partners; each gets back what they deposited.
+pub fn withdraw_joint_savings(ctx: Context<WithdrawJointSavings>) -> Result<()> {
+    refund_partners(
+        &ctx.accounts.savings,
+        ctx.accounts.initiator.to_account_info(),
+        ctx.accounts.partner.to_account_info(),
+    )
+}
+
+#[derive(Accounts)]
+pub struct RefundJointSavings<'info> {
+    #[account(
+        mut,
+        close = initiator,
+        has_one = initiator,
+        has_one = partner,
+        seeds = [b"joint-savings", initiator.key().as_ref(), partner.key().as_ref(), savings.savings_id.to_le_bytes().as_ref()],
+        bump = savings.bump
+    )]
+    pub savings: Account<'info, JointSavings>,
+    /// CHECK: refunded, matched by has_one
+    #[account(mut)]
+    pub initiator: AccountInfo<'info>,
+    /// CHECK: refunded, matched by has_one
+    #[account(mut)]
+    pub partner: AccountInfo<'info>,
+}
+
+// Permissionless once the target date passes without the target being met.
+pub fn refund_joint_savings(ctx: Context<RefundJointSavings>) -> Result<()> {
+    let savings = &ctx.accounts.savings;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= savings.target_date && !savings.is_reached(),
+        GluXError::JointSavingsClosed
+    );
+    refund_partners(
+        savings,
+        ctx.accounts.initiator.clone(),
+        ctx.accounts.partner.clone(),
+    )
+}
+
+fn refund_partners<'info>(
+    savings: &Account<'info, JointSavings>,
+    initiator: AccountInfo<'info>,
+    partner: AccountInfo<'info>,
+) -> Result<()> {
+    let savings_info = savings.to_account_info();
+    for (index, wallet) in [initiator, partner].into_iter().enumerate() {
+        let refund = savings.contributed[index];
+        if refund > 0 {
+            payout_from_goal(&savings_info, wallet, refund)?;
+        }
+    }
+    Ok(())
+}
@@ -1,21 +1,40 @@
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::clock::Clock;
+use solana_program::hash::hash;
 
 pub fn submit_proof(ctx: Context<SubmitProof>, subgoal_index: u8, proof_uri: String) -> Result<()> {
-    let goals = &mut ctx.accounts.goals;
+    let goals = ctx.accounts.goals.load()?;
     require_keys_eq!(
         goals.taker,
         ctx.accounts.taker.key(),
         GluXError::UnauthorizedTaker
     );
+    require!(!goals.is_emergency_exited(), GluXError::GoalEmergencyExited);
+    // streamed incentives vest over time, see withdraw_streamed
+    require!(
+        !matches!(goals.eventype(), EventType::Streaming),
+        GluXError::EventTypeNotSupport
+    );
     let index = subgoal_index as usize;
     require!(
         index < goals.active_sub_goals as usize,
         GluXError::SubGoalIndexOutOfBounds
     );
 
-    let goal = &mut goals.sub_goals[index];
+    require!(
+        goals.prerequisites_met(&ctx.accounts.sub_goal),
+        GluXError::SubGoalPrerequisitesPending
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let grace_period = if goals.has_late_penalty() {
+        Some(goals.late_penalty.grace_period)
+    } else {
+        None
+    };
+
+    let goal = &mut ctx.accounts.sub_goal;
     require!(goal.is_active, GluXError::SubGoalIndexOutOfBounds);
 
     match goal.status {
@@ -26,24 +45,65 @@ pub fn submit_proof(ctx: Context<SubmitProof>, subgoal_index: u8, proof_uri: Str
         }
     };
 
+    if let Some(grace_period) = grace_period {
+        require!(
+            goal.deadline <= 0 || now <= goal.deadline.saturating_add(grace_period),
+            GluXError::SubmissionWindowClosed
+        );
+    }
+
     goal.proof_uri = string_to_fixed(&proof_uri);
     goal.status = SubGoalStatus::ProofSubmitted;
-    goal.submitted_at = Clock::get()?.unix_timestamp;
+    goal.submitted_at = now;
     Ok(())
 }
 
-pub fn review_subgoal(ctx: Context<ReviewSubGoal>, subgoal_index: u8, approve: bool) -> Result<()> {
-    let goals = &mut ctx.accounts.goals;
-    require_keys_eq!(
-        goals.issuer,
+pub fn review_subgoal<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ReviewSubGoal<'info>>,
+    subgoal_index: u8,
+    approve: bool,
+) -> Result<()> {
+    let goal_info = ctx.accounts.goals.to_account_info();
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    let taker_info = ctx.accounts.taker_account.to_account_info();
+    let payees = Payees {
+        taker: &taker_info,
+        beneficiaries: ctx.accounts.beneficiaries.as_deref(),
+        wallets: ctx.remaining_accounts,
+    };
+    apply_review(
+        &mut goals,
+        &goal_info,
+        &mut ctx.accounts.sub_goal,
         ctx.accounts.issuer.key(),
+        &payees,
+        subgoal_index,
+        approve,
+    )
+}
+
+// Review logic shared by review_subgoal and batch_review_subgoals. The caller
+// has already matched `sub_goal` to the goal and index.
+pub(crate) fn apply_review(
+    goals: &mut TotalGoal,
+    goal_info: &AccountInfo,
+    sub_goal: &mut SubGoalAccount,
+    reviewer: Pubkey,
+    payees: &Payees,
+    subgoal_index: u8,
+    approve: bool,
+) -> Result<()> {
+    let verifier = sub_goal.verifier;
+    require!(
+        reviewer == goals.issuer || (verifier != Pubkey::default() && reviewer == verifier),
         GluXError::UnauthorizedSigner
     );
-    require_keys_eq!(
-        goals.taker,
-        ctx.accounts.taker_account.key(),
-        GluXError::UnauthorizedTaker
+    require_keys_eq!(goals.taker, payees.taker.key(), GluXError::UnauthorizedTaker);
+    require!(
+        !matches!(goals.eventype(), EventType::Streaming),
+        GluXError::EventTypeNotSupport
     );
+    require!(!goals.is_dao_governed(), GluXError::DaoGoverned);
 
     let index = subgoal_index as usize;
     require!(
@@ -51,58 +111,98 @@ pub fn review_subgoal(ctx: Context<ReviewSubGoal>, subgoal_index: u8, approve: b
         GluXError::SubGoalIndexOutOfBounds
     );
 
-    let incentive_amount = {
-        let goal = &mut goals.sub_goals[index];
-        require!(goal.is_active, GluXError::SubGoalIndexOutOfBounds);
+    let prerequisites_met = goals.prerequisites_met(sub_goal);
+    require!(sub_goal.is_active, GluXError::SubGoalIndexOutOfBounds);
 
-        if !approve {
-            goal.status = SubGoalStatus::Rejected;
-            return Ok(());
-        }
+    if !approve {
+        sub_goal.status = SubGoalStatus::Rejected;
+        return Ok(());
+    }
 
-        require!(
-            matches!(
-                goal.status,
-                SubGoalStatus::ProofSubmitted | SubGoalStatus::Pending
-            ),
-            GluXError::ProofMissing
-        );
+    require!(prerequisites_met, GluXError::SubGoalPrerequisitesPending);
 
-        goal.status = SubGoalStatus::Approved;
-        goal.incentive_amount
+    require!(
+        matches!(
+            sub_goal.status,
+            SubGoalStatus::ProofSubmitted | SubGoalStatus::Pending
+        ),
+        GluXError::ProofMissing
+    );
+    // matched funds are only released against a proof the taker submitted
+    require!(
+        goals.matched_amount == 0 || matches!(sub_goal.status, SubGoalStatus::ProofSubmitted),
+        GluXError::ProofMissing
+    );
+
+    release_sub_goal(goals, goal_info, sub_goal, payees, subgoal_index)
+}
+
+// Pays out an approved subgoal, applying the late penalty if configured.
+// Shared by issuer reviews and DAO votes.
+pub(crate) fn release_sub_goal(
+    goals: &mut TotalGoal,
+    goal_info: &AccountInfo,
+    sub_goal: &mut SubGoalAccount,
+    payees: &Payees,
+    subgoal_index: u8,
+) -> Result<()> {
+    require!(!goals.is_emergency_exited(), GluXError::GoalEmergencyExited);
+    // the refund pool was snapshotted from the unspent balance
+    require!(goals.refund_started == 0, GluXError::GoalRefundStarted);
+    sub_goal.status = SubGoalStatus::Approved;
+    let incentive_amount = sub_goal.incentive_amount;
+
+    // late proofs pay a decayed share, the rest stays reclaimable by the issuer
+    let payout_amount = if goals.has_late_penalty() {
+        let lateness = if sub_goal.submitted_at > 0 {
+            sub_goal.submitted_at.saturating_sub(sub_goal.deadline)
+        } else {
+            0
+        };
+        LatePenalty::from(goals.late_penalty).apply(incentive_amount, lateness)
+    } else {
+        incentive_amount
     };
+    let withheld = incentive_amount.saturating_sub(payout_amount);
 
-    payout_to_taker(
-        goals,
-        ctx.accounts.taker_account.to_account_info(),
-        incentive_amount,
-    )?;
+    if payout_amount > 0 {
+        payees.pay(goals, goal_info, subgoal_index, payout_amount)?;
+    }
 
-    goals.sub_goals[index].status = SubGoalStatus::Paid;
-    goals.released_amount = goals.released_amount.saturating_add(incentive_amount);
+    sub_goal.status = SubGoalStatus::Paid;
+    goals.settle_sub_goal(subgoal_index, incentive_amount);
+    goals.released_amount = goals.released_amount.saturating_add(payout_amount);
+    goals.penalty_withheld = goals.penalty_withheld.saturating_add(withheld);
     goals.completed_count = goals.completed_count.saturating_add(1);
     Ok(())
 }
 
-pub fn trigger_surprise(ctx: Context<TriggerSurprise>) -> Result<()> {
-    let goals = &mut ctx.accounts.goals;
+pub fn trigger_surprise<'info>(
+    ctx: Context<'_, '_, 'info, 'info, TriggerSurprise<'info>>,
+    message_key: Option<Vec<u8>>,
+) -> Result<()> {
+    let goal_info = ctx.accounts.goals.to_account_info();
+    let mut goals = ctx.accounts.goals.load_mut()?;
     require_keys_eq!(
         goals.taker,
         ctx.accounts.taker_account.key(),
         GluXError::UnauthorizedTaker
     );
+    require!(!goals.is_emergency_exited(), GluXError::GoalEmergencyExited);
+    require!(goals.refund_started == 0, GluXError::GoalRefundStarted);
     let now = Clock::get()?.unix_timestamp;
     require!(
-        matches!(goals.eventype, EventType::SurpriseTime),
+        matches!(goals.eventype(), EventType::SurpriseTime),
         GluXError::EventTypeNotSupport
     );
+    // windowed surprises hold the window end until reveal_surprise fixes the time
     require!(
         now >= goals.surprise_trigger_ts,
         GluXError::SurpriseTimeNotReached
     );
 
     let incentive_amount = {
-        let goal = &mut goals.sub_goals[0];
+        let goal = &ctx.accounts.sub_goal;
         require!(goal.is_active, GluXError::SubGoalIndexOutOfBounds);
         if matches!(goal.status, SubGoalStatus::Paid) {
             return err!(GluXError::SubGoalAlreadyFinalized);
@@ -110,20 +210,89 @@ pub fn trigger_surprise(ctx: Context<TriggerSurprise>) -> Result<()> {
         goal.incentive_amount
     };
 
-    payout_to_taker(
-        goals,
-        ctx.accounts.taker_account.to_account_info(),
-        incentive_amount,
-    )?;
+    let taker_info = ctx.accounts.taker_account.to_account_info();
+    let payees = Payees {
+        taker: &taker_info,
+        beneficiaries: ctx.accounts.beneficiaries.as_deref(),
+        wallets: ctx.remaining_accounts,
+    };
+    payees.pay(&goals, &goal_info, 0, incentive_amount)?;
 
-    goals.sub_goals[0].status = SubGoalStatus::Paid;
+    ctx.accounts.sub_goal.status = SubGoalStatus::Paid;
+    goals.settle_sub_goal(0, incentive_amount);
     goals.released_amount = goals.released_amount.saturating_add(incentive_amount);
     goals.completed_count = goals.completed_count.saturating_add(1);
+
+    // the key is optional, and one that does not open the message is
+    // ignored, so the message never blocks the payout itself
+    if let Some(key) = message_key {
+        let _ = open_sealed_message(&mut goals, &key);
+    }
+    Ok(())
+}
+
+pub fn reveal_sealed_message(ctx: Context<RevealSealedMessage>, message_key: Vec<u8>) -> Result<()> {
+    require!(
+        matches!(ctx.accounts.sub_goal.status, SubGoalStatus::Paid),
+        GluXError::SealedMessageLocked
+    );
+    open_sealed_message(&mut *ctx.accounts.goals.load_mut()?, &message_key)
+}
+
+// Fixes a windowed surprise time once. The committed secret is mixed with
+// the hash of the reveal slot recorded at setup, which did not exist when
+// the issuer committed, so neither party can predict or steer the result.
+// The hash has to still be in the slot hashes sysvar; a surprise that is
+// never revealed fires at the window end.
+pub fn reveal_surprise(ctx: Context<RevealSurprise>, secret: [u8; 32]) -> Result<()> {
+    let goal_key = ctx.accounts.goals.key();
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    require_keys_eq!(
+        goals.issuer,
+        ctx.accounts.issuer.key(),
+        GluXError::UnauthorizedSigner
+    );
+    require!(
+        matches!(goals.eventype(), EventType::SurpriseTime) && goals.has_surprise_window(),
+        GluXError::EventTypeNotSupport
+    );
+    require!(goals.surprise_revealed == 0, GluXError::SurpriseAlreadyRevealed);
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp < goals.surprise_window_end,
+        GluXError::SurpriseRevealClosed
+    );
+    require!(
+        clock.slot > goals.surprise_reveal_slot,
+        GluXError::SurpriseRevealSlotPending
+    );
+    require!(
+        hash(&secret).to_bytes() == goals.surprise_commitment,
+        GluXError::SurpriseCommitmentMismatch
+    );
+
+    let slot_hashes = ctx.accounts.recent_slothashes.try_borrow_data()?;
+    let slot_hash = slot_hash_at(&slot_hashes, goals.surprise_reveal_slot)
+        .ok_or(GluXError::SlotHashesUnavailable)?;
+    let trigger_ts = derive_surprise_ts(
+        &secret,
+        &slot_hash,
+        &goal_key,
+        goals.start_time,
+        goals.surprise_window_end,
+    );
+
+    goals.surprise_trigger_ts = trigger_ts;
+    goals.surprise_revealed = 1;
+    let goal = &mut ctx.accounts.sub_goal;
+    goal.deadline = trigger_ts;
+    goal.auto_release_at = trigger_ts;
     Ok(())
 }
 
 pub fn claim_unused(ctx: Context<ClaimUnused>) -> Result<()> {
-    let goals = &mut ctx.accounts.goals;
+    let goal_info = ctx.accounts.goals.to_account_info();
+    let mut goals = ctx.accounts.goals.load_mut()?;
     require_keys_eq!(
         goals.issuer,
         ctx.accounts.issuer.key(),
@@ -132,82 +301,262 @@ pub fn claim_unused(ctx: Context<ClaimUnused>) -> Result<()> {
     let now = Clock::get()?.unix_timestamp;
     require!(now >= goals.unlock_time, GluXError::UnlockTimeNotReached);
 
-    let remaining = goals.deposited_amount.saturating_sub(goals.released_amount);
-    require!(remaining > 0, GluXError::NoFundsAvailable);
+    let amount = take_unused(&mut goals, now)?;
+    payout_from_goal(&goal_info, ctx.accounts.issuer.to_account_info(), amount)
+}
+
+// Books the issuer's unspent balance as released and returns it. Shared by
+// claim_unused and the dead man switch. A stream's vested but unwithdrawn
+// funds stay behind for withdraw_streamed.
+pub(crate) fn take_unused(goals: &mut TotalGoal, now: i64) -> Result<u64> {
+    if goals.sponsored_amount > 0 {
+        require!(goals.issuer_refunded == 0, GluXError::RefundAlreadyClaimed);
+    }
+    let amount = book_unused(goals, now);
+    require!(amount > 0, GluXError::NoFundsAvailable);
+    Ok(amount)
+}
 
-    payout_from_goal(goals, ctx.accounts.issuer.to_account_info(), remaining)?;
+// Same as take_unused, but an empty balance books zero instead of failing.
+pub(crate) fn book_unused(goals: &mut TotalGoal, now: i64) -> u64 {
+    // sponsored goals split the unspent balance pro-rata, see claim_sponsor_refund
+    if goals.sponsored_amount > 0 {
+        goals.start_refund(now);
+        if goals.issuer_refunded != 0 {
+            return 0;
+        }
+        let share = goals.refund_share(goals.issuer_contribution());
+        goals.released_amount = goals.released_amount.saturating_add(share);
+        goals.issuer_refunded = 1;
+        return share;
+    }
 
+    // a recurring goal keeps what its current period still owes the taker
+    let reserved = if goals.is_recurring() {
+        goals.allocated_amount
+    } else {
+        0
+    };
+    let remaining = goals
+        .unreleased_amount()
+        .saturating_sub(goals.stream_owed(now))
+        .saturating_sub(reserved);
     goals.released_amount = goals.released_amount.saturating_add(remaining);
-    Ok(())
+    remaining
 }
 
 #[derive(Accounts)]
+#[instruction(subgoal_index: u8)]
 pub struct SubmitProof<'info> {
+    #[account(
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
     #[account(
         mut,
-        seeds = [b"gluex-goals", goals.issuer.as_ref(), goals.taker.as_ref(), goals.id.to_le_bytes().as_ref()],
-        bump = goals.bump
+        seeds = [b"gluex-subgoal", goals.key().as_ref(), &[subgoal_index]],
+        bump = sub_goal.bump
     )]
-    pub goals: Account<'info, TotalGoal>,
+    pub sub_goal: Account<'info, SubGoalAccount>,
     pub taker: Signer<'info>,
 }
 
 #[derive(Accounts)]
+#[instruction(subgoal_index: u8)]
 pub struct ReviewSubGoal<'info> {
     #[account(
         mut,
-        seeds = [b"gluex-goals", goals.issuer.as_ref(), goals.taker.as_ref(), goals.id.to_le_bytes().as_ref()],
-        bump = goals.bump
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        mut,
+        seeds = [b"gluex-subgoal", goals.key().as_ref(), &[subgoal_index]],
+        bump = sub_goal.bump
     )]
-    pub goals: Account<'info, TotalGoal>,
+    pub sub_goal: Account<'info, SubGoalAccount>,
+    // the issuer, or the subgoal's verifier when one is set
     pub issuer: Signer<'info>,
     /// CHECK: destination validated via key comparison
     #[account(mut)]
     pub taker_account: AccountInfo<'info>,
+    // required when the goal splits payouts
+    #[account(
+        seeds = [b"goal-beneficiaries", goals.key().as_ref()],
+        bump = beneficiaries.bump
+    )]
+    pub beneficiaries: Option<Account<'info, GoalBeneficiaries>>,
+    // remaining accounts: the beneficiary wallets of the subgoal's split, in
+    // stored order
 }
 
 #[derive(Accounts)]
 pub struct TriggerSurprise<'info> {
     #[account(
         mut,
-        seeds = [b"gluex-goals", goals.issuer.as_ref(), goals.taker.as_ref(), goals.id.to_le_bytes().as_ref()],
-        bump = goals.bump
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        mut,
+        seeds = [b"gluex-subgoal", goals.key().as_ref(), &[0u8]],
+        bump = sub_goal.bump
     )]
-    pub goals: Account<'info, TotalGoal>,
+    pub sub_goal: Account<'info, SubGoalAccount>,
     /// CHECK: validated against stored taker key
     #[account(mut)]
     pub taker_account: AccountInfo<'info>,
+    // required when the goal splits payouts
+    #[account(
+        seeds = [b"goal-beneficiaries", goals.key().as_ref()],
+        bump = beneficiaries.bump
+    )]
+    pub beneficiaries: Option<Account<'info, GoalBeneficiaries>>,
+    // remaining accounts: the beneficiary wallets of subgoal 0's split, in
+    // stored order
+}
+
+#[derive(Accounts)]
+pub struct RevealSealedMessage<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        seeds = [b"gluex-subgoal", goals.key().as_ref(), &[0u8]],
+        bump = sub_goal.bump
+    )]
+    pub sub_goal: Account<'info, SubGoalAccount>,
+}
+
+#[derive(Accounts)]
+pub struct RevealSurprise<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        mut,
+        seeds = [b"gluex-subgoal", goals.key().as_ref(), &[0u8]],
+        bump = sub_goal.bump
+    )]
+    pub sub_goal: Account<'info, SubGoalAccount>,
+    pub issuer: Signer<'info>,
+    /// CHECK: address pinned to the slot hashes sysvar, read as raw bytes
+    #[account(address = SLOT_HASHES_SYSVAR)]
+    pub recent_slothashes: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
 pub struct ClaimUnused<'info> {
     #[account(
         mut,
-        seeds = [b"gluex-goals", goals.issuer.as_ref(), goals.taker.as_ref(), goals.id.to_le_bytes().as_ref()],
-        bump = goals.bump
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
     )]
-    pub goals: Account<'info, TotalGoal>,
+    pub goals: AccountLoader<'info, TotalGoal>,
     #[account(mut)]
     pub issuer: Signer<'info>,
 }
 
-fn payout_to_taker(
-    goals: &mut Account<TotalGoal>,
-    taker_account: AccountInfo,
-    amount: u64,
-) -> Result<()> {
-    payout_from_goal(goals, taker_account, amount)
+fn open_sealed_message(goals: &mut TotalGoal, message_key: &[u8]) -> Result<()> {
+    require!(goals.has_sealed_message(), GluXError::SealedMessageInvalid);
+    require!(goals.sealed_message.revealed == 0, GluXError::SealedMessageInvalid);
+    require!(
+        message_key.len() <= MAX_SEALED_REVEAL_LENGTH,
+        GluXError::SealedMessageInvalid
+    );
+    require!(
+        hash(message_key).to_bytes() == goals.sealed_message.commitment,
+        GluXError::SealedMessageMismatch
+    );
+
+    let sealed = &mut goals.sealed_message;
+    sealed.key = [0u8; MAX_SEALED_REVEAL_LENGTH];
+    sealed.key[..message_key.len()].copy_from_slice(message_key);
+    sealed.key_len = message_key.len() as u8;
+    sealed.revealed = 1;
+    Ok(())
+}
+
+fn payout_to_taker(goal_info: &AccountInfo, taker_account: AccountInfo, amount: u64) -> Result<()> {
+    payout_from_goal(goal_info, taker_account, amount)
+}
+
+// Recipients of a subgoal payout: the taker, or the wallets of the goal's
+// beneficiary split, passed in the order they are stored.
+pub(crate) struct Payees<'a, 'info> {
+    pub taker: &'a AccountInfo<'info>,
+    pub beneficiaries: Option<&'a GoalBeneficiaries>,
+    pub wallets: &'a [AccountInfo<'info>],
+}
+
+impl<'a, 'info> Payees<'a, 'info> {
+    pub fn taker(taker: &'a AccountInfo<'info>) -> Self {
+        Payees {
+            taker,
+            beneficiaries: None,
+            wallets: &[],
+        }
+    }
+
+    fn pay(
+        &self,
+        goals: &TotalGoal,
+        goal_info: &AccountInfo,
+        subgoal_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        let shares = match self.beneficiaries {
+            Some(beneficiaries) => beneficiaries.shares_for(subgoal_index),
+            None => {
+                require!(!goals.has_split_payouts(), GluXError::BeneficiaryMismatch);
+                &[]
+            }
+        };
+        if shares.is_empty() {
+            return payout_to_taker(goal_info, self.taker.clone(), amount);
+        }
+
+        require!(
+            self.wallets.len() == shares.len(),
+            GluXError::BeneficiaryMismatch
+        );
+        let amounts = split_amount(amount, shares);
+        let rent_exempt = Rent::get()?.minimum_balance(0);
+        for ((share, wallet), portion) in shares.iter().zip(self.wallets).zip(amounts) {
+            require_keys_eq!(share.wallet, wallet.key(), GluXError::BeneficiaryMismatch);
+            // wallets are funded at configure_beneficiaries; one emptied since
+            // then has to be topped up before it can take a dust portion
+            require!(
+                wallet.lamports() > 0 || portion == 0 || portion >= rent_exempt,
+                GluXError::BeneficiaryWalletUnfunded
+            );
+            if portion > 0 {
+                payout_from_goal(goal_info, wallet.clone(), portion)?;
+            }
+        }
+        Ok(())
+    }
 }
 
-fn payout_from_goal(
-    goals: &mut Account<TotalGoal>,
+// Moves lamports straight out of the goal PDA. Only touches lamports, so it
+// is safe to call while the goal data is loaded.
+pub(crate) fn payout_from_goal(
+    goal_info: &AccountInfo,
     destination: AccountInfo,
     amount: u64,
 ) -> Result<()> {
     require!(amount > 0, GluXError::NoFundsAvailable);
-    let goal_account_info = goals.to_account_info();
 
-    **goal_account_info.try_borrow_mut_lamports()? = goal_account_info
+    **goal_info.try_borrow_mut_lamports()? = goal_info
         .lamports()
         .checked_sub(amount)
         .ok_or(GluXError::NoFundsAvailable)?;
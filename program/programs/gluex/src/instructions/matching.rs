@@ -0,0 +1,272 @@
+use super::manage::payout_from_goal;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+#[instruction(pool_id: i64)]
+pub struct CreateMatchingPool<'info> {
+    #[account(
+        init,
+        payer = sponsor,
+        space = MATCHING_POOL_SPACE,
+        seeds = [b"matching-pool", sponsor.key().as_ref(), pool_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub matching_pool: Account<'info, MatchingPool>,
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_matching_pool(
+    ctx: Context<CreateMatchingPool>,
+    pool_id: i64,
+    config: MatchingPoolConfig,
+    initial_funding: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(config.ratio_bps > 0, GluXError::MatchingPoolConfigInvalid);
+    require!(
+        config.per_goal_cap > 0 || config.per_bounty_cap > 0,
+        GluXError::MatchingPoolConfigInvalid
+    );
+    require!(config.per_issuer_cap > 0, GluXError::MatchingPoolConfigInvalid);
+    require!(config.expires_at > now, GluXError::MatchingPoolConfigInvalid);
+
+    let pool = &mut ctx.accounts.matching_pool;
+    pool.sponsor = ctx.accounts.sponsor.key();
+    pool.pool_id = pool_id;
+    pool.ratio_bps = config.ratio_bps;
+    pool.per_goal_cap = config.per_goal_cap;
+    pool.per_bounty_cap = config.per_bounty_cap;
+    pool.per_issuer_cap = config.per_issuer_cap;
+    pool.relations_mask = config.relations_mask;
+    pool.event_types_mask = config.event_types_mask;
+    pool.min_reputation = config.min_reputation;
+    pool.expires_at = config.expires_at;
+    pool.total_funded = 0;
+    pool.total_matched = 0;
+    pool.bump = ctx.bumps.matching_pool;
+
+    if initial_funding > 0 {
+        deposit_to_pool(
+            pool,
+            ctx.accounts.sponsor.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            initial_funding,
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundMatchingPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"matching-pool", matching_pool.sponsor.as_ref(), matching_pool.pool_id.to_le_bytes().as_ref()],
+        bump = matching_pool.bump
+    )]
+    pub matching_pool: Account<'info, MatchingPool>,
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn fund_matching_pool(ctx: Context<FundMatchingPool>, amount: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.matching_pool;
+    require_keys_eq!(
+        pool.sponsor,
+        ctx.accounts.sponsor.key(),
+        GluXError::UnauthorizedSigner
+    );
+    require!(amount > 0, GluXError::PayerAccountInsufficient);
+    let now = Clock::get()?.unix_timestamp;
+    require!(now < pool.expires_at, GluXError::MatchingPoolExpired);
+
+    deposit_to_pool(
+        pool,
+        ctx.accounts.sponsor.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+pub struct OpenMatchingAllowance<'info> {
+    #[account(
+        seeds = [b"matching-pool", matching_pool.sponsor.as_ref(), matching_pool.pool_id.to_le_bytes().as_ref()],
+        bump = matching_pool.bump
+    )]
+    pub matching_pool: Account<'info, MatchingPool>,
+    #[account(
+        init,
+        payer = issuer,
+        space = MATCHING_ALLOWANCE_SPACE,
+        seeds = [b"matching-allowance", matching_pool.key().as_ref(), issuer.key().as_ref()],
+        bump
+    )]
+    pub allowance: Account<'info, MatchingAllowance>,
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// An issuer opens one allowance per pool before its goals or bounties can be
+// matched; it tracks how much of the pool's per_issuer_cap they have drawn.
+pub fn open_matching_allowance(ctx: Context<OpenMatchingAllowance>) -> Result<()> {
+    let allowance = &mut ctx.accounts.allowance;
+    allowance.pool = ctx.accounts.matching_pool.key();
+    allowance.issuer = ctx.accounts.issuer.key();
+    allowance.matched_amount = 0;
+    allowance.bump = ctx.bumps.allowance;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawMatchingPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"matching-pool", matching_pool.sponsor.as_ref(), matching_pool.pool_id.to_le_bytes().as_ref()],
+        bump = matching_pool.bump
+    )]
+    pub matching_pool: Account<'info, MatchingPool>,
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+}
+
+pub fn withdraw_matching_pool(ctx: Context<WithdrawMatchingPool>) -> Result<()> {
+    let pool = &mut ctx.accounts.matching_pool;
+    require_keys_eq!(
+        pool.sponsor,
+        ctx.accounts.sponsor.key(),
+        GluXError::UnauthorizedSigner
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= pool.expires_at, GluXError::MatchingPoolActive);
+
+    let available = pool_available(pool)?;
+    require!(available > 0, GluXError::NoFundsAvailable);
+    move_pool_lamports(pool, &ctx.accounts.sponsor.to_account_info(), available)
+}
+
+#[derive(Accounts)]
+pub struct ReclaimMatchedFunds<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        mut,
+        address = goals.load()?.matching_pool,
+        seeds = [b"matching-pool", matching_pool.sponsor.as_ref(), matching_pool.pool_id.to_le_bytes().as_ref()],
+        bump = matching_pool.bump
+    )]
+    pub matching_pool: Account<'info, MatchingPool>,
+}
+
+// Permissionless: once the goal unlocks, the matched slice of the unspent
+// balance flows back into the pool it was drawn from.
+pub fn reclaim_matched_funds(ctx: Context<ReclaimMatchedFunds>) -> Result<()> {
+    let goal_info = ctx.accounts.goals.to_account_info();
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= goals.unlock_time, GluXError::UnlockTimeNotReached);
+    require!(goals.matched_amount > 0, GluXError::NoFundsAvailable);
+    require!(goals.matching_refunded == 0, GluXError::RefundAlreadyClaimed);
+
+    goals.start_refund(now);
+    let share = goals.refund_share(goals.matched_amount);
+    require!(share > 0, GluXError::NoFundsAvailable);
+
+    payout_from_goal(&goal_info, ctx.accounts.matching_pool.to_account_info(), share)?;
+
+    goals.released_amount = goals.released_amount.saturating_add(share);
+    goals.matching_refunded = 1;
+    Ok(())
+}
+
+pub(crate) fn check_issuer_reputation(
+    pool: &MatchingPool,
+    issuer_profile: Option<&Account<AgentProfile>>,
+    issuer: &Pubkey,
+) -> Result<()> {
+    if pool.min_reputation == 0 {
+        return Ok(());
+    }
+    let profile = issuer_profile.ok_or(GluXError::MatchingPoolIneligible)?;
+    require_keys_eq!(profile.owner, *issuer, GluXError::MatchingPoolIneligible);
+    require!(
+        profile.reputation_score >= pool.min_reputation,
+        GluXError::MatchingPoolIneligible
+    );
+    Ok(())
+}
+
+pub(crate) fn pool_available(pool: &Account<MatchingPool>) -> Result<u64> {
+    let info = pool.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(info.data_len());
+    Ok(info.lamports().saturating_sub(rent_exempt))
+}
+
+// Draws the match for `deposit` into `destination`, bounded by `cap`, the
+// pool balance and what is left of the issuer's allowance. Returns the
+// matched lamports.
+pub(crate) fn draw_matched_funds(
+    pool: &mut Account<MatchingPool>,
+    allowance: Option<&mut Account<MatchingAllowance>>,
+    issuer: &Pubkey,
+    destination: &AccountInfo,
+    deposit: u64,
+    cap: u64,
+) -> Result<u64> {
+    let allowance = allowance.ok_or(GluXError::MatchingPoolIneligible)?;
+    require_keys_eq!(allowance.pool, pool.key(), GluXError::MatchingPoolIneligible);
+    require_keys_eq!(allowance.issuer, *issuer, GluXError::MatchingPoolIneligible);
+    let headroom = pool.per_issuer_cap.saturating_sub(allowance.matched_amount);
+
+    let amount = pool.match_amount(deposit, cap.min(headroom), pool_available(pool)?);
+    if amount > 0 {
+        move_pool_lamports(pool, destination, amount)?;
+        pool.total_matched = pool.total_matched.saturating_add(amount);
+        allowance.matched_amount = allowance.matched_amount.saturating_add(amount);
+    }
+    Ok(amount)
+}
+
+fn move_pool_lamports(
+    pool: &mut Account<MatchingPool>,
+    destination: &AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    let pool_info = pool.to_account_info();
+    **pool_info.try_borrow_mut_lamports()? = pool_info
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(GluXError::NoFundsAvailable)?;
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(amount)
+        .ok_or(GluXError::NoFundsAvailable)?;
+    Ok(())
+}
+
+fn deposit_to_pool<'info>(
+    pool: &mut Account<'info, MatchingPool>,
+    sponsor: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let dep_ctx = CpiContext::new(
+        system_program,
+        system_program::Transfer {
+            from: sponsor,
+            to: pool.to_account_info(),
+        },
+    );
+    system_program::transfer(dep_ctx, amount)?;
+    pool.total_funded = pool.total_funded.saturating_add(amount);
+    Ok(())
+}
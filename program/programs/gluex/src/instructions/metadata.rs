@@ -0,0 +1,80 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetGoalMetadata<'info> {
+    #[account(
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        init_if_needed,
+        payer = issuer,
+        space = GOAL_METADATA_SPACE,
+        seeds = [b"goal-metadata", goals.key().as_ref()],
+        bump
+    )]
+    pub metadata: Account<'info, GoalMetadata>,
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Points the goal at off-chain metadata. Only the issuer may publish or
+// replace it; each update bumps `revision` so clients can refetch.
+pub fn set_goal_metadata(
+    ctx: Context<SetGoalMetadata>,
+    uri: String,
+    content_hash: [u8; 32],
+) -> Result<()> {
+    require_keys_eq!(
+        ctx.accounts.goals.load()?.issuer,
+        ctx.accounts.issuer.key(),
+        GluXError::UnauthorizedSigner
+    );
+    require!(
+        !uri.is_empty() && uri.len() <= MAX_METADATA_URI_LENGTH,
+        GluXError::MetadataInvalid
+    );
+    require!(content_hash != [0u8; 32], GluXError::MetadataInvalid);
+
+    let metadata = &mut ctx.accounts.metadata;
+    if metadata.goal == Pubkey::default() {
+        metadata.goal = ctx.accounts.goals.key();
+        metadata.bump = ctx.bumps.metadata;
+    } else {
+        metadata.revision = metadata.revision.saturating_add(1);
+    }
+    metadata.uri = uri;
+    metadata.content_hash = content_hash;
+    metadata.updated_at = Clock::get()?.unix_timestamp;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseGoalMetadata<'info> {
+    #[account(
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        mut,
+        close = issuer,
+        seeds = [b"goal-metadata", goals.key().as_ref()],
+        bump = metadata.bump
+    )]
+    pub metadata: Account<'info, GoalMetadata>,
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+}
+
+pub fn close_goal_metadata(ctx: Context<CloseGoalMetadata>) -> Result<()> {
+    require_keys_eq!(
+        ctx.accounts.goals.load()?.issuer,
+        ctx.accounts.issuer.key(),
+        GluXError::UnauthorizedSigner
+    );
+    Ok(())
+}
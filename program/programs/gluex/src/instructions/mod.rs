@@ -3,9 +3,47 @@ pub use manage::*;
 pub use fee::*;
 pub use bounty::*;
 pub use profile::*;
+pub use sponsor::*;
+pub use matching::*;
+pub use transfer::*;
+pub use registry::*;
+pub use amend::*;
+pub use metadata::*;
+pub use template::*;
+pub use batch::*;
+pub use recurring::*;
+pub use stream::*;
+pub use challenge::*;
+pub use circle::*;
+pub use chore::*;
+pub use dao::*;
+pub use joint::*;
+pub use beneficiary::*;
+pub use dead_man::*;
+pub use rollover::*;
+pub use emergency::*;
 
 pub mod create;
 pub mod manage;
 pub mod fee;
 pub mod bounty;
 pub mod profile;
+pub mod sponsor;
+pub mod matching;
+pub mod transfer;
+pub mod registry;
+pub mod amend;
+pub mod metadata;
+pub mod template;
+pub mod batch;
+pub mod recurring;
+pub mod stream;
+pub mod challenge;
+pub mod circle;
+pub mod chore;
+pub mod dao;
+pub mod joint;
+pub mod beneficiary;
+pub mod dead_man;
+pub mod rollover;
+pub mod emergency;
@@ -0,0 +1,153 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+pub struct MakeGoalRecurring<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Turns a fresh goal into one that repeats every `period` seconds for
+// `cycles` periods in total. The subgoals describe a single period; `funding`
+// optionally pre-funds later periods from the issuer in the same call.
+pub fn make_goal_recurring(
+    ctx: Context<MakeGoalRecurring>,
+    period: i64,
+    cycles: u16,
+    funding: u64,
+) -> Result<()> {
+    {
+        let mut goals = ctx.accounts.goals.load_mut()?;
+        require_keys_eq!(
+            goals.issuer,
+            ctx.accounts.issuer.key(),
+            GluXError::UnauthorizedSigner
+        );
+        require!(
+            !goals.is_recurring()
+                && !matches!(goals.eventype(), EventType::SurpriseTime | EventType::Streaming)
+                && goals.failed == 0
+                && goals.refund_started == 0
+                && goals.settled_mask == 0,
+            GluXError::RecurrenceInvalid
+        );
+        // periods may not overlap, or two sets of deadlines would be open
+        require!(
+            cycles >= 2 && period > 0 && period >= goals.completion_time - goals.start_time,
+            GluXError::RecurrenceInvalid
+        );
+
+        goals.recurrence_period = period;
+        goals.recurrence_cycles = cycles;
+        goals.recurrence_cycle = 0;
+        goals.total_incentive_amount = goals.total_incentive_amount.saturating_add(funding);
+        goals.deposited_amount = goals.deposited_amount.saturating_add(funding);
+    }
+
+    if funding > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.issuer.to_account_info(),
+                    to: ctx.accounts.goals.to_account_info(),
+                },
+            ),
+            funding,
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RearmRecurringGoal<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    // remaining accounts: every SubGoalAccount of the goal, in index order
+}
+
+// Starts the next period once every subgoal is settled or the current period
+// has ended. Anyone may crank it. Proofs submitted by the period end get
+// RECURRENCE_REVIEW_GRACE_SECONDS for review first. Active subgoals return to
+// Pending with their deadlines shifted by the period; proofs still unreviewed
+// after the grace lapse, and unpaid incentives fall back into the goal balance
+// that funds the next period.
+pub fn rearm_recurring_goal<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RearmRecurringGoal<'info>>,
+) -> Result<()> {
+    let goal_key = ctx.accounts.goals.key();
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    require!(
+        goals.is_recurring() && goals.failed == 0 && goals.refund_started == 0,
+        GluXError::RecurrenceInvalid
+    );
+    require!(
+        goals.recurrence_cycle.saturating_add(1) < goals.recurrence_cycles,
+        GluXError::RecurrenceExhausted
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        goals.all_settled() || now >= goals.completion_time,
+        GluXError::RecurrenceNotDue
+    );
+    require!(
+        ctx.remaining_accounts.len() == goals.active_sub_goals as usize,
+        GluXError::SubGoalAccountMismatch
+    );
+
+    let review_open = now < goals.completion_time.saturating_add(RECURRENCE_REVIEW_GRACE_SECONDS);
+    let period = goals.recurrence_period;
+    let mut settled_mask: u64 = 0;
+    let mut allocated: u64 = 0;
+    for (index, info) in ctx.remaining_accounts.iter().enumerate() {
+        let mut sub_goal = Account::<SubGoalAccount>::try_from(info)?;
+        require!(
+            sub_goal.goal == goal_key && sub_goal.index as usize == index,
+            GluXError::SubGoalAccountMismatch
+        );
+        if !sub_goal.is_active {
+            // deactivated subgoals stay out of every later period
+            settled_mask |= 1u64 << index;
+            continue;
+        }
+        require!(
+            !(review_open && matches!(sub_goal.status, SubGoalStatus::ProofSubmitted)),
+            GluXError::RecurrenceNotDue
+        );
+        sub_goal.status = SubGoalStatus::Pending;
+        sub_goal.proof_uri = [0u8; MAX_PROOF_URI_LENGTH];
+        sub_goal.submitted_at = 0;
+        sub_goal.deadline = sub_goal.deadline.saturating_add(period);
+        if sub_goal.auto_release_at > 0 {
+            sub_goal.auto_release_at = sub_goal.auto_release_at.saturating_add(period);
+        }
+        allocated = allocated.saturating_add(sub_goal.incentive_amount);
+        sub_goal.exit(ctx.program_id)?;
+    }
+
+    // withheld late penalties belong to the issuer, not to later periods
+    let available = goals
+        .unreleased_amount()
+        .saturating_sub(goals.penalty_withheld);
+    require!(allocated <= available, GluXError::RecurrenceUnderfunded);
+
+    goals.settled_mask = settled_mask;
+    goals.allocated_amount = allocated;
+    goals.recurrence_cycle = goals.recurrence_cycle.saturating_add(1);
+    goals.start_time = goals.start_time.saturating_add(period);
+    goals.completion_time = goals.completion_time.saturating_add(period);
+    goals.unlock_time = goals.unlock_time.saturating_add(period);
+    Ok(())
+}
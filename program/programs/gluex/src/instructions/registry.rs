@@ -0,0 +1,238 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct InitUserIndex<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = USER_GOAL_INDEX_SPACE,
+        seeds = [b"user-goal-index", owner.as_ref()],
+        bump
+    )]
+    pub user_index: Account<'info, UserGoalIndex>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Anyone may pay for a user's index so a taker never has to act first.
+pub fn init_user_index(ctx: Context<InitUserIndex>, owner: Pubkey) -> Result<()> {
+    let index = &mut ctx.accounts.user_index;
+    index.owner = owner;
+    index.next_goal_id = 0;
+    index.bump = ctx.bumps.user_index;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(kind: IndexKind)]
+pub struct IndexGoal<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        mut,
+        seeds = [b"user-goal-index", user_index.owner.as_ref()],
+        bump = user_index.bump
+    )]
+    pub user_index: Account<'info, UserGoalIndex>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = USER_INDEX_PAGE_SPACE,
+        seeds = [
+            b"user-index-page",
+            user_index.owner.as_ref(),
+            &[kind as u8],
+            &current_page(&user_index, kind).to_le_bytes()
+        ],
+        bump
+    )]
+    pub page: Account<'info, UserIndexPage>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn index_goal(ctx: Context<IndexGoal>, kind: IndexKind) -> Result<()> {
+    let entry = ctx.accounts.goals.key();
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    let owner = ctx.accounts.user_index.owner;
+    match kind {
+        IndexKind::IssuedGoal => {
+            require_keys_eq!(goals.issuer, owner, GluXError::UnauthorizedSigner);
+            require!(goals.issuer_indexed == 0, GluXError::AlreadyIndexed);
+            goals.issuer_indexed = 1;
+        }
+        IndexKind::TakenGoal => {
+            require_keys_eq!(goals.taker, owner, GluXError::UnauthorizedTaker);
+            require!(goals.taker_indexed == 0, GluXError::AlreadyIndexed);
+            goals.taker_indexed = 1;
+        }
+        _ => return err!(GluXError::IndexKindMismatch),
+    };
+
+    drop(goals);
+    push_entry(
+        &mut ctx.accounts.user_index,
+        &mut ctx.accounts.page,
+        ctx.bumps.page,
+        kind,
+        entry,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(kind: IndexKind)]
+pub struct IndexBounty<'info> {
+    #[account(
+        mut,
+        seeds = [b"open-bounty", open_bounty.issuer.as_ref(), open_bounty.bounty_id.to_le_bytes().as_ref()],
+        bump = open_bounty.bump
+    )]
+    pub open_bounty: Account<'info, OpenBounty>,
+    // required when recording a claimed bounty
+    #[account(
+        mut,
+        seeds = [b"bounty-exec", open_bounty.key().as_ref(), user_index.owner.as_ref()],
+        bump = bounty_execution.bump
+    )]
+    pub bounty_execution: Option<Account<'info, BountyExecution>>,
+    #[account(
+        mut,
+        seeds = [b"user-goal-index", user_index.owner.as_ref()],
+        bump = user_index.bump
+    )]
+    pub user_index: Account<'info, UserGoalIndex>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = USER_INDEX_PAGE_SPACE,
+        seeds = [
+            b"user-index-page",
+            user_index.owner.as_ref(),
+            &[kind as u8],
+            &current_page(&user_index, kind).to_le_bytes()
+        ],
+        bump
+    )]
+    pub page: Account<'info, UserIndexPage>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn index_bounty(ctx: Context<IndexBounty>, kind: IndexKind) -> Result<()> {
+    let owner = ctx.accounts.user_index.owner;
+    let entry = match kind {
+        IndexKind::PublishedBounty => {
+            let bounty = &mut ctx.accounts.open_bounty;
+            require_keys_eq!(bounty.issuer, owner, GluXError::UnauthorizedSigner);
+            require!(!bounty.indexed, GluXError::AlreadyIndexed);
+            bounty.indexed = true;
+            bounty.key()
+        }
+        IndexKind::ClaimedBounty => {
+            let execution = ctx
+                .accounts
+                .bounty_execution
+                .as_mut()
+                .ok_or(GluXError::IndexKindMismatch)?;
+            require!(!execution.indexed, GluXError::AlreadyIndexed);
+            execution.indexed = true;
+            ctx.accounts.open_bounty.key()
+        }
+        _ => return err!(GluXError::IndexKindMismatch),
+    };
+
+    push_entry(
+        &mut ctx.accounts.user_index,
+        &mut ctx.accounts.page,
+        ctx.bumps.page,
+        kind,
+        entry,
+    )
+}
+
+// Ids below `next_goal_id` are handed out by the issuer's index, so goals
+// created with another id must sit past that range; creating with exactly
+// `next_goal_id` allocates it. Issuers without an index may pick any id.
+pub fn claim_goal_id(index_info: &AccountInfo, goal_id: i64) -> Result<()> {
+    if index_info.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*index_info.owner, crate::ID, GluXError::GoalIdMismatch);
+    let mut data = index_info.try_borrow_mut_data()?;
+    let mut index = UserGoalIndex::try_deserialize(&mut &data[..])?;
+    require!(goal_id >= index.next_goal_id, GluXError::GoalIdMismatch);
+    if goal_id == index.next_goal_id {
+        index.next_goal_id = index.next_goal_id.saturating_add(1);
+        index.try_serialize(&mut &mut data[..])?;
+    }
+    Ok(())
+}
+
+// Page that receives the next entry of `kind`; pages fill up in order.
+pub fn current_page(index: &UserGoalIndex, kind: IndexKind) -> u32 {
+    index.count(kind) / USER_INDEX_PAGE_SIZE as u32
+}
+
+// Removes `entry` from the owner's list of `kind`. The last entry of the last
+// page moves into the freed slot so pages stay dense; `last_page` is only
+// needed when that is a different page from `page`.
+pub fn remove_entry(
+    index: &mut Account<UserGoalIndex>,
+    page: &mut Account<UserIndexPage>,
+    last_page: Option<&mut Account<UserIndexPage>>,
+    kind: IndexKind,
+    entry: Pubkey,
+) -> Result<()> {
+    require!(
+        page.owner == index.owner && page.kind == kind as u8,
+        GluXError::IndexKindMismatch
+    );
+    let position = page
+        .entries
+        .iter()
+        .position(|key| *key == entry)
+        .ok_or(GluXError::IndexEntryMissing)?;
+    let last = index.count(kind).saturating_sub(1) / USER_INDEX_PAGE_SIZE as u32;
+    if page.page == last {
+        page.entries.swap_remove(position);
+    } else {
+        let last_page = last_page.ok_or(GluXError::IndexKindMismatch)?;
+        require!(
+            last_page.owner == index.owner
+                && last_page.kind == kind as u8
+                && last_page.page == last,
+            GluXError::IndexKindMismatch
+        );
+        let moved = last_page.entries.pop().ok_or(GluXError::IndexEntryMissing)?;
+        page.entries[position] = moved;
+    }
+    index.drop_count(kind);
+    Ok(())
+}
+
+fn push_entry(
+    index: &mut Account<UserGoalIndex>,
+    page: &mut Account<UserIndexPage>,
+    page_bump: u8,
+    kind: IndexKind,
+    entry: Pubkey,
+) -> Result<()> {
+    if page.owner == Pubkey::default() {
+        page.owner = index.owner;
+        page.kind = kind as u8;
+        page.page = current_page(index, kind);
+        page.bump = page_bump;
+    }
+    page.entries.push(entry);
+    index.bump_count(kind);
+    Ok(())
+}
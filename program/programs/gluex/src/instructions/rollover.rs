@@ -0,0 +1,143 @@
+use super::create::{create_sub_goal_accounts, param_check, prepare_sub_goals, GoalSpec};
+use super::manage::{payout_from_goal, take_unused};
+use super::registry::claim_goal_id;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(taker: Pubkey, goal_id: i64)]
+pub struct RolloverGoal<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", predecessor.load()?.address_issuer().as_ref(), predecessor.load()?.address_taker().as_ref(), predecessor.load()?.id.to_le_bytes().as_ref()],
+        bump = predecessor.load()?.bump
+    )]
+    pub predecessor: AccountLoader<'info, TotalGoal>,
+    #[account(
+        init, payer = issuer, space = GOAL_ACCOUNT_SPACE,
+        seeds = [b"gluex-goals", issuer.key().as_ref(), taker.as_ref(), goal_id.to_le_bytes().as_ref()], bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        init,
+        payer = issuer,
+        space = GOAL_ROLLOVER_SPACE,
+        seeds = [b"goal-rollover", predecessor.key().as_ref()],
+        bump
+    )]
+    pub rollover: Account<'info, GoalRollover>,
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+    /// CHECK: the issuer's UserGoalIndex PDA, empty when they have none; see claim_goal_id
+    #[account(mut, seeds = [b"user-goal-index", issuer.key().as_ref()], bump)]
+    pub issuer_index: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts: one SubGoalAccount PDA per successor subgoal, in index order
+}
+
+// Moves what is left of a finished goal straight into a successor goal for
+// the same or a new taker, instead of claim_unused and a fresh deposit. The
+// escrow already paid its protocol fee, so none is taken again.
+pub fn rollover_goal<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RolloverGoal<'info>>,
+    taker: Pubkey,
+    goal_id: i64,
+    input: RolloverGoalInput,
+) -> Result<()> {
+    let predecessor_info = ctx.accounts.predecessor.to_account_info();
+    let mut predecessor = ctx.accounts.predecessor.load_mut()?;
+    require_keys_eq!(
+        predecessor.issuer,
+        ctx.accounts.issuer.key(),
+        GluXError::UnauthorizedSigner
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= predecessor.unlock_time, GluXError::UnlockTimeNotReached);
+    // sponsors' shares are refunded to them, never rolled over
+    require!(
+        predecessor.sponsored_amount == 0
+            && !matches!(predecessor.eventype(), EventType::Streaming),
+        GluXError::RolloverInvalid
+    );
+    let amount = take_unused(&mut predecessor, now)?;
+
+    // the escrow funds the successor, so it stands in for the payer balance
+    param_check(
+        &predecessor_info,
+        &input.description,
+        &input.eventype,
+        &input.sub_goals,
+        amount,
+        input.completion_time,
+        input.locked_amount,
+        input.unlock_time,
+        &input.config,
+    )?;
+    let (sub_goals, active_sub_goals, start_time, surprise_ts, checkpoint_interval) =
+        prepare_sub_goals(
+            &input.eventype,
+            &input.sub_goals,
+            amount,
+            input.completion_time,
+            &input.config,
+            now,
+        )?;
+    let prerequisites = match input.eventype {
+        EventType::TargetAchieve => prerequisite_masks(0, &input.sub_goals)?,
+        _ => vec![0u64; sub_goals.len()],
+    };
+    claim_goal_id(&ctx.accounts.issuer_index, goal_id)?;
+
+    let spec = GoalSpec {
+        description: &input.description,
+        room: predecessor.room(),
+        relations: predecessor.relations(),
+        eventype: input.eventype,
+        active_sub_goals,
+        total_incentive_amount: amount,
+        deposited_amount: amount,
+        completion_time: input.completion_time,
+        locked_amount: input.locked_amount,
+        unlock_time: input.unlock_time,
+        start_time,
+        surprise_ts,
+        checkpoint_interval,
+        slot: Clock::get()?.slot,
+        config: &input.config,
+    };
+    let predecessor_key = ctx.accounts.predecessor.key();
+    {
+        let mut successor = ctx.accounts.goals.load_init()?;
+        spec.write(
+            &mut successor,
+            ctx.accounts.issuer.key(),
+            taker,
+            goal_id,
+            ctx.bumps.goals,
+        );
+        successor.predecessor = predecessor_key;
+        successor.allocated_amount = sub_goals.iter().map(|goal| goal.incentive_amount).sum();
+    }
+    drop(predecessor);
+
+    let goal_key = ctx.accounts.goals.key();
+    payout_from_goal(&predecessor_info, ctx.accounts.goals.to_account_info(), amount)?;
+
+    let rollover = &mut ctx.accounts.rollover;
+    rollover.predecessor = predecessor_key;
+    rollover.successor = goal_key;
+    rollover.amount = amount;
+    rollover.rolled_at = now;
+    rollover.bump = ctx.bumps.rollover;
+
+    create_sub_goal_accounts(
+        ctx.program_id,
+        &ctx.accounts.issuer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        goal_key,
+        0,
+        &sub_goals,
+        &prerequisites,
+        ctx.remaining_accounts,
+    )
+}
@@ -0,0 +1,179 @@
+use super::manage::payout_from_goal;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+pub struct AddFunds<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        init_if_needed,
+        payer = sponsor,
+        space = GOAL_SPONSOR_SPACE,
+        seeds = [b"goal-sponsor", goals.key().as_ref(), sponsor.key().as_ref()],
+        bump
+    )]
+    pub sponsor_record: Account<'info, GoalSponsor>,
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+    #[account(mut)]
+    pub fee_pool: Option<Account<'info, FeePool>>,
+    // optional earmark: if present, the deposit raises this subgoal's incentive;
+    // otherwise every subgoal account is passed as remaining accounts in index order
+    #[account(
+        mut,
+        seeds = [b"gluex-subgoal", goals.key().as_ref(), &[sub_goal.index]],
+        bump = sub_goal.bump
+    )]
+    pub sub_goal: Option<Account<'info, SubGoalAccount>>,
+    pub system_program: Program<'info, System>,
+}
+
+// Top-ups raise subgoal incentives: earmarked ones a single open subgoal,
+// the rest every open subgoal in proportion to its incentive, the way
+// matched funds are spread at setup.
+pub fn add_funds<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AddFunds<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, GluXError::PayerAccountInsufficient);
+    let fee = ctx
+        .accounts
+        .fee_pool
+        .as_ref()
+        .map(|pool| pool.fee_for(amount))
+        .unwrap_or(0);
+    let deposited = amount.saturating_sub(fee);
+
+    // goal data must be released before the deposit CPI below
+    {
+        let goal_key = ctx.accounts.goals.key();
+        let mut goals = ctx.accounts.goals.load_mut()?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now < goals.unlock_time
+                && goals.refund_started == 0
+                && goals.failed == 0
+                && !goals.is_stream_halted(),
+            GluXError::FundingClosed
+        );
+
+        if let Some(goal) = ctx.accounts.sub_goal.as_mut() {
+            require!(goal.is_active, GluXError::SubGoalIndexOutOfBounds);
+            require!(
+                !matches!(goal.status, SubGoalStatus::Approved | SubGoalStatus::Paid),
+                GluXError::SubGoalAlreadyFinalized
+            );
+            goal.incentive_amount = goal.incentive_amount.saturating_add(deposited);
+        } else {
+            require!(
+                ctx.remaining_accounts.len() == goals.active_sub_goals as usize,
+                GluXError::SubGoalAccountMismatch
+            );
+            let mut open = Vec::new();
+            for (index, info) in ctx.remaining_accounts.iter().enumerate() {
+                let sub_goal = Account::<SubGoalAccount>::try_from(info)?;
+                require!(
+                    sub_goal.goal == goal_key && sub_goal.index as usize == index,
+                    GluXError::SubGoalAccountMismatch
+                );
+                if sub_goal.is_active
+                    && !matches!(sub_goal.status, SubGoalStatus::Approved | SubGoalStatus::Paid)
+                {
+                    open.push(sub_goal);
+                }
+            }
+            require!(!open.is_empty(), GluXError::FundingClosed);
+            let incentives: Vec<u64> = open.iter().map(|goal| goal.incentive_amount).collect();
+            for (mut sub_goal, share) in open.into_iter().zip(bonus_shares(&incentives, deposited)) {
+                sub_goal.incentive_amount = sub_goal.incentive_amount.saturating_add(share);
+                sub_goal.exit(ctx.program_id)?;
+            }
+        }
+
+        goals.allocated_amount = goals.allocated_amount.saturating_add(deposited);
+        goals.total_incentive_amount = goals.total_incentive_amount.saturating_add(amount);
+        goals.deposited_amount = goals.deposited_amount.saturating_add(deposited);
+        goals.sponsored_amount = goals.sponsored_amount.saturating_add(deposited);
+    }
+
+    let record = &mut ctx.accounts.sponsor_record;
+    if record.sponsor == Pubkey::default() {
+        record.goal = ctx.accounts.goals.key();
+        record.sponsor = ctx.accounts.sponsor.key();
+        record.bump = ctx.bumps.sponsor_record;
+    }
+    record.contributed_amount = record.contributed_amount.saturating_add(deposited);
+
+    if fee > 0 {
+        if let Some(fee_pool_acct) = ctx.accounts.fee_pool.as_ref() {
+            let fee_ctx = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.sponsor.to_account_info(),
+                    to: fee_pool_acct.to_account_info(),
+                },
+            );
+            system_program::transfer(fee_ctx, fee)?;
+        }
+    }
+
+    if deposited > 0 {
+        let dep_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.sponsor.to_account_info(),
+                to: ctx.accounts.goals.to_account_info(),
+            },
+        );
+        system_program::transfer(dep_ctx, deposited)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimSponsorRefund<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        mut,
+        seeds = [b"goal-sponsor", goals.key().as_ref(), sponsor.key().as_ref()],
+        bump = sponsor_record.bump
+    )]
+    pub sponsor_record: Account<'info, GoalSponsor>,
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+}
+
+pub fn claim_sponsor_refund(ctx: Context<ClaimSponsorRefund>) -> Result<()> {
+    let goal_info = ctx.accounts.goals.to_account_info();
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    let record = &mut ctx.accounts.sponsor_record;
+    let now = Clock::get()?.unix_timestamp;
+    // an emergency withdrawal frees sponsors from waiting for unlock
+    require!(
+        now >= goals.unlock_time || goals.is_emergency_exited(),
+        GluXError::UnlockTimeNotReached
+    );
+    require!(!record.refunded, GluXError::RefundAlreadyClaimed);
+
+    goals.start_refund(now);
+    let share = goals.refund_share(record.contributed_amount);
+    require!(share > 0, GluXError::NoFundsAvailable);
+
+    payout_from_goal(&goal_info, ctx.accounts.sponsor.to_account_info(), share)?;
+
+    goals.released_amount = goals.released_amount.saturating_add(share);
+    record.refunded = true;
+    Ok(())
+}
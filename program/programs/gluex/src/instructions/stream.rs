@@ -0,0 +1,127 @@
+use super::manage::payout_from_goal;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct WithdrawStreamed<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        mut,
+        seeds = [b"gluex-subgoal", goals.key().as_ref(), &[0u8]],
+        bump = sub_goal.bump
+    )]
+    pub sub_goal: Account<'info, SubGoalAccount>,
+    #[account(mut)]
+    pub taker: Signer<'info>,
+}
+
+// Pays the taker everything vested but not yet withdrawn. The stream subgoal
+// is marked Paid once the whole (or halted) stream has been withdrawn.
+pub fn withdraw_streamed(ctx: Context<WithdrawStreamed>) -> Result<()> {
+    let goal_info = ctx.accounts.goals.to_account_info();
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    require!(
+        matches!(goals.eventype(), EventType::Streaming),
+        GluXError::StreamInvalid
+    );
+    require_keys_eq!(
+        goals.taker,
+        ctx.accounts.taker.key(),
+        GluXError::UnauthorizedTaker
+    );
+    require!(!goals.is_emergency_exited(), GluXError::GoalEmergencyExited);
+    let sub_goal = &mut ctx.accounts.sub_goal;
+    require!(
+        sub_goal.is_active && !matches!(sub_goal.status, SubGoalStatus::Paid),
+        GluXError::SubGoalAlreadyFinalized
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let vested = goals.vested_amount(sub_goal.incentive_amount, now);
+    let amount = vested
+        .saturating_sub(goals.stream_withdrawn)
+        .min(goals.unreleased_amount());
+    require!(amount > 0, GluXError::NoFundsAvailable);
+
+    payout_from_goal(&goal_info, ctx.accounts.taker.to_account_info(), amount)?;
+    goals.stream_withdrawn = goals.stream_withdrawn.saturating_add(amount);
+    goals.released_amount = goals.released_amount.saturating_add(amount);
+
+    if vested >= sub_goal.incentive_amount {
+        sub_goal.status = SubGoalStatus::Paid;
+        goals.settle_sub_goal(0, sub_goal.incentive_amount);
+        if !goals.is_stream_halted() {
+            goals.completed_count = goals.completed_count.saturating_add(1);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct HaltStream<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    #[account(
+        mut,
+        seeds = [b"gluex-subgoal", goals.key().as_ref(), &[0u8]],
+        bump = sub_goal.bump
+    )]
+    pub sub_goal: Account<'info, SubGoalAccount>,
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+}
+
+// Fails a streaming goal before it completes. The stream is cut down to what
+// has vested, which the taker can still withdraw, and the unvested remainder
+// goes back to the issuer. Sponsored goals keep the remainder in the goal so
+// it is refunded pro-rata through claim_unused and claim_sponsor_refund.
+pub fn halt_stream(ctx: Context<HaltStream>) -> Result<()> {
+    let goal_info = ctx.accounts.goals.to_account_info();
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    require!(
+        matches!(goals.eventype(), EventType::Streaming),
+        GluXError::StreamInvalid
+    );
+    require_keys_eq!(
+        goals.issuer,
+        ctx.accounts.issuer.key(),
+        GluXError::UnauthorizedSigner
+    );
+    require!(!goals.is_stream_halted(), GluXError::StreamHalted);
+    require!(
+        goals.failed == 0 && goals.refund_started == 0,
+        GluXError::FundingClosed
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(now < goals.completion_time, GluXError::StreamInvalid);
+
+    let sub_goal = &mut ctx.accounts.sub_goal;
+    let vested = goals.vested_amount(sub_goal.incentive_amount, now);
+    let unvested = sub_goal.incentive_amount.saturating_sub(vested);
+    sub_goal.incentive_amount = vested;
+    goals.allocated_amount = goals.allocated_amount.saturating_sub(unvested);
+    goals.stream_halted_at = now;
+    goals.failed = 1;
+
+    // nothing left to withdraw: the stream is settled right away
+    if vested <= goals.stream_withdrawn {
+        sub_goal.status = SubGoalStatus::Paid;
+        goals.settle_sub_goal(0, vested);
+    }
+
+    let refund = unvested.min(goals.unreleased_amount());
+    if goals.sponsored_amount == 0 && refund > 0 {
+        payout_from_goal(&goal_info, ctx.accounts.issuer.to_account_info(), refund)?;
+        goals.released_amount = goals.released_amount.saturating_add(refund);
+    }
+    Ok(())
+}
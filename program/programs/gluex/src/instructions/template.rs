@@ -0,0 +1,116 @@
+use super::create::{setup_goal, SetupGoal};
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(template_id: u64)]
+pub struct CreateGoalTemplate<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = GOAL_TEMPLATE_SPACE,
+        seeds = [b"goal-template", creator.key().as_ref(), template_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub template: Account<'info, GoalTemplate>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_goal_template(
+    ctx: Context<CreateGoalTemplate>,
+    template_id: u64,
+    input: GoalTemplateInput,
+) -> Result<()> {
+    require!(input.validate(), GluXError::TemplateInvalid);
+    ctx.accounts.template.apply(
+        ctx.accounts.creator.key(),
+        template_id,
+        input,
+        ctx.bumps.template,
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateGoalTemplate<'info> {
+    #[account(
+        mut,
+        has_one = creator,
+        seeds = [b"goal-template", creator.key().as_ref(), template.template_id.to_le_bytes().as_ref()],
+        bump = template.bump
+    )]
+    pub template: Account<'info, GoalTemplate>,
+    pub creator: Signer<'info>,
+}
+
+// Only affects goals created afterwards; existing goals keep their copy.
+pub fn update_goal_template(ctx: Context<UpdateGoalTemplate>, input: GoalTemplateInput) -> Result<()> {
+    require!(input.validate(), GluXError::TemplateInvalid);
+    let template = &mut ctx.accounts.template;
+    let (creator, template_id, bump) = (template.creator, template.template_id, template.bump);
+    template.apply(creator, template_id, input, bump);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseGoalTemplate<'info> {
+    #[account(
+        mut,
+        close = creator,
+        has_one = creator,
+        seeds = [b"goal-template", creator.key().as_ref(), template.template_id.to_le_bytes().as_ref()],
+        bump = template.bump
+    )]
+    pub template: Account<'info, GoalTemplate>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+pub fn close_goal_template(_ctx: Context<CloseGoalTemplate>) -> Result<()> {
+    Ok(())
+}
+
+// Instantiates the template passed as `template` for `taker`. The template
+// supplies structure, shares and offsets; only the amount and start vary.
+pub fn setup_goal_from_template<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetupGoal<'info>>,
+    taker: Pubkey,
+    goal_id: i64,
+    total_incentive_amount: u64,
+    start_time: i64,
+) -> Result<()> {
+    let fee_pool = ctx.accounts.fee_pool.as_ref().map(|pool| pool.key());
+    let template = ctx
+        .accounts
+        .template
+        .as_mut()
+        .ok_or(error!(GluXError::TemplateMismatch))?;
+    if template.fee_pool != Pubkey::default() {
+        require!(
+            fee_pool == Some(template.fee_pool),
+            GluXError::TemplateMismatch
+        );
+    }
+    template.instances = template.instances.saturating_add(1);
+
+    let instance = template.instantiate(total_incentive_amount, start_time);
+    let description = template.description.clone();
+    let (room, relations, eventype) = (template.room, template.relations, template.eventype);
+    setup_goal(
+        ctx,
+        taker,
+        goal_id,
+        description,
+        room,
+        relations,
+        eventype,
+        instance.sub_goals,
+        total_incentive_amount,
+        instance.completion_time,
+        instance.locked_amount,
+        instance.unlock_time,
+        instance.config,
+    )
+}
@@ -0,0 +1,139 @@
+use super::registry::remove_entry;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeGoalTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptGoalTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"gluex-goals", goals.load()?.address_issuer().as_ref(), goals.load()?.address_taker().as_ref(), goals.load()?.id.to_le_bytes().as_ref()],
+        bump = goals.load()?.bump
+    )]
+    pub goals: AccountLoader<'info, TotalGoal>,
+    pub new_party: Signer<'info>,
+    // required when the outgoing party indexed the goal: their index, the page
+    // listing the goal, and their last page of that kind when it differs
+    #[account(
+        mut,
+        seeds = [b"user-goal-index", old_index.owner.as_ref()],
+        bump = old_index.bump
+    )]
+    pub old_index: Option<Account<'info, UserGoalIndex>>,
+    #[account(
+        mut,
+        seeds = [b"user-index-page", entry_page.owner.as_ref(), &[entry_page.kind], &entry_page.page.to_le_bytes()],
+        bump = entry_page.bump
+    )]
+    pub entry_page: Option<Account<'info, UserIndexPage>>,
+    #[account(
+        mut,
+        seeds = [b"user-index-page", last_page.owner.as_ref(), &[last_page.kind], &last_page.page.to_le_bytes()],
+        bump = last_page.bump
+    )]
+    pub last_page: Option<Account<'info, UserIndexPage>>,
+}
+
+// Drops the goal from the outgoing party's index so it only lists goals they
+// still hold; the incoming party indexes it again through index_goal.
+fn unindex_goal(accounts: &mut AcceptGoalTransfer, kind: IndexKind, old_party: Pubkey) -> Result<()> {
+    let goal_key = accounts.goals.key();
+    let index = accounts
+        .old_index
+        .as_mut()
+        .ok_or(GluXError::IndexKindMismatch)?;
+    require_keys_eq!(index.owner, old_party, GluXError::IndexKindMismatch);
+    let page = accounts
+        .entry_page
+        .as_mut()
+        .ok_or(GluXError::IndexKindMismatch)?;
+    remove_entry(index, page, accounts.last_page.as_mut(), kind, goal_key)
+}
+
+// Passing `Pubkey::default()` cancels a pending proposal.
+pub fn propose_issuer_transfer(ctx: Context<ProposeGoalTransfer>, new_issuer: Pubkey) -> Result<()> {
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    require_keys_eq!(
+        goals.issuer,
+        ctx.accounts.authority.key(),
+        GluXError::UnauthorizedSigner
+    );
+    require!(
+        new_issuer != goals.issuer && new_issuer != goals.taker,
+        GluXError::TransferTargetInvalid
+    );
+    goals.pending_issuer = new_issuer;
+    Ok(())
+}
+
+pub fn accept_issuer_transfer(ctx: Context<AcceptGoalTransfer>) -> Result<()> {
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    let new_issuer = ctx.accounts.new_party.key();
+    require!(
+        goals.pending_issuer != Pubkey::default() && goals.pending_issuer == new_issuer,
+        GluXError::TransferNotProposed
+    );
+    // the taker may have changed since the proposal
+    require!(new_issuer != goals.taker, GluXError::TransferTargetInvalid);
+    if goals.issuer_indexed != 0 {
+        let old_issuer = goals.issuer;
+        drop(goals);
+        unindex_goal(ctx.accounts, IndexKind::IssuedGoal, old_issuer)?;
+        goals = ctx.accounts.goals.load_mut()?;
+    }
+    goals.pin_address_keys();
+    goals.issuer = new_issuer;
+    goals.issuer_indexed = 0;
+    goals.clear_pending_transfers();
+    Ok(())
+}
+
+// Only the issuer proposes a new taker, since the taker's payouts move with
+// the goal; a taker handing over asks the issuer to propose the successor.
+// The proposed key must accept before it takes effect.
+pub fn propose_taker_transfer(ctx: Context<ProposeGoalTransfer>, new_taker: Pubkey) -> Result<()> {
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    require_keys_eq!(
+        goals.issuer,
+        ctx.accounts.authority.key(),
+        GluXError::UnauthorizedSigner
+    );
+    require!(
+        new_taker != goals.taker && new_taker != goals.issuer,
+        GluXError::TransferTargetInvalid
+    );
+    goals.pending_taker = new_taker;
+    Ok(())
+}
+
+pub fn accept_taker_transfer(ctx: Context<AcceptGoalTransfer>) -> Result<()> {
+    let mut goals = ctx.accounts.goals.load_mut()?;
+    let new_taker = ctx.accounts.new_party.key();
+    require!(
+        goals.pending_taker != Pubkey::default() && goals.pending_taker == new_taker,
+        GluXError::TransferNotProposed
+    );
+    // the issuer may have changed since the proposal
+    require!(new_taker != goals.issuer, GluXError::TransferTargetInvalid);
+    if goals.taker_indexed != 0 {
+        let old_taker = goals.taker;
+        drop(goals);
+        unindex_goal(ctx.accounts, IndexKind::TakenGoal, old_taker)?;
+        goals = ctx.accounts.goals.load_mut()?;
+    }
+    goals.pin_address_keys();
+    goals.taker = new_taker;
+    goals.taker_indexed = 0;
+    goals.clear_pending_transfers();
+    Ok(())
+}
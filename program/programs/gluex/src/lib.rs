@@ -12,8 +12,8 @@ pub mod gluex {
 
     use super::*;
 
-    pub fn setup_goal(
-        ctx: Context<SetupGoal>,
+    pub fn setup_goal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetupGoal<'info>>,
         taker: Pubkey,
         goal_id: i64,
         description: String,
@@ -77,14 +77,17 @@ pub mod gluex {
         instructions::fee::migrate_fee_pool(ctx, bump)
     }
 
-    pub fn migrate_total_goal(
-        ctx: Context<MigrateTotalGoal>,
-        issuer: Pubkey,
-        taker: Pubkey,
-        goal_id: i64,
-        bump: u8,
+    pub fn migrate_goal_subgoals<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateGoalSubGoals<'info>>,
     ) -> Result<()> {
-        instructions::create::migrate_total_goal(ctx, issuer, taker, goal_id, bump)
+        instructions::create::migrate_goal_subgoals(ctx)
+    }
+
+    pub fn add_subgoals<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddSubGoals<'info>>,
+        sub_goals: Vec<SubGoalInput>,
+    ) -> Result<()> {
+        instructions::create::add_subgoals(ctx, sub_goals)
     }
 
     pub fn submit_proof(
@@ -95,22 +98,438 @@ pub mod gluex {
         instructions::manage::submit_proof(ctx, subgoal_index, proof_uri)
     }
 
-    pub fn review_subgoal(
-        ctx: Context<ReviewSubGoal>,
+    pub fn review_subgoal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReviewSubGoal<'info>>,
         subgoal_index: u8,
         approve: bool,
     ) -> Result<()> {
         instructions::manage::review_subgoal(ctx, subgoal_index, approve)
     }
 
-    pub fn trigger_surprise(ctx: Context<TriggerSurprise>) -> Result<()> {
-        instructions::manage::trigger_surprise(ctx)
+    pub fn trigger_surprise<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TriggerSurprise<'info>>,
+        message_key: Option<Vec<u8>>,
+    ) -> Result<()> {
+        instructions::manage::trigger_surprise(ctx, message_key)
+    }
+
+    pub fn reveal_sealed_message(
+        ctx: Context<RevealSealedMessage>,
+        message_key: Vec<u8>,
+    ) -> Result<()> {
+        instructions::manage::reveal_sealed_message(ctx, message_key)
+    }
+
+    pub fn reveal_surprise(ctx: Context<RevealSurprise>, secret: [u8; 32]) -> Result<()> {
+        instructions::manage::reveal_surprise(ctx, secret)
     }
 
     pub fn claim_unused(ctx: Context<ClaimUnused>) -> Result<()> {
         instructions::manage::claim_unused(ctx)
     }
 
+    // User Registry
+    pub fn init_user_index(ctx: Context<InitUserIndex>, owner: Pubkey) -> Result<()> {
+        instructions::registry::init_user_index(ctx, owner)
+    }
+
+    pub fn index_goal(ctx: Context<IndexGoal>, kind: IndexKind) -> Result<()> {
+        instructions::registry::index_goal(ctx, kind)
+    }
+
+    pub fn index_bounty(ctx: Context<IndexBounty>, kind: IndexKind) -> Result<()> {
+        instructions::registry::index_bounty(ctx, kind)
+    }
+
+    // Goal Party Transfers
+    pub fn propose_issuer_transfer(
+        ctx: Context<ProposeGoalTransfer>,
+        new_issuer: Pubkey,
+    ) -> Result<()> {
+        instructions::transfer::propose_issuer_transfer(ctx, new_issuer)
+    }
+
+    pub fn accept_issuer_transfer(ctx: Context<AcceptGoalTransfer>) -> Result<()> {
+        instructions::transfer::accept_issuer_transfer(ctx)
+    }
+
+    pub fn propose_taker_transfer(
+        ctx: Context<ProposeGoalTransfer>,
+        new_taker: Pubkey,
+    ) -> Result<()> {
+        instructions::transfer::propose_taker_transfer(ctx, new_taker)
+    }
+
+    pub fn accept_taker_transfer(ctx: Context<AcceptGoalTransfer>) -> Result<()> {
+        instructions::transfer::accept_taker_transfer(ctx)
+    }
+
+    // Subgoal Amendments
+    pub fn propose_subgoal_amendment(
+        ctx: Context<ProposeSubGoalAmendment>,
+        edits: Vec<SubGoalEdit>,
+        additions: Vec<SubGoalInput>,
+    ) -> Result<()> {
+        instructions::amend::propose_subgoal_amendment(ctx, edits, additions)
+    }
+
+    pub fn accept_subgoal_amendment<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptSubGoalAmendment<'info>>,
+    ) -> Result<()> {
+        instructions::amend::accept_subgoal_amendment(ctx)
+    }
+
+    pub fn cancel_subgoal_amendment(ctx: Context<CancelSubGoalAmendment>) -> Result<()> {
+        instructions::amend::cancel_subgoal_amendment(ctx)
+    }
+
+    // Goal Metadata
+    pub fn set_goal_metadata(
+        ctx: Context<SetGoalMetadata>,
+        uri: String,
+        content_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::metadata::set_goal_metadata(ctx, uri, content_hash)
+    }
+
+    pub fn close_goal_metadata(ctx: Context<CloseGoalMetadata>) -> Result<()> {
+        instructions::metadata::close_goal_metadata(ctx)
+    }
+
+    // Goal Templates
+    pub fn create_goal_template(
+        ctx: Context<CreateGoalTemplate>,
+        template_id: u64,
+        input: GoalTemplateInput,
+    ) -> Result<()> {
+        instructions::template::create_goal_template(ctx, template_id, input)
+    }
+
+    pub fn update_goal_template(
+        ctx: Context<UpdateGoalTemplate>,
+        input: GoalTemplateInput,
+    ) -> Result<()> {
+        instructions::template::update_goal_template(ctx, input)
+    }
+
+    pub fn close_goal_template(ctx: Context<CloseGoalTemplate>) -> Result<()> {
+        instructions::template::close_goal_template(ctx)
+    }
+
+    pub fn setup_goal_from_template<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetupGoal<'info>>,
+        taker: Pubkey,
+        goal_id: i64,
+        total_incentive_amount: u64,
+        start_time: i64,
+    ) -> Result<()> {
+        instructions::template::setup_goal_from_template(
+            ctx,
+            taker,
+            goal_id,
+            total_incentive_amount,
+            start_time,
+        )
+    }
+
+    // Batch Goals
+    pub fn batch_setup_goals<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchSetupGoals<'info>>,
+        takers: Vec<Pubkey>,
+        goal_id: i64,
+        input: BatchGoalInput,
+    ) -> Result<()> {
+        instructions::batch::batch_setup_goals(ctx, takers, goal_id, input)
+    }
+
+    pub fn batch_review_subgoals<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchReviewSubGoals<'info>>,
+        subgoal_index: u8,
+        approve: bool,
+    ) -> Result<()> {
+        instructions::batch::batch_review_subgoals(ctx, subgoal_index, approve)
+    }
+
+    // Recurring Goals
+    pub fn make_goal_recurring(
+        ctx: Context<MakeGoalRecurring>,
+        period: i64,
+        cycles: u16,
+        funding: u64,
+    ) -> Result<()> {
+        instructions::recurring::make_goal_recurring(ctx, period, cycles, funding)
+    }
+
+    pub fn rearm_recurring_goal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RearmRecurringGoal<'info>>,
+    ) -> Result<()> {
+        instructions::recurring::rearm_recurring_goal(ctx)
+    }
+
+    // Streaming Goals
+    pub fn withdraw_streamed(ctx: Context<WithdrawStreamed>) -> Result<()> {
+        instructions::stream::withdraw_streamed(ctx)
+    }
+
+    pub fn halt_stream(ctx: Context<HaltStream>) -> Result<()> {
+        instructions::stream::halt_stream(ctx)
+    }
+
+    // Group Challenges
+    pub fn create_group_challenge(
+        ctx: Context<CreateGroupChallenge>,
+        challenge_id: u64,
+        input: GroupChallengeInput,
+    ) -> Result<()> {
+        instructions::challenge::create_group_challenge(ctx, challenge_id, input)
+    }
+
+    pub fn join_group_challenge(ctx: Context<JoinGroupChallenge>) -> Result<()> {
+        instructions::challenge::join_group_challenge(ctx)
+    }
+
+    pub fn submit_challenge_proof(
+        ctx: Context<SubmitChallengeProof>,
+        checkpoint: u8,
+        proof_uri: String,
+    ) -> Result<()> {
+        instructions::challenge::submit_challenge_proof(ctx, checkpoint, proof_uri)
+    }
+
+    pub fn review_challenge_proof(
+        ctx: Context<ReviewChallengeProof>,
+        checkpoint: u8,
+        approve: bool,
+    ) -> Result<()> {
+        instructions::challenge::review_challenge_proof(ctx, checkpoint, approve)
+    }
+
+    pub fn settle_group_challenge(ctx: Context<SettleGroupChallenge>) -> Result<()> {
+        instructions::challenge::settle_group_challenge(ctx)
+    }
+
+    pub fn claim_challenge_payout(ctx: Context<ClaimChallengePayout>) -> Result<()> {
+        instructions::challenge::claim_challenge_payout(ctx)
+    }
+
+    pub fn close_group_challenge(ctx: Context<CloseGroupChallenge>) -> Result<()> {
+        instructions::challenge::close_group_challenge(ctx)
+    }
+
+    // Savings Circles
+    pub fn create_savings_circle(
+        ctx: Context<CreateSavingsCircle>,
+        circle_id: u64,
+        input: SavingsCircleInput,
+    ) -> Result<()> {
+        instructions::circle::create_savings_circle(ctx, circle_id, input)
+    }
+
+    pub fn join_savings_circle(ctx: Context<JoinSavingsCircle>) -> Result<()> {
+        instructions::circle::join_savings_circle(ctx)
+    }
+
+    pub fn start_savings_circle(ctx: Context<StartSavingsCircle>) -> Result<()> {
+        instructions::circle::start_savings_circle(ctx)
+    }
+
+    pub fn contribute_savings_circle(ctx: Context<ContributeSavingsCircle>) -> Result<()> {
+        instructions::circle::contribute_savings_circle(ctx)
+    }
+
+    pub fn payout_savings_round(ctx: Context<PayoutSavingsRound>) -> Result<()> {
+        instructions::circle::payout_savings_round(ctx)
+    }
+
+    pub fn close_savings_circle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseSavingsCircle<'info>>,
+    ) -> Result<()> {
+        instructions::circle::close_savings_circle(ctx)
+    }
+
+    // Chore Boards
+    pub fn create_chore_board(
+        ctx: Context<CreateChoreBoard>,
+        child: Pubkey,
+        auto_approve_after: i64,
+        funding: u64,
+    ) -> Result<()> {
+        instructions::chore::create_chore_board(ctx, child, auto_approve_after, funding)
+    }
+
+    pub fn fund_chore_board(ctx: Context<FundChoreBoard>, amount: u64) -> Result<()> {
+        instructions::chore::fund_chore_board(ctx, amount)
+    }
+
+    pub fn withdraw_chore_funds(ctx: Context<WithdrawChoreFunds>, amount: u64) -> Result<()> {
+        instructions::chore::withdraw_chore_funds(ctx, amount)
+    }
+
+    pub fn add_chore(
+        ctx: Context<AddChore>,
+        title: String,
+        reward: u64,
+        weekly_cap: u8,
+    ) -> Result<()> {
+        instructions::chore::add_chore(ctx, title, reward, weekly_cap)
+    }
+
+    pub fn update_chore(
+        ctx: Context<UpdateChore>,
+        reward: u64,
+        weekly_cap: u8,
+        is_active: bool,
+    ) -> Result<()> {
+        instructions::chore::update_chore(ctx, reward, weekly_cap, is_active)
+    }
+
+    pub fn check_in_chore(ctx: Context<CheckInChore>, proof_uri: String) -> Result<()> {
+        instructions::chore::check_in_chore(ctx, proof_uri)
+    }
+
+    pub fn review_chore_completions<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReviewChoreCompletions<'info>>,
+        approvals: Vec<bool>,
+    ) -> Result<()> {
+        instructions::chore::review_chore_completions(ctx, approvals)
+    }
+
+    pub fn auto_approve_chore(ctx: Context<AutoApproveChore>) -> Result<()> {
+        instructions::chore::auto_approve_chore(ctx)
+    }
+
+    // DAO Governance
+    pub fn configure_dao_governance(
+        ctx: Context<ConfigureDaoGovernance>,
+        input: DaoGovernanceInput,
+    ) -> Result<()> {
+        instructions::dao::configure_dao_governance(ctx, input)
+    }
+
+    pub fn cast_dao_vote(
+        ctx: Context<CastDaoVote>,
+        subgoal_index: u8,
+        approve: bool,
+    ) -> Result<()> {
+        instructions::dao::cast_dao_vote(ctx, subgoal_index, approve)
+    }
+
+    pub fn finalize_dao_vote<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeDaoVote<'info>>,
+        subgoal_index: u8,
+    ) -> Result<()> {
+        instructions::dao::finalize_dao_vote(ctx, subgoal_index)
+    }
+
+    pub fn withdraw_dao_vote_tokens(ctx: Context<WithdrawDaoVoteTokens>) -> Result<()> {
+        instructions::dao::withdraw_dao_vote_tokens(ctx)
+    }
+
+    // Joint Savings
+    pub fn create_joint_savings(
+        ctx: Context<CreateJointSavings>,
+        savings_id: u64,
+        input: JointSavingsInput,
+    ) -> Result<()> {
+        instructions::joint::create_joint_savings(ctx, savings_id, input)
+    }
+
+    pub fn contribute_joint_savings(
+        ctx: Context<ContributeJointSavings>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::joint::contribute_joint_savings(ctx, amount)
+    }
+
+    pub fn update_joint_destination(
+        ctx: Context<UpdateJointDestination>,
+        destination: Pubkey,
+    ) -> Result<()> {
+        instructions::joint::update_joint_destination(ctx, destination)
+    }
+
+    pub fn release_joint_savings(ctx: Context<ReleaseJointSavings>) -> Result<()> {
+        instructions::joint::release_joint_savings(ctx)
+    }
+
+    pub fn withdraw_joint_savings(ctx: Context<WithdrawJointSavings>) -> Result<()> {
+        instructions::joint::withdraw_joint_savings(ctx)
+    }
+
+    pub fn refund_joint_savings(ctx: Context<RefundJointSavings>) -> Result<()> {
+        instructions::joint::refund_joint_savings(ctx)
+    }
+
+    // Beneficiary Splits
+    pub fn configure_beneficiaries(
+        ctx: Context<ConfigureBeneficiaries>,
+        input: BeneficiaryInput,
+    ) -> Result<()> {
+        instructions::beneficiary::configure_beneficiaries(ctx, input)
+    }
+
+    // Dead Man Switch
+    pub fn trigger_dead_man_switch(ctx: Context<TriggerDeadManSwitch>) -> Result<()> {
+        instructions::dead_man::trigger_dead_man_switch(ctx)
+    }
+
+    // Goal Rollover
+    pub fn rollover_goal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RolloverGoal<'info>>,
+        taker: Pubkey,
+        goal_id: i64,
+        input: RolloverGoalInput,
+    ) -> Result<()> {
+        instructions::rollover::rollover_goal(ctx, taker, goal_id, input)
+    }
+
+    // Emergency Withdrawal
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, taker_amount: u64) -> Result<()> {
+        instructions::emergency::emergency_withdraw(ctx, taker_amount)
+    }
+
+    // Goal Sponsorship
+    pub fn add_funds<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddFunds<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::sponsor::add_funds(ctx, amount)
+    }
+
+    pub fn claim_sponsor_refund(ctx: Context<ClaimSponsorRefund>) -> Result<()> {
+        instructions::sponsor::claim_sponsor_refund(ctx)
+    }
+
+    // Matching Pools
+    pub fn create_matching_pool(
+        ctx: Context<CreateMatchingPool>,
+        pool_id: i64,
+        config: MatchingPoolConfig,
+        initial_funding: u64,
+    ) -> Result<()> {
+        instructions::matching::create_matching_pool(ctx, pool_id, config, initial_funding)
+    }
+
+    pub fn fund_matching_pool(ctx: Context<FundMatchingPool>, amount: u64) -> Result<()> {
+        instructions::matching::fund_matching_pool(ctx, amount)
+    }
+
+    pub fn withdraw_matching_pool(ctx: Context<WithdrawMatchingPool>) -> Result<()> {
+        instructions::matching::withdraw_matching_pool(ctx)
+    }
+
+    pub fn reclaim_matched_funds(ctx: Context<ReclaimMatchedFunds>) -> Result<()> {
+        instructions::matching::reclaim_matched_funds(ctx)
+    }
+
+    pub fn open_matching_allowance(ctx: Context<OpenMatchingAllowance>) -> Result<()> {
+        instructions::matching::open_matching_allowance(ctx)
+    }
+
+    pub fn reclaim_bounty_match(ctx: Context<ReclaimBountyMatch>) -> Result<()> {
+        instructions::bounty::reclaim_bounty_match(ctx)
+    }
+
     // Bounty System
     pub fn publish_bounty(
         ctx: Context<PublishBounty>,
@@ -136,6 +555,10 @@ pub mod gluex {
         instructions::bounty::claim_bounty(ctx)
     }
 
+    pub fn submit_bounty_proof(ctx: Context<SubmitBountyProof>, proof_uri: String) -> Result<()> {
+        instructions::bounty::submit_bounty_proof(ctx, proof_uri)
+    }
+
     pub fn verify_and_reward_bounty(
         ctx: Context<VerifyAndRewardBounty>,
         is_approved: bool,
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use super::user_state::SubGoalInput;
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SubGoalEdit {
+    pub index: u8,
+    pub title: Option<String>,
+    pub deadline: Option<i64>,
+    pub incentive_amount: Option<u64>,
+    pub auto_release_at: Option<i64>,
+    pub deactivate: bool, // sets is_active = false, other fields are ignored
+}
+
+// Pending change to a goal's subgoals, applied only once the other party
+// accepts. One open proposal per goal.
+#[account]
+#[derive(Default, Debug)]
+pub struct SubGoalAmendment {
+    pub goal: Pubkey,
+    pub proposer: Pubkey,
+    pub edits: Vec<SubGoalEdit>,       // capped at MAX_AMENDMENT_EDITS
+    pub additions: Vec<SubGoalInput>,  // capped at MAX_AMENDMENT_ADDITIONS
+    pub base_index: u8,                // goal's subgoal count when proposed, first addition index
+    pub proposed_at: i64,
+    pub bump: u8,
+}
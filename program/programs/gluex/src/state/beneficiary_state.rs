@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use super::constraints::{BASIS_POINTS, MAX_BENEFICIARIES, MAX_BENEFICIARY_OVERRIDES};
+
+#[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct BeneficiaryShare {
+    pub wallet: Pubkey,
+    pub bps: u16, // share of each payout
+}
+
+#[derive(Debug, Default, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SubGoalBeneficiaries {
+    pub index: u8,
+    pub shares: Vec<BeneficiaryShare>,
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct BeneficiaryInput {
+    pub shares: Vec<BeneficiaryShare>,             // goal-wide split, empty pays the taker
+    pub sub_goal_shares: Vec<SubGoalBeneficiaries>, // per-subgoal splits overriding `shares`
+}
+
+// Payout split of a goal, stored at [b"goal-beneficiaries", goal]. Approved
+// subgoals pay every listed wallet its share instead of the taker alone.
+#[account]
+#[derive(Default, Debug)]
+pub struct GoalBeneficiaries {
+    pub goal: Pubkey,
+    pub shares: Vec<BeneficiaryShare>,              // capped at MAX_BENEFICIARIES
+    pub sub_goal_shares: Vec<SubGoalBeneficiaries>, // capped at MAX_BENEFICIARY_OVERRIDES
+    pub bump: u8,
+}
+
+impl BeneficiaryInput {
+    pub fn validate(&self, active_sub_goals: u8) -> bool {
+        let overrides_ok = self.sub_goal_shares.len() <= MAX_BENEFICIARY_OVERRIDES
+            && self.sub_goal_shares.iter().enumerate().all(|(idx, split)| {
+                split.index < active_sub_goals
+                    && !split.shares.is_empty()
+                    && shares_valid(&split.shares)
+                    && self.sub_goal_shares[..idx]
+                        .iter()
+                        .all(|other| other.index != split.index)
+            });
+        (self.shares.is_empty() || shares_valid(&self.shares))
+            && !(self.shares.is_empty() && self.sub_goal_shares.is_empty())
+            && overrides_ok
+    }
+}
+
+impl GoalBeneficiaries {
+    // Split applying to a subgoal; empty when it pays the taker alone.
+    pub fn shares_for(&self, index: u8) -> &[BeneficiaryShare] {
+        self.sub_goal_shares
+            .iter()
+            .find(|split| split.index == index)
+            .map_or(&self.shares, |split| &split.shares)
+    }
+}
+
+// Shares must name distinct wallets and add up to exactly 100%.
+fn shares_valid(shares: &[BeneficiaryShare]) -> bool {
+    let total: u64 = shares.iter().map(|share| share.bps as u64).sum();
+    let unique = shares
+        .iter()
+        .enumerate()
+        .all(|(idx, share)| shares[..idx].iter().all(|other| other.wallet != share.wallet));
+    shares.len() <= MAX_BENEFICIARIES
+        && unique
+        && shares
+            .iter()
+            .all(|share| share.bps > 0 && share.wallet != Pubkey::default())
+        && total == BASIS_POINTS
+}
+
+// Splits `amount` by basis points; rounding dust goes to the last share.
+pub fn split_amount(amount: u64, shares: &[BeneficiaryShare]) -> Vec<u64> {
+    let mut amounts: Vec<u64> = shares
+        .iter()
+        .map(|share| ((amount as u128) * (share.bps as u128) / (BASIS_POINTS as u128)) as u64)
+        .collect();
+    let distributed: u64 = amounts.iter().sum();
+    if let Some(last) = amounts.last_mut() {
+        *last = last.saturating_add(amount.saturating_sub(distributed));
+    }
+    amounts
+}
@@ -13,6 +13,11 @@ pub struct OpenBounty {
     pub current_claims: u16,
     pub is_active: bool,
     pub bump: u8,
+    pub matching_pool: Pubkey,
+    pub matched_amount: u64,
+    pub indexed: bool,
+    pub approved_claims: u16,
+    pub matching_refunded: bool,
 }
 
 #[account]
@@ -25,4 +30,5 @@ pub struct BountyExecution {
     pub is_rejected: bool,
     pub proof_uri: String,
     pub bump: u8,
+    pub indexed: bool,
 }
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+
+use super::constraints::{MAX_CHALLENGE_CHECKPOINTS, MAX_PROOF_URI_LENGTH};
+use super::user_state_impl::low_bits;
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct GroupChallengeInput {
+    pub description: String,
+    pub stake_amount: u64,     // every participant stakes the same amount
+    pub checkpoints: Vec<i64>, // proof deadlines, ascending
+    pub join_deadline: i64,    // joining closes here, before the first checkpoint
+    pub settle_time: i64,      // reviews close and the pot is split from here
+    pub max_participants: u16,
+    pub fee_pool: Pubkey, // fee pool the pot pays into, default for no fee
+}
+
+// Shared pot for the GroupGame room. Participants stake into the challenge
+// PDA and prove the same checkpoints; at settlement the pot, minus the
+// protocol fee, is split among participants who completed every checkpoint.
+#[account]
+#[derive(Default, Debug)]
+pub struct GroupChallenge {
+    pub organizer: Pubkey, // reviews proofs
+    pub challenge_id: u64,
+    pub description: String,
+    pub stake_amount: u64,
+    pub checkpoints: Vec<i64>, // capped at MAX_CHALLENGE_CHECKPOINTS
+    pub join_deadline: i64,
+    pub settle_time: i64,
+    pub max_participants: u16,
+    pub participants: u16,
+    pub finishers: u16,     // participants with every checkpoint approved
+    pub claimed: u16,       // participant records closed after settlement
+    pub pot: u64,           // stakes held by the challenge
+    pub fee_pool: Pubkey,
+    pub settled: bool,
+    pub payout_per_finisher: u64, // 0 when nobody finished and stakes are refunded
+    pub bump: u8,
+}
+
+// One participant's stake and checkpoint progress, stored at
+// [b"challenge-participant", challenge, participant].
+#[account]
+#[derive(Debug)]
+pub struct ChallengeParticipant {
+    pub challenge: Pubkey,
+    pub participant: Pubkey,
+    pub stake: u64,
+    pub submitted_mask: u64, // checkpoints with a proof awaiting review
+    pub approved_mask: u64,  // checkpoints approved by the organizer
+    pub proof_uris: [[u8; MAX_PROOF_URI_LENGTH]; MAX_CHALLENGE_CHECKPOINTS],
+    pub completed: bool,
+    pub bump: u8,
+}
+
+impl GroupChallengeInput {
+    pub fn validate(&self, now: i64) -> bool {
+        let count = self.checkpoints.len();
+        let ascending = self.checkpoints.windows(2).all(|pair| pair[0] < pair[1]);
+        let first = self.checkpoints.first().copied().unwrap_or(0);
+        let last = self.checkpoints.last().copied().unwrap_or(0);
+        count > 0
+            && count <= MAX_CHALLENGE_CHECKPOINTS
+            && ascending
+            && self.stake_amount > 0
+            && self.max_participants >= 2
+            && self.join_deadline > now
+            && first > self.join_deadline
+            && self.settle_time >= last
+    }
+}
+
+impl GroupChallenge {
+    pub fn all_checkpoints(&self) -> u64 {
+        low_bits(self.checkpoints.len())
+    }
+
+    // Lamports owed to a participant once the challenge is settled: finishers
+    // split the pot, and if nobody finished every stake is returned.
+    pub fn payout_for(&self, participant: &ChallengeParticipant) -> u64 {
+        if self.finishers == 0 {
+            participant.stake
+        } else if participant.completed {
+            self.payout_per_finisher
+        } else {
+            0
+        }
+    }
+}
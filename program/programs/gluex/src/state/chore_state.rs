@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+use super::constraints::{CHORE_WEEK_SECONDS, MAX_PROOF_URI_LENGTH, MAX_SUBGOAL_TITLE_LENGTH};
+use super::user_state::SubGoalStatus;
+
+// Allowance account a parent funds for one child. Chores pay out of it per
+// approved completion.
+#[account]
+#[derive(Default, Debug)]
+pub struct ChoreBoard {
+    pub parent: Pubkey,
+    pub child: Pubkey,
+    pub created_at: i64,          // weeks are counted from here
+    pub auto_approve_after: i64,  // seconds before an unreviewed check-in approves itself
+    pub deposited: u64,
+    pub released: u64,
+    pub pending_rewards: u64,     // rewards of check-ins awaiting review
+    pub chore_count: u8,
+    pub bump: u8,
+}
+
+// Recurring chore on a board, stored at [b"chore", board, index].
+#[account]
+#[derive(Debug)]
+pub struct Chore {
+    pub board: Pubkey,
+    pub index: u8,
+    pub title: [u8; MAX_SUBGOAL_TITLE_LENGTH],
+    pub reward: u64,     // paid per approved completion
+    pub weekly_cap: u8,  // check-ins accepted per week
+    pub week: u32,       // week `week_count` refers to
+    pub week_count: u8,  // check-ins this week, pending or paid
+    pub completions: u32, // check-ins so far, used to derive completion ids
+    pub is_active: bool,
+    pub bump: u8,
+}
+
+// One check-in, stored at [b"chore-completion", chore, id]. Follows the
+// subgoal lifecycle: ProofSubmitted until reviewed, then Paid or Rejected.
+#[account]
+#[derive(Debug)]
+pub struct ChoreCompletion {
+    pub chore: Pubkey,
+    pub id: u32,
+    pub week: u32,
+    pub reward: u64, // chore reward when checked in
+    pub status: SubGoalStatus,
+    pub proof_uri: [u8; MAX_PROOF_URI_LENGTH], // optional photo proof
+    pub submitted_at: i64,
+    pub bump: u8,
+}
+
+impl ChoreBoard {
+    pub fn week_at(&self, now: i64) -> u32 {
+        (now.saturating_sub(self.created_at).max(0) / CHORE_WEEK_SECONDS) as u32
+    }
+
+    // Balance not yet paid out or promised to pending check-ins.
+    pub fn available(&self) -> u64 {
+        self.deposited
+            .saturating_sub(self.released)
+            .saturating_sub(self.pending_rewards)
+    }
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use solana_program::hash::hashv;
+
+use super::constraints::MAX_CIRCLE_MEMBERS;
+use super::user_state::Relations;
+use super::user_state_impl::low_bits;
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SavingsCircleInput {
+    pub relations: Relations,
+    pub members: Vec<Pubkey>,
+    pub contribution_amount: u64, // paid by every member each round
+    pub collateral_amount: u64,   // posted on joining, covers missed contributions
+    pub period: i64,              // seconds per round
+    pub start_time: i64,          // round 0 opens here
+    pub payout_order: Vec<u8>,    // member indices by round, empty to randomize
+}
+
+// Rotating savings circle: each round every member contributes a fixed
+// amount and the round pot goes to one member, until every member has been
+// paid once. Missed contributions are covered from the member's collateral.
+#[account]
+#[derive(Default, Debug)]
+pub struct SavingsCircle {
+    pub organizer: Pubkey,
+    pub circle_id: u64,
+    pub relations: Relations,
+    pub members: Vec<Pubkey>,    // capped at MAX_CIRCLE_MEMBERS
+    pub payout_order: Vec<u8>,   // member indices by round, fixed on start
+    pub collateral: Vec<u64>,    // collateral left per member
+    pub missed: Vec<u8>,         // missed contributions per member
+    pub contribution_amount: u64,
+    pub collateral_amount: u64,
+    pub period: i64,
+    pub start_time: i64,
+    pub joined_mask: u16,        // members that posted collateral
+    pub draw_slot: u64,          // slot whose hash draws the order, fixed when the last member joins
+    pub contributions: Vec<u16>, // members that paid in, per round
+    pub current_round: u8,       // next round to pay out
+    pub started: bool,
+    pub bump: u8,
+}
+
+impl SavingsCircleInput {
+    pub fn validate(&self, now: i64) -> bool {
+        let count = self.members.len();
+        let unique = self
+            .members
+            .iter()
+            .enumerate()
+            .all(|(idx, member)| !self.members[..idx].contains(member));
+        let order_ok = self.payout_order.is_empty() || is_permutation(&self.payout_order, count);
+        (2..=MAX_CIRCLE_MEMBERS).contains(&count)
+            && unique
+            && order_ok
+            && self.contribution_amount > 0
+            && self.collateral_amount > 0
+            && self.period > 0
+            && self.start_time > now
+    }
+}
+
+impl SavingsCircle {
+    pub fn rounds(&self) -> u8 {
+        self.members.len() as u8
+    }
+
+    pub fn all_members(&self) -> u16 {
+        low_bits(self.members.len()) as u16
+    }
+
+    pub fn member_index(&self, member: &Pubkey) -> Option<usize> {
+        self.members.iter().position(|key| key == member)
+    }
+
+    pub fn round_end(&self, round: u8) -> i64 {
+        self.start_time
+            .saturating_add(self.period.saturating_mul(round as i64 + 1))
+    }
+
+    // Round whose contribution window contains `now`.
+    pub fn round_at(&self, now: i64) -> u8 {
+        let elapsed = now.saturating_sub(self.start_time).max(0);
+        (elapsed / self.period.max(1)).min(u8::MAX as i64) as u8
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.started && self.current_round >= self.rounds()
+    }
+}
+
+pub fn is_permutation(order: &[u8], count: usize) -> bool {
+    let mut seen: u64 = 0;
+    order.len() == count
+        && order.iter().all(|idx| {
+            let bit = 1u64 << (*idx as usize).min(63);
+            let fresh = (*idx as usize) < count && seen & bit == 0;
+            seen |= bit;
+            fresh
+        })
+}
+
+// Fisher-Yates shuffle of member indices seeded by a recent slot hash and
+// the circle address.
+pub fn shuffled_order(count: usize, slot_hash: &[u8], circle: &Pubkey) -> Vec<u8> {
+    let mut order: Vec<u8> = (0..count as u8).collect();
+    let mut entropy = hashv(&[slot_hash, circle.as_ref()]).to_bytes();
+    for idx in (1..count).rev() {
+        entropy = hashv(&[&entropy]).to_bytes();
+        let mut seed = [0u8; 8];
+        seed.copy_from_slice(&entropy[..8]);
+        let swap = (u64::from_le_bytes(seed) % (idx as u64 + 1)) as usize;
+        order.swap(idx, swap);
+    }
+    order
+}
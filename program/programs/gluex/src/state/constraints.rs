@@ -1,11 +1,18 @@
-pub const MAXIUMUN_SUBGOALS: usize = 5;
+use super::user_state::TotalGoal;
+use anchor_lang::prelude::*;
+
+pub const MAXIUMUN_SUBGOALS: usize = 64; // settled/prerequisite bitmasks are u64
+pub const LEGACY_SUBGOAL_SLOTS: usize = 5; // inline subgoal array before SubGoalAccount
 pub const MAXIUMUN_DESCRIPTIONS_LENS: usize = 512;
 pub const MAXIUMUN_SUBGOAL_AMOUNT: u64 = 2000;
 pub const MAX_SUBGOAL_TITLE_LENGTH: usize = 48;
 pub const MAX_PROOF_URI_LENGTH: usize = 128;
+pub const MAX_SEALED_REVEAL_LENGTH: usize = 128;
+pub const BASIS_POINTS: u64 = 10_000;
 pub const HABIT_CHECKPOINTS: usize = 3;
 pub const HABIT_INTERVAL_SECONDS: i64 = 7 * 24 * 60 * 60;
-pub const MAX_DESCRIPTION_BYTES: usize = 512;
+pub const MAX_DESCRIPTION_BYTES: usize = MAXIUMUN_DESCRIPTIONS_LENS; // inline goal description, localized text lives in GoalMetadata
+pub const SLOT_HASHES_SYSVAR: Pubkey = pubkey!("SysvarS1otHashes111111111111111111111111111");
 pub const SUB_GOAL_SIZE: usize = MAX_SUBGOAL_TITLE_LENGTH
     + 8 // deadline
     + 8 // incentive amount
@@ -15,17 +22,55 @@ pub const SUB_GOAL_SIZE: usize = MAX_SUBGOAL_TITLE_LENGTH
     + 32 // verifier pubkey
     + 8 // auto release
     + 1; // is_active
-pub const GOAL_ACCOUNT_BASE_SIZE: usize = 32 // issuer
-    + 32 // taker
-    + 4 // description string prefix
-    + MAX_DESCRIPTION_BYTES
-    + 3 // enums room/relations/eventype
-    + 72 // u64 fields (9 * 8 bytes)
-    + 4 // misc u8/bool counters
-    + 64; // padding / future use
+// zero-copy layout, see TotalGoal for the field order and padding
 pub const GOAL_ACCOUNT_SPACE: usize = 8 // anchor discriminator
-    + GOAL_ACCOUNT_BASE_SIZE
-    + (SUB_GOAL_SIZE * MAXIUMUN_SUBGOALS);
+    + core::mem::size_of::<TotalGoal>();
+
+// TotalGoal has its own discriminator so goals in the original
+// inline-subgoal layout, which used Anchor's default, never decode as the
+// current layout
+pub const GOAL_ACCOUNT_DISCRIMINATOR: &[u8] = b"gluexgl3";
+
+pub const SUB_GOAL_ACCOUNT_SPACE: usize = 8 // anchor discriminator
+    + 32 // goal
+    + 1 // index
+    + SUB_GOAL_SIZE
+    + 8 // prerequisite mask
+    + 1; // bump
+
+pub const GOAL_SPONSOR_SPACE: usize = 8 // anchor discriminator
+    + 32 // goal
+    + 32 // sponsor
+    + 8 // contributed amount
+    + 1 // refunded
+    + 1; // bump
+
+// subgoal amendment related constraints
+pub const MAX_AMENDMENT_EDITS: usize = 8;
+pub const MAX_AMENDMENT_ADDITIONS: usize = 4;
+pub const MAX_AMENDMENT_PREREQUISITES: usize = 8;
+
+pub const SUB_GOAL_EDIT_SIZE: usize = 1 // index
+    + (1 + 4 + MAX_SUBGOAL_TITLE_LENGTH) // optional title
+    + (1 + 8) // optional deadline
+    + (1 + 8) // optional incentive amount
+    + (1 + 8) // optional auto release
+    + 1; // deactivate
+
+pub const SUB_GOAL_INPUT_SIZE: usize = (4 + MAX_SUBGOAL_TITLE_LENGTH) // title
+    + 8 // deadline
+    + 8 // incentive amount
+    + 8 // auto release
+    + (4 + MAX_AMENDMENT_PREREQUISITES); // prerequisites
+
+pub const SUB_GOAL_AMENDMENT_SPACE: usize = 8 // anchor discriminator
+    + 32 // goal
+    + 32 // proposer
+    + 4 + (SUB_GOAL_EDIT_SIZE * MAX_AMENDMENT_EDITS) // edits
+    + 4 + (SUB_GOAL_INPUT_SIZE * MAX_AMENDMENT_ADDITIONS) // additions
+    + 1 // base index
+    + 8 // proposed at
+    + 1; // bump
 
 // fee pool related constraints
 pub const MAX_MAINTAINERS: usize = 10;
@@ -44,3 +89,253 @@ pub const FEE_POOL_SPACE: usize = 8 // anchor discriminator
     + 1 // bump
     + 4 // donations vec length prefix
     + (DONATION_ENTRY_SIZE * MAX_DONATIONS); // donations fixed capacity
+
+// matching pool related constraints
+pub const MATCHING_POOL_SPACE: usize = 8 // anchor discriminator
+    + 32 // sponsor
+    + 8 // pool id
+    + 2 // ratio bps
+    + 8 // per goal cap
+    + 8 // per bounty cap
+    + 8 // per issuer cap
+    + 1 // relations mask
+    + 1 // event types mask
+    + 8 // min reputation
+    + 8 // expires at
+    + 8 // total funded
+    + 8 // total matched
+    + 1; // bump
+
+pub const MATCHING_ALLOWANCE_SPACE: usize = 8 // anchor discriminator
+    + 32 // pool
+    + 32 // issuer
+    + 8 // matched amount
+    + 1; // bump
+
+// user registry related constraints
+pub const USER_INDEX_PAGE_SIZE: usize = 32;
+
+pub const USER_GOAL_INDEX_SPACE: usize = 8 // anchor discriminator
+    + 32 // owner
+    + 8 // next goal id
+    + (4 * 4) // per-kind counters
+    + 1; // bump
+
+pub const USER_INDEX_PAGE_SPACE: usize = 8 // anchor discriminator
+    + 32 // owner
+    + 1 // kind
+    + 4 // page number
+    + 4 // entries vec length prefix
+    + (32 * USER_INDEX_PAGE_SIZE) // entries
+    + 1; // bump
+
+// goal metadata related constraints
+pub const MAX_METADATA_URI_LENGTH: usize = 200;
+
+pub const GOAL_METADATA_SPACE: usize = 8 // anchor discriminator
+    + 32 // goal
+    + 4 + MAX_METADATA_URI_LENGTH // uri
+    + 32 // content hash
+    + 4 // revision
+    + 8 // updated_at
+    + 1; // bump
+
+// goal template related constraints
+pub const MAX_TEMPLATE_SUBGOALS: usize = 16;
+pub const MAX_TEMPLATE_PREREQUISITES: usize = 8;
+
+pub const TEMPLATE_SUB_GOAL_SIZE: usize = (4 + MAX_SUBGOAL_TITLE_LENGTH) // title
+    + 8 // deadline offset
+    + 2 // incentive bps
+    + 8 // auto release offset
+    + (4 + MAX_TEMPLATE_PREREQUISITES); // prerequisites
+
+pub const GOAL_TEMPLATE_SPACE: usize = 8 // anchor discriminator
+    + 32 // creator
+    + 8 // template id
+    + (4 + MAX_DESCRIPTION_BYTES) // description
+    + 3 // room, relations, eventype
+    + 4 + (MAX_TEMPLATE_SUBGOALS * TEMPLATE_SUB_GOAL_SIZE) // subgoals
+    + 8 // duration
+    + 8 // unlock delay
+    + 2 // locked bps
+    + 8 // checkpoint interval
+    + 8 // surprise offset
+    + 1 // sequential
+    + (1 + 8 + 1 + 2 + 8 + 2) // optional late penalty
+    + 32 // fee pool
+    + 32 // verifier
+    + 8 // instances
+    + 1; // bump
+
+// batch instructions are bounded by transaction size and compute
+pub const MAX_BATCH_GOALS: usize = 10;
+
+// group challenge related constraints
+pub const MAX_CHALLENGE_CHECKPOINTS: usize = 8;
+
+pub const GROUP_CHALLENGE_SPACE: usize = 8 // anchor discriminator
+    + 32 // organizer
+    + 8 // challenge id
+    + (4 + MAXIUMUN_DESCRIPTIONS_LENS) // description
+    + 8 // stake amount
+    + 4 + (MAX_CHALLENGE_CHECKPOINTS * 8) // checkpoints
+    + 8 // join deadline
+    + 8 // settle time
+    + 2 // max participants
+    + 2 // participants
+    + 2 // finishers
+    + 2 // claimed
+    + 8 // pot
+    + 32 // fee pool
+    + 1 // settled
+    + 8 // payout per finisher
+    + 1; // bump
+
+pub const CHALLENGE_PARTICIPANT_SPACE: usize = 8 // anchor discriminator
+    + 32 // challenge
+    + 32 // participant
+    + 8 // stake
+    + 8 // submitted mask
+    + 8 // approved mask
+    + (MAX_CHALLENGE_CHECKPOINTS * MAX_PROOF_URI_LENGTH) // proof uris
+    + 1 // completed
+    + 1; // bump
+
+// savings circle related constraints
+pub const MAX_CIRCLE_MEMBERS: usize = 12; // one round per member
+pub const CIRCLE_DRAW_SLOT_DELAY: u64 = 4; // draw slot is this far past the slot the circle filled
+
+pub const SAVINGS_CIRCLE_SPACE: usize = 8 // anchor discriminator
+    + 32 // organizer
+    + 8 // circle id
+    + 1 // relations
+    + 4 + (MAX_CIRCLE_MEMBERS * 32) // members
+    + 4 + MAX_CIRCLE_MEMBERS // payout order
+    + 4 + (MAX_CIRCLE_MEMBERS * 8) // collateral
+    + 4 + MAX_CIRCLE_MEMBERS // missed
+    + 8 // contribution amount
+    + 8 // collateral amount
+    + 8 // period
+    + 8 // start time
+    + 2 // joined mask
+    + 8 // draw slot
+    + 4 + (MAX_CIRCLE_MEMBERS * 2) // contributions
+    + 1 // current round
+    + 1 // started
+    + 1; // bump
+
+// chore board related constraints
+pub const CHORE_WEEK_SECONDS: i64 = 7 * 24 * 60 * 60;
+pub const MAX_CHORE_REVIEWS: usize = 10;
+
+pub const CHORE_BOARD_SPACE: usize = 8 // anchor discriminator
+    + 32 // parent
+    + 32 // child
+    + 8 // created at
+    + 8 // auto approve after
+    + 8 // deposited
+    + 8 // released
+    + 8 // pending rewards
+    + 1 // chore count
+    + 1; // bump
+
+pub const CHORE_SPACE: usize = 8 // anchor discriminator
+    + 32 // board
+    + 1 // index
+    + MAX_SUBGOAL_TITLE_LENGTH // title
+    + 8 // reward
+    + 1 // weekly cap
+    + 4 // week
+    + 1 // week count
+    + 4 // completions
+    + 1 // is active
+    + 1; // bump
+
+pub const CHORE_COMPLETION_SPACE: usize = 8 // anchor discriminator
+    + 32 // chore
+    + 4 // id
+    + 4 // week
+    + 8 // reward
+    + 1 // status
+    + MAX_PROOF_URI_LENGTH // proof uri
+    + 8 // submitted at
+    + 1; // bump
+
+// dao governance related constraints
+pub const MAX_DAO_MEMBERS: usize = 32;
+pub const SPL_TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const SPL_TOKEN_ACCOUNT_LEN: usize = 165;
+
+pub const DAO_GOVERNANCE_SPACE: usize = 8 // anchor discriminator
+    + 32 // goal
+    + 1 // mode
+    + 32 // membership mint
+    + 4 + (MAX_DAO_MEMBERS * 32) // members
+    + 8 // quorum
+    + 2 // approval bps
+    + 8 // voting period
+    + 1; // bump
+
+pub const DAO_TALLY_SPACE: usize = 8 // anchor discriminator
+    + 32 // sub goal
+    + 8 // round
+    + 8 // yes weight
+    + 8 // no weight
+    + 4 // voters
+    + 1; // bump
+
+pub const DAO_VOTE_RECORD_SPACE: usize = 8 // anchor discriminator
+    + 32 // sub goal
+    + 32 // voter
+    + 8 // round
+    + 1 // approve
+    + 8 // weight
+    + 1; // bump
+
+// joint savings related constraints
+pub const JOINT_SAVINGS_SPACE: usize = 8 // anchor discriminator
+    + 32 // initiator
+    + 32 // partner
+    + 8 // savings id
+    + 1 // relations
+    + 32 // destination
+    + 8 // target amount
+    + 8 // target date
+    + 8 // contribution amount
+    + 8 // period
+    + 8 // start time
+    + (2 * 8) // contributed
+    + 1; // bump
+
+// beneficiary split related constraints
+pub const MAX_BENEFICIARIES: usize = 4;
+pub const MAX_BENEFICIARY_OVERRIDES: usize = 8;
+pub const BENEFICIARY_SHARE_SIZE: usize = 32 // wallet
+    + 2; // bps
+
+pub const GOAL_BENEFICIARIES_SPACE: usize = 8 // anchor discriminator
+    + 32 // goal
+    + 4 + (MAX_BENEFICIARIES * BENEFICIARY_SHARE_SIZE) // shares
+    + 4 + (MAX_BENEFICIARY_OVERRIDES * (1 + 4 + MAX_BENEFICIARIES * BENEFICIARY_SHARE_SIZE)) // sub goal shares
+    + 1; // bump
+
+// dead man switch related constraints
+pub const MAX_KEEPER_TIP_BPS: u16 = 100; // 1% of the released funds
+
+// goal rollover related constraints
+pub const GOAL_ROLLOVER_SPACE: usize = 8 // anchor discriminator
+    + 32 // predecessor
+    + 32 // successor
+    + 8 // amount
+    + 8 // rolled at
+    + 1; // bump
+
+// recurring goal related constraints
+pub const RECURRENCE_REVIEW_GRACE_SECONDS: i64 = 3 * 24 * 60 * 60; // review time for proofs pending at period end
+
+// group challenge related constraints
+pub const CHALLENGE_CLAIM_PERIOD_SECONDS: i64 = 30 * 24 * 60 * 60; // claims stay open this long after settle_time
+
+// randomized surprise related constraints
+pub const SURPRISE_REVEAL_SLOT_DELAY: u64 = 4; // reveal slot is this far past the setup slot
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use super::constraints::{BASIS_POINTS, MAX_DAO_MEMBERS};
+
+#[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum DaoVoteMode {
+    #[default]
+    MemberList = 1, // one vote per listed member
+    TokenWeighted,  // weight is the voter's balance of the membership mint
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct DaoGovernanceInput {
+    pub mode: DaoVoteMode,
+    pub membership_mint: Pubkey, // TokenWeighted only
+    pub members: Vec<Pubkey>,    // MemberList only
+    pub quorum: u64,             // votes (or tokens) that must be cast
+    pub approval_bps: u16,       // share of cast weight needed to approve
+    pub voting_period: i64,      // seconds after submit_proof votes are accepted
+}
+
+// Voting rules for a Relations::Dao goal, stored at [b"dao-governance", goal].
+// Once attached, subgoals are approved or rejected by vote instead of review.
+#[account]
+#[derive(Default, Debug)]
+pub struct DaoGovernance {
+    pub goal: Pubkey,
+    pub mode: DaoVoteMode,
+    pub membership_mint: Pubkey,
+    pub members: Vec<Pubkey>, // capped at MAX_DAO_MEMBERS
+    pub quorum: u64,
+    pub approval_bps: u16,
+    pub voting_period: i64,
+    pub bump: u8,
+}
+
+// Running tally for one subgoal, stored at [b"dao-tally", sub_goal]. Reset
+// whenever a new proof opens a new voting round.
+#[account]
+#[derive(Default, Debug)]
+pub struct DaoTally {
+    pub sub_goal: Pubkey,
+    pub round: i64, // submitted_at of the proof being voted on
+    pub yes_weight: u64,
+    pub no_weight: u64,
+    pub voters: u32,
+    pub bump: u8,
+}
+
+// One member's vote in one round, stored at
+// [b"dao-vote", sub_goal, voter, round] so nobody votes twice per proof.
+#[account]
+#[derive(Default, Debug)]
+pub struct DaoVoteRecord {
+    pub sub_goal: Pubkey,
+    pub voter: Pubkey,
+    pub round: i64,
+    pub approve: bool,
+    pub weight: u64,
+    pub bump: u8,
+}
+
+impl DaoGovernanceInput {
+    pub fn validate(&self) -> bool {
+        let members_ok = match self.mode {
+            DaoVoteMode::MemberList => {
+                !self.members.is_empty()
+                    && self.members.len() <= MAX_DAO_MEMBERS
+                    && self.quorum <= self.members.len() as u64
+            }
+            DaoVoteMode::TokenWeighted => {
+                self.members.is_empty() && self.membership_mint != Pubkey::default()
+            }
+        };
+        members_ok
+            && self.quorum > 0
+            && self.approval_bps > 0
+            && (self.approval_bps as u64) <= BASIS_POINTS
+            && self.voting_period > 0
+    }
+}
+
+impl DaoTally {
+    pub fn approved(&self, governance: &DaoGovernance) -> bool {
+        let cast = self.yes_weight.saturating_add(self.no_weight);
+        cast >= governance.quorum
+            && (self.yes_weight as u128) * (BASIS_POINTS as u128)
+                >= (governance.approval_bps as u128) * (cast as u128)
+    }
+}
@@ -16,7 +16,7 @@ pub enum GluXError {
     #[msg("EventType type is not supported")]
     EventTypeNotSupport,
 
-    #[msg("SubGoal number too many,maximum is five target")]
+    #[msg("SubGoal number too many,maximum is 64 targets")]
     SubGoalNumExceed,
 
     #[msg(
@@ -91,4 +91,226 @@ pub enum GluXError {
 
     #[msg("Agent profile cannot refer itself")]
     SelfReferralNotAllowed,
+
+    #[msg("Surprise window is invalid")]
+    SurpriseWindowInvalid,
+
+    #[msg("Revealed secret does not match the surprise commitment")]
+    SurpriseCommitmentMismatch,
+
+    #[msg("Surprise time already revealed")]
+    SurpriseAlreadyRevealed,
+
+    #[msg("Surprise window already opened, reveal is closed")]
+    SurpriseRevealClosed,
+
+    #[msg("Slot hashes sysvar is unavailable")]
+    SlotHashesUnavailable,
+
+    #[msg("Sealed message is invalid")]
+    SealedMessageInvalid,
+
+    #[msg("Revealed message does not match the sealed commitment")]
+    SealedMessageMismatch,
+
+    #[msg("Sealed message can only be opened after the surprise is paid")]
+    SealedMessageLocked,
+
+    #[msg("Subgoal prerequisite index is invalid")]
+    SubGoalPrerequisiteInvalid,
+
+    #[msg("Subgoal prerequisites contain a cycle")]
+    SubGoalDependencyCycle,
+
+    #[msg("Subgoal prerequisites are not paid yet")]
+    SubGoalPrerequisitesPending,
+
+    #[msg("Late penalty configuration is invalid")]
+    LatePenaltyInvalid,
+
+    #[msg("Subgoal deadline and grace period have passed")]
+    SubmissionWindowClosed,
+
+    #[msg("Goal no longer accepts additional funds")]
+    FundingClosed,
+
+    #[msg("Refund already claimed")]
+    RefundAlreadyClaimed,
+
+    #[msg("Matching pool configuration is invalid")]
+    MatchingPoolConfigInvalid,
+
+    #[msg("Matching pool has expired")]
+    MatchingPoolExpired,
+
+    #[msg("Matching pool has not expired yet")]
+    MatchingPoolActive,
+
+    #[msg("Not eligible for this matching pool")]
+    MatchingPoolIneligible,
+
+    #[msg("No matching transfer proposal for this signer")]
+    TransferNotProposed,
+
+    #[msg("Goal transfer target is invalid")]
+    TransferTargetInvalid,
+
+    #[msg("Goal id falls inside the range allocated by the user index")]
+    GoalIdMismatch,
+
+    #[msg("Entry already recorded in the user index")]
+    AlreadyIndexed,
+
+    #[msg("Index kind does not match the supplied accounts")]
+    IndexKindMismatch,
+
+    #[msg("Subgoal account does not match the goal or index")]
+    SubGoalAccountMismatch,
+
+    #[msg("Goal account is not in the legacy inline-subgoal layout")]
+    LegacyGoalLayoutExpected,
+
+    #[msg("Subgoal amendment is invalid")]
+    AmendmentInvalid,
+
+    #[msg("Only the counterparty can accept this amendment")]
+    AmendmentUnauthorized,
+
+    #[msg("Amended incentives exceed the unreleased goal balance")]
+    AmendmentExceedsBalance,
+
+    #[msg("Goal metadata URI is empty or too long, or the content hash is missing")]
+    MetadataInvalid,
+
+    #[msg("Goal template is invalid")]
+    TemplateInvalid,
+
+    #[msg("Goal template does not match the creator or fee pool")]
+    TemplateMismatch,
+
+    #[msg("Batch is empty, too large, or its accounts do not match the takers")]
+    BatchInvalid,
+
+    #[msg("Recurrence settings are invalid for this goal")]
+    RecurrenceInvalid,
+
+    #[msg("Current period is still open")]
+    RecurrenceNotDue,
+
+    #[msg("All configured periods have run")]
+    RecurrenceExhausted,
+
+    #[msg("Goal balance cannot fund the next period")]
+    RecurrenceUnderfunded,
+
+    #[msg("Streaming settings are invalid or the goal does not stream")]
+    StreamInvalid,
+
+    #[msg("Stream has already been halted")]
+    StreamHalted,
+
+    #[msg("Group challenge settings are invalid")]
+    ChallengeInvalid,
+
+    #[msg("Group challenge is full or no longer open for this action")]
+    ChallengeClosed,
+
+    #[msg("Group challenge has not been settled yet")]
+    ChallengeUnsettled,
+
+    #[msg("Savings circle settings are invalid")]
+    CircleInvalid,
+
+    #[msg("Signer is not a member of this savings circle")]
+    CircleNotMember,
+
+    #[msg("Savings circle is not open for this action yet")]
+    CircleNotReady,
+
+    #[msg("Contribution for this round is closed or already made")]
+    CircleContributionClosed,
+
+    #[msg("Chore settings are invalid or the chore is inactive")]
+    ChoreInvalid,
+
+    #[msg("Chore has reached its weekly cap")]
+    ChoreCapReached,
+
+    #[msg("Allowance balance cannot cover this chore reward")]
+    ChoreUnderfunded,
+
+    #[msg("Check-in is still within its review window")]
+    ChoreReviewPending,
+
+    #[msg("DAO governance settings are invalid for this goal")]
+    DaoConfigInvalid,
+
+    #[msg("Subgoals of DAO-governed goals are decided by vote")]
+    DaoGoverned,
+
+    #[msg("Voter is not eligible under the DAO governance rules")]
+    DaoVoterIneligible,
+
+    #[msg("Voting window is closed")]
+    DaoVotingClosed,
+
+    #[msg("Voting window is still open")]
+    DaoVotingOpen,
+
+    #[msg("Joint savings settings are invalid")]
+    JointSavingsInvalid,
+
+    #[msg("Signer is not a partner of this joint savings goal")]
+    JointSavingsNotPartner,
+
+    #[msg("Joint savings goal no longer accepts contributions")]
+    JointSavingsClosed,
+
+    #[msg("Joint savings target has not been reached")]
+    JointSavingsTargetPending,
+
+    #[msg("Beneficiary split is invalid for this goal")]
+    BeneficiariesInvalid,
+
+    #[msg("Beneficiary accounts do not match the stored split")]
+    BeneficiaryMismatch,
+
+    #[msg("Dead man switch settings are invalid")]
+    DeadManSwitchInvalid,
+
+    #[msg("Dead man switch is not configured or its grace period is still running")]
+    DeadManSwitchNotReady,
+
+    #[msg("Goal cannot be rolled over")]
+    RolloverInvalid,
+
+    #[msg("Goal was closed by an emergency withdrawal")]
+    GoalEmergencyExited,
+
+    #[msg("Emergency split exceeds the remaining funds")]
+    EmergencySplitInvalid,
+
+    #[msg("Goal account is smaller than the current layout")]
+    GoalAccountTooSmall,
+
+    #[msg("Bounty proof is empty or too long")]
+    BountyProofInvalid,
+
+    #[msg("Bounty deadline has not passed")]
+    BountyDeadlineNotReached,
+
+    #[msg("Unspent funds are being refunded; no further payouts")]
+    GoalRefundStarted,
+
+    #[msg("Contribution does not cover the amount due under the schedule")]
+    JointContributionBelowSchedule,
+
+    #[msg("Beneficiary wallet must hold at least the rent-exempt minimum")]
+    BeneficiaryWalletUnfunded,
+
+    #[msg("Surprise reveal slot has not passed yet")]
+    SurpriseRevealSlotPending,
+
+    #[msg("Goal is not listed in the supplied user index page")]
+    IndexEntryMissing,
 }
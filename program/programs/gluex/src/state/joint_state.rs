@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+use super::user_state::Relations;
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct JointSavingsInput {
+    pub relations: Relations,
+    pub destination: Pubkey,      // receives the savings once the target is met
+    pub target_amount: u64,
+    pub target_date: i64,
+    pub contribution_amount: u64, // expected from each partner every period
+    pub period: i64,              // seconds between scheduled contributions
+    pub start_time: i64,          // first contribution is due here
+}
+
+// Shared savings pot for a couple, stored at
+// [b"joint-savings", initiator, partner, savings_id]. Both partners deposit
+// on a schedule; contributions are tracked per side so an early withdrawal
+// or a missed target refunds each partner what they put in.
+#[account]
+#[derive(Default, Debug)]
+pub struct JointSavings {
+    pub initiator: Pubkey,
+    pub partner: Pubkey,
+    pub savings_id: u64,
+    pub relations: Relations,
+    pub destination: Pubkey,
+    pub target_amount: u64,
+    pub target_date: i64,
+    pub contribution_amount: u64,
+    pub period: i64,
+    pub start_time: i64,
+    pub contributed: [u64; 2], // by initiator, then partner
+    pub bump: u8,
+}
+
+impl JointSavingsInput {
+    pub fn validate(&self, now: i64) -> bool {
+        matches!(self.relations, Relations::Lover | Relations::Partner)
+            && self.destination != Pubkey::default()
+            && self.target_amount > 0
+            && self.contribution_amount > 0
+            && self.period > 0
+            && self.start_time < self.target_date
+            && self.target_date > now
+    }
+}
+
+impl JointSavings {
+    pub fn partner_index(&self, key: &Pubkey) -> Option<usize> {
+        [self.initiator, self.partner]
+            .iter()
+            .position(|partner| partner == key)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.contributed[0].saturating_add(self.contributed[1])
+    }
+
+    pub fn is_reached(&self) -> bool {
+        self.total() >= self.target_amount
+    }
+
+    // Amount each partner should have deposited by `now` under the schedule,
+    // counting the period that is currently due.
+    pub fn scheduled_amount(&self, now: i64) -> u64 {
+        if now < self.start_time {
+            return 0;
+        }
+        let due = now.min(self.target_date).saturating_sub(self.start_time) / self.period.max(1) + 1;
+        self.contribution_amount.saturating_mul(due as u64)
+    }
+
+    // How far a partner is behind the schedule at `now`.
+    pub fn shortfall(&self, index: usize, now: i64) -> u64 {
+        self.scheduled_amount(now).saturating_sub(self.contributed[index])
+    }
+}
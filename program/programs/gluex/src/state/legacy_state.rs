@@ -0,0 +1,31 @@
+use super::constraints::LEGACY_SUBGOAL_SLOTS;
+use super::user_state::*;
+use anchor_lang::prelude::*;
+
+// TotalGoal layout from before subgoals moved into SubGoalAccount PDAs.
+// Only read by migrate_goal_subgoals, never written.
+#[derive(Debug, AnchorDeserialize)]
+pub struct LegacyTotalGoal {
+    pub issuer: Pubkey,
+    pub taker: Pubkey,
+    pub id: i64,
+    pub description: String,
+    pub room: Roomspace,
+    pub relations: Relations,
+    pub eventype: EventType,
+    pub sub_goals: [SubGoal; LEGACY_SUBGOAL_SLOTS],
+    pub active_sub_goals: u8,
+    pub total_incentive_amount: u64,
+    pub deposited_amount: u64,
+    pub released_amount: u64,
+    pub completion_time: i64,
+    pub locked_amount: u64,
+    pub unlock_time: i64,
+    pub start_time: i64,
+    pub surprise_trigger_ts: i64,
+    pub checkpoint_interval: i64,
+    pub completed_count: u8,
+    pub failed: bool,
+    pub version: u8,
+    pub bump: u8,
+}
@@ -0,0 +1,66 @@
+use super::constraints::BASIS_POINTS;
+use super::user_state::{EventType, Relations};
+use anchor_lang::prelude::*;
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct MatchingPoolConfig {
+    pub ratio_bps: u16,        // matched lamports per deposited lamport, in basis points
+    pub per_goal_cap: u64,     // 0 disables goal matching
+    pub per_bounty_cap: u64,   // 0 disables bounty matching
+    pub per_issuer_cap: u64,   // lifetime matched lamports per issuer
+    pub relations_mask: u8,    // bit `1 << Relations as u8`, 0 accepts any
+    pub event_types_mask: u8,  // bit `1 << EventType as u8`, 0 accepts any
+    pub min_reputation: u64,   // issuer AgentProfile score required, 0 disables
+    pub expires_at: i64,
+}
+
+#[account]
+#[derive(Default, Debug)]
+pub struct MatchingPool {
+    pub sponsor: Pubkey,
+    pub pool_id: i64,
+    pub ratio_bps: u16,
+    pub per_goal_cap: u64,
+    pub per_bounty_cap: u64,
+    pub per_issuer_cap: u64,
+    pub relations_mask: u8,
+    pub event_types_mask: u8,
+    pub min_reputation: u64,
+    pub expires_at: i64,
+    pub total_funded: u64,
+    pub total_matched: u64,
+    pub bump: u8,
+}
+
+impl MatchingPool {
+    pub fn accepts_goal(&self, relations: Relations, eventype: EventType) -> bool {
+        let relations_ok =
+            self.relations_mask == 0 || self.relations_mask & (1u8 << relations as u8) != 0;
+        let event_ok =
+            self.event_types_mask == 0 || self.event_types_mask & (1u8 << eventype as u8) != 0;
+        // surprise and streamed goals pay out without a reviewed proof
+        let reviewed = !matches!(eventype, EventType::SurpriseTime | EventType::Streaming);
+        self.per_goal_cap > 0 && relations_ok && event_ok && reviewed
+    }
+
+    pub fn accepts_bounty(&self) -> bool {
+        self.per_bounty_cap > 0
+    }
+
+    // Matched lamports for `deposit`, bounded by the cap and what the pool still holds.
+    pub fn match_amount(&self, deposit: u64, cap: u64, available: u64) -> u64 {
+        let matched = ((deposit as u128) * (self.ratio_bps as u128) / (BASIS_POINTS as u128)) as u64;
+        matched.min(cap).min(available)
+    }
+}
+
+// Matched lamports an issuer has drawn from one pool, bounded by the pool's
+// per_issuer_cap across all of the issuer's goals and bounties.
+#[account]
+#[derive(Default, Debug)]
+pub struct MatchingAllowance {
+    pub pool: Pubkey,
+    pub issuer: Pubkey,
+    pub matched_amount: u64,
+    pub bump: u8,
+}
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+// Rich goal description kept off-chain. The JSON at `uri` (title, body,
+// images, localized text) is pinned by `content_hash` so clients can detect
+// tampering; the inline TotalGoal description stays a short summary.
+#[account]
+#[derive(Default, Debug)]
+pub struct GoalMetadata {
+    pub goal: Pubkey,
+    pub uri: String,            // capped at MAX_METADATA_URI_LENGTH
+    pub content_hash: [u8; 32], // sha256 of the JSON bytes served at `uri`
+    pub revision: u32,          // bumped on every update
+    pub updated_at: i64,
+    pub bump: u8,
+}
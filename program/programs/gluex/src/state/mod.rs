@@ -4,10 +4,34 @@ pub use constraints::*;
 pub use errors::*;
 pub use bounty_state::*;
 pub use profile_state::*;
+pub use matching_state::*;
+pub use registry_state::*;
+pub use legacy_state::*;
+pub use amendment_state::*;
+pub use metadata_state::*;
+pub use template_state::*;
+pub use challenge_state::*;
+pub use circle_state::*;
+pub use chore_state::*;
+pub use dao_state::*;
+pub use joint_state::*;
+pub use beneficiary_state::*;
 
 pub mod user_state;
 pub mod user_state_impl;
 pub mod constraints;
 pub mod errors;
 pub mod bounty_state;
-pub mod profile_state;
\ No newline at end of file
+pub mod profile_state;
+pub mod matching_state;
+pub mod registry_state;
+pub mod legacy_state;
+pub mod amendment_state;
+pub mod metadata_state;
+pub mod template_state;
+pub mod challenge_state;
+pub mod circle_state;
+pub mod chore_state;
+pub mod dao_state;
+pub mod joint_state;
+pub mod beneficiary_state;
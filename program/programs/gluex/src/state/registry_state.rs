@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum IndexKind {
+    IssuedGoal = 1,
+    TakenGoal,
+    PublishedBounty,
+    ClaimedBounty,
+}
+
+#[account]
+#[derive(Default, Debug)]
+pub struct UserGoalIndex {
+    pub owner: Pubkey,
+    pub next_goal_id: i64, // next id handed out to goals this user issues
+    pub issued_goals: u32,
+    pub taken_goals: u32,
+    pub published_bounties: u32,
+    pub claimed_bounties: u32,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(Default, Debug)]
+pub struct UserIndexPage {
+    pub owner: Pubkey,
+    pub kind: u8, // IndexKind discriminant
+    pub page: u32,
+    pub entries: Vec<Pubkey>, // goal or bounty PDAs, capped at USER_INDEX_PAGE_SIZE
+    pub bump: u8,
+}
+
+impl UserGoalIndex {
+    pub fn count(&self, kind: IndexKind) -> u32 {
+        match kind {
+            IndexKind::IssuedGoal => self.issued_goals,
+            IndexKind::TakenGoal => self.taken_goals,
+            IndexKind::PublishedBounty => self.published_bounties,
+            IndexKind::ClaimedBounty => self.claimed_bounties,
+        }
+    }
+
+    pub fn bump_count(&mut self, kind: IndexKind) {
+        let counter = match kind {
+            IndexKind::IssuedGoal => &mut self.issued_goals,
+            IndexKind::TakenGoal => &mut self.taken_goals,
+            IndexKind::PublishedBounty => &mut self.published_bounties,
+            IndexKind::ClaimedBounty => &mut self.claimed_bounties,
+        };
+        *counter = counter.saturating_add(1);
+    }
+
+    pub fn drop_count(&mut self, kind: IndexKind) {
+        let counter = match kind {
+            IndexKind::IssuedGoal => &mut self.issued_goals,
+            IndexKind::TakenGoal => &mut self.taken_goals,
+            IndexKind::PublishedBounty => &mut self.published_bounties,
+            IndexKind::ClaimedBounty => &mut self.claimed_bounties,
+        };
+        *counter = counter.saturating_sub(1);
+    }
+}
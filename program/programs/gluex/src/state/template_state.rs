@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+
+use super::constraints::{
+    BASIS_POINTS, MAX_DESCRIPTION_BYTES, MAX_SUBGOAL_TITLE_LENGTH, MAX_TEMPLATE_PREREQUISITES,
+    MAX_TEMPLATE_SUBGOALS,
+};
+use super::user_state::*;
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct TemplateSubGoal {
+    pub title: String,
+    pub deadline_offset: i64,     // seconds after the goal start
+    pub incentive_bps: u16,       // share of the goal amount
+    pub auto_release_offset: i64, // seconds after the goal start, 0 releases at the deadline
+    pub prerequisites: Vec<u8>,
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct GoalTemplateInput {
+    pub description: String,
+    pub room: Roomspace,
+    pub relations: Relations,
+    pub eventype: EventType,
+    pub sub_goals: Vec<TemplateSubGoal>, // TargetAchieve only
+    pub duration: i64,                   // completion time, seconds after the goal start
+    pub unlock_delay: i64,               // unlock time, seconds after completion
+    pub locked_bps: u16,                 // share of the goal amount locked until unlock
+    pub checkpoint_interval: i64,        // HabitTraning only, 0 uses the weekly default
+    pub surprise_offset: i64,            // SurpriseTime only, seconds after the goal start
+    pub sequential: bool,
+    pub late_penalty: Option<LatePenalty>,
+    pub fee_pool: Pubkey, // fee pool goals must pay into, default when fees are optional
+    pub verifier: Pubkey, // may review subgoals besides the issuer, default for issuer only
+}
+
+// Reusable goal structure owned by its creator (e.g. a coach). Amounts are
+// stored as shares and times as offsets so one template fits every client.
+#[account]
+#[derive(Default, Debug)]
+pub struct GoalTemplate {
+    pub creator: Pubkey,
+    pub template_id: u64,
+    pub description: String,
+    pub room: Roomspace,
+    pub relations: Relations,
+    pub eventype: EventType,
+    pub sub_goals: Vec<TemplateSubGoal>, // capped at MAX_TEMPLATE_SUBGOALS
+    pub duration: i64,
+    pub unlock_delay: i64,
+    pub locked_bps: u16,
+    pub checkpoint_interval: i64,
+    pub surprise_offset: i64,
+    pub sequential: bool,
+    pub late_penalty: Option<LatePenalty>,
+    pub fee_pool: Pubkey,
+    pub verifier: Pubkey,
+    pub instances: u64, // goals created from this template
+    pub bump: u8,
+}
+
+// setup_goal arguments derived from a template for one goal.
+pub struct TemplateInstance {
+    pub sub_goals: Vec<SubGoalInput>,
+    pub completion_time: i64,
+    pub locked_amount: u64,
+    pub unlock_time: i64,
+    pub config: GoalConfigInput,
+}
+
+impl GoalTemplateInput {
+    pub fn validate(&self) -> bool {
+        let offsets_ok = self.duration > 0
+            && self.unlock_delay >= 0
+            && self.checkpoint_interval >= 0
+            && (self.locked_bps as u64) <= BASIS_POINTS;
+        let shares: u64 = self.sub_goals.iter().map(|goal| goal.incentive_bps as u64).sum();
+        let sub_goals_ok = self.sub_goals.len() <= MAX_TEMPLATE_SUBGOALS
+            && shares <= BASIS_POINTS
+            && self.sub_goals.iter().all(|goal| {
+                goal.title.len() <= MAX_SUBGOAL_TITLE_LENGTH
+                    && goal.deadline_offset > 0
+                    && goal.deadline_offset <= self.duration
+                    && goal.auto_release_offset >= 0
+                    && goal.prerequisites.len() <= MAX_TEMPLATE_PREREQUISITES
+            });
+        let event_ok = match self.eventype {
+            EventType::TargetAchieve => !self.sub_goals.is_empty(),
+            EventType::HabitTraning => self.sub_goals.is_empty(),
+            EventType::SurpriseTime => {
+                self.sub_goals.is_empty()
+                    && self.surprise_offset > 0
+                    && self.surprise_offset <= self.duration
+            }
+            EventType::Streaming => self.sub_goals.is_empty(),
+        };
+        let penalty_ok = self.late_penalty.is_none_or(|penalty| penalty.validate());
+        self.description.len() <= MAX_DESCRIPTION_BYTES
+            && offsets_ok
+            && sub_goals_ok
+            && event_ok
+            && penalty_ok
+    }
+}
+
+impl GoalTemplate {
+    pub fn apply(&mut self, creator: Pubkey, template_id: u64, input: GoalTemplateInput, bump: u8) {
+        self.creator = creator;
+        self.template_id = template_id;
+        self.description = input.description;
+        self.room = input.room;
+        self.relations = input.relations;
+        self.eventype = input.eventype;
+        self.sub_goals = input.sub_goals;
+        self.duration = input.duration;
+        self.unlock_delay = input.unlock_delay;
+        self.locked_bps = input.locked_bps;
+        self.checkpoint_interval = input.checkpoint_interval;
+        self.surprise_offset = input.surprise_offset;
+        self.sequential = input.sequential;
+        self.late_penalty = input.late_penalty;
+        self.fee_pool = input.fee_pool;
+        self.verifier = input.verifier;
+        self.bump = bump;
+    }
+
+    pub fn instantiate(&self, total_incentive_amount: u64, start_time: i64) -> TemplateInstance {
+        let share = |bps: u16| {
+            ((total_incentive_amount as u128) * (bps as u128) / (BASIS_POINTS as u128)) as u64
+        };
+        let sub_goals = self
+            .sub_goals
+            .iter()
+            .map(|goal| SubGoalInput {
+                title: goal.title.clone(),
+                deadline: start_time.saturating_add(goal.deadline_offset),
+                incentive_amount: share(goal.incentive_bps),
+                auto_release_at: if goal.auto_release_offset > 0 {
+                    start_time.saturating_add(goal.auto_release_offset)
+                } else {
+                    0
+                },
+                prerequisites: goal.prerequisites.clone(),
+            })
+            .collect();
+        let completion_time = start_time.saturating_add(self.duration);
+        let surprise_time = match self.eventype {
+            EventType::SurpriseTime => start_time.saturating_add(self.surprise_offset),
+            _ => 0,
+        };
+
+        TemplateInstance {
+            sub_goals,
+            completion_time,
+            locked_amount: share(self.locked_bps),
+            unlock_time: completion_time.saturating_add(self.unlock_delay),
+            config: GoalConfigInput {
+                start_time,
+                surprise_time,
+                checkpoint_interval: self.checkpoint_interval,
+                surprise_window: None,
+                sealed_message: None,
+                sequential: self.sequential,
+                late_penalty: self.late_penalty,
+                dead_man_switch: None,
+            },
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use super::constraints::{
-    MAXIUMUN_SUBGOALS, MAX_DESCRIPTION_BYTES, MAX_PROOF_URI_LENGTH, MAX_SUBGOAL_TITLE_LENGTH,
+    GOAL_ACCOUNT_DISCRIMINATOR, MAX_DESCRIPTION_BYTES, MAX_PROOF_URI_LENGTH,
+    MAX_SEALED_REVEAL_LENGTH, MAX_SUBGOAL_TITLE_LENGTH,
 };
 use anchor_lang::prelude::*;
 
@@ -35,6 +36,7 @@ pub enum EventType {
     HabitTraning = 1,
     TargetAchieve,
     SurpriseTime,
+    Streaming, // incentive vests linearly between start_time and completion_time
 }
 
 impl Default for EventType {
@@ -58,12 +60,45 @@ impl Default for SubGoalStatus {
     }
 }
 
+#[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum DecayCurve {
+    #[default]
+    Linear = 1,
+    Step,
+}
+
+#[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize)]
+pub struct LatePenalty {
+    pub grace_period: i64,   // seconds after a subgoal deadline proofs are still accepted, 0 disables
+    pub curve: DecayCurve,
+    pub floor_bps: u16,      // minimum share paid at the end of the grace period
+    pub step_interval: i64,  // step curve only: seconds per penalty step
+    pub step_bps: u16,       // step curve only: share withheld per step
+}
+
+#[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum FallbackRecipient {
+    #[default]
+    Taker = 1,
+    Wallet,  // e.g. a charity
+    FeePool,
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize)]
+pub struct DeadManSwitch {
+    pub recipient: FallbackRecipient,
+    pub wallet: Pubkey,       // Wallet/FeePool only: address receiving the funds
+    pub grace_period: i64,    // seconds after unlock_time before anyone may release
+    pub keeper_tip_bps: u16,  // share paid to whoever triggers the release
+}
+
 #[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
 pub struct SubGoalInput {
     pub title: String,
     pub deadline: i64,
     pub incentive_amount: u64,
     pub auto_release_at: i64,
+    pub prerequisites: Vec<u8>, // indices that must be Paid before this one
 }
 
 #[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
@@ -71,6 +106,51 @@ pub struct GoalConfigInput {
     pub start_time: i64,
     pub surprise_time: i64,
     pub checkpoint_interval: i64,
+    pub surprise_window: Option<SurpriseWindowInput>, // randomize surprise_time within a window
+    pub sealed_message: Option<SealedMessageInput>,   // message opened when the surprise fires
+    pub sequential: bool,                             // subgoals must be paid in index order
+    pub late_penalty: Option<LatePenalty>,            // decay payouts for late proofs
+    pub dead_man_switch: Option<DeadManSwitch>,       // fallback if the issuer never reclaims
+}
+
+// setup_goal arguments for a goal funded by its predecessor's unspent
+// escrow. Room and relations carry over from the predecessor.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct RolloverGoalInput {
+    pub description: String,
+    pub eventype: EventType,
+    pub sub_goals: Vec<SubGoalInput>,
+    pub completion_time: i64,
+    pub locked_amount: u64,
+    pub unlock_time: i64,
+    pub config: GoalConfigInput,
+}
+
+// setup_goal arguments applied to every taker of a batch_setup_goals call.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct BatchGoalInput {
+    pub description: String,
+    pub room: Roomspace,
+    pub relations: Relations,
+    pub eventype: EventType,
+    pub sub_goals: Vec<SubGoalInput>,
+    pub total_incentive_amount: u64, // per goal
+    pub completion_time: i64,
+    pub locked_amount: u64,
+    pub unlock_time: i64,
+    pub config: GoalConfigInput,
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SurpriseWindowInput {
+    pub window_end: i64,        // window opens at `surprise_time`, closes here
+    pub commitment: [u8; 32],   // sha256 of the issuer secret revealed later
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SealedMessageInput {
+    pub uri: String,            // encrypted message or media reference
+    pub commitment: [u8; 32],   // sha256 of the key/plaintext (salted by the client)
 }
 
 #[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize)]
@@ -111,18 +191,60 @@ impl SubGoal {
     }
 }
 
-#[account]
-#[derive(Default, Debug)]
+#[zero_copy]
+#[derive(Debug)]
+pub struct SealedMessage {
+    pub uri: [u8; MAX_PROOF_URI_LENGTH],      // encrypted message or media reference
+    pub commitment: [u8; 32],                 // zero when no message is attached
+    pub key: [u8; MAX_SEALED_REVEAL_LENGTH],  // key or plaintext, set once revealed
+    pub key_len: u8,
+    pub revealed: u8,
+}
+
+impl Default for SealedMessage {
+    fn default() -> Self {
+        SealedMessage {
+            uri: [0; MAX_PROOF_URI_LENGTH],
+            commitment: [0; 32],
+            key: [0; MAX_SEALED_REVEAL_LENGTH],
+            key_len: 0,
+            revealed: 0,
+        }
+    }
+}
+
+// LatePenalty as stored inside the zero-copy TotalGoal.
+#[zero_copy]
+#[derive(Debug, Default)]
+pub struct LatePenaltyConfig {
+    pub grace_period: i64,
+    pub step_interval: i64,
+    pub floor_bps: u16,
+    pub step_bps: u16,
+    pub curve: u8, // DecayCurve discriminant
+    pub padding: [u8; 3],
+}
+
+// DeadManSwitch as stored inside the zero-copy TotalGoal.
+#[zero_copy]
+#[derive(Debug, Default)]
+pub struct DeadManSwitchConfig {
+    pub wallet: Pubkey,
+    pub grace_period: i64, // 0 when no switch is configured
+    pub keeper_tip_bps: u16,
+    pub recipient: u8, // FallbackRecipient discriminant
+    pub padding: [u8; 5],
+}
+
+// Zero-copy so hot paths like submit_proof/review_subgoal only touch the
+// bytes they read. Enums and flags are stored as u8, fields are ordered
+// by alignment and the tail is padded explicitly.
+#[account(zero_copy, discriminator = GOAL_ACCOUNT_DISCRIMINATOR)]
+#[derive(Debug)]
 pub struct TotalGoal {
-    pub issuer: Pubkey, // 8 + (32 * 2) + des.len() + 3 + (40 * 3) + (8 * 4) + 1
+    pub issuer: Pubkey,
     pub taker: Pubkey,
     pub id: i64,
-    pub description: String, // capped via MAX_DESCRIPTION_BYTES
-    pub room: Roomspace,
-    pub relations: Relations,
-    pub eventype: EventType,
-    pub sub_goals: [SubGoal; MAXIUMUN_SUBGOALS], // subgoal list
-    pub active_sub_goals: u8,
     pub total_incentive_amount: u64, // total incentive amount
     pub deposited_amount: u64,
     pub released_amount: u64,
@@ -132,16 +254,223 @@ pub struct TotalGoal {
     pub start_time: i64,
     pub surprise_trigger_ts: i64,
     pub checkpoint_interval: i64,
+    pub surprise_window_end: i64, // 0 when surprise time is fixed
+    pub settled_mask: u64,        // subgoals that are paid or deactivated, by index
+    pub allocated_amount: u64,    // incentives of subgoals not settled yet
+    pub penalty_withheld: u64,    // withheld by late penalties, reclaimable by the issuer
+    pub sponsored_amount: u64,    // net deposits added through add_funds
+    pub refund_pool: u64,         // unspent funds snapshotted when refunds start
+    pub matched_amount: u64,      // counted in sponsored_amount, refunded to the pool
+    pub late_penalty: LatePenaltyConfig,
+    pub matching_pool: Pubkey,         // default when no matched funds were drawn
+    pub seed_issuer: Pubkey,           // issuer key the address was derived from
+    pub seed_taker: Pubkey,            // taker key the address was derived from
+    pub pending_issuer: Pubkey,        // proposed issuer awaiting acceptance
+    pub pending_taker: Pubkey,         // proposed taker awaiting acceptance
+    pub surprise_commitment: [u8; 32], // sha256(secret) committed at setup
+    pub description: [u8; MAX_DESCRIPTION_BYTES],
+    pub sealed_message: SealedMessage,
+    pub description_len: u16,
+    pub room: u8,      // Roomspace discriminant
+    pub relations: u8, // Relations discriminant
+    pub eventype: u8,  // EventType discriminant
+    pub active_sub_goals: u8, // number of SubGoalAccount PDAs, indices 0..active_sub_goals
     pub completed_count: u8,
-    pub failed: bool,
+    pub failed: u8,
     pub version: u8,
     pub bump: u8,
+    pub surprise_revealed: u8,
+    pub sequential: u8,
+    pub refund_started: u8,
+    pub issuer_refunded: u8,
+    pub matching_refunded: u8,
+    pub issuer_indexed: u8, // recorded in the current issuer's UserIndexPage
+    pub taker_indexed: u8,  // recorded in the current taker's UserIndexPage
+    pub padding: [u8; 5],
+    pub recurrence_period: i64, // seconds each re-arm shifts the schedule by, 0 when one-off
+    pub recurrence_cycle: u16,  // periods re-armed so far
+    pub recurrence_cycles: u16, // total periods including the first
+    pub recurrence_padding: [u8; 4],
+    pub stream_withdrawn: u64, // streamed to the taker so far
+    pub stream_halted_at: i64, // vesting stops here once the issuer halts, 0 while streaming
+    pub dao_governed: u8,      // subgoals are decided by DaoGovernance votes
+    pub split_payouts: u8,     // subgoal payouts follow the goal's GoalBeneficiaries
+    pub emergency_exited: u8,  // closed early by issuer and taker, terminal
+    pub flags_padding: [u8; 5],
+    pub dead_man_switch: DeadManSwitchConfig,
+    pub predecessor: Pubkey, // goal this one was rolled over from, default when none
+    pub surprise_reveal_slot: u64, // slot whose hash fixes a windowed surprise time
+}
+
+// One milestone of a goal, stored at [b"gluex-subgoal", goal, index] so
+// goals only pay rent for the subgoals they actually have.
+#[account]
+#[derive(Debug)]
+pub struct SubGoalAccount {
+    pub goal: Pubkey,
+    pub index: u8,
+    pub title: [u8; MAX_SUBGOAL_TITLE_LENGTH],
+    pub deadline: i64,
+    pub incentive_amount: u64,
+    pub status: SubGoalStatus,
+    pub proof_uri: [u8; MAX_PROOF_URI_LENGTH],
+    pub submitted_at: i64,
+    pub verifier: Pubkey,
+    pub auto_release_at: i64,
+    pub is_active: bool,
+    pub prerequisites: u64, // bitmask of indices that must settle first
+    pub bump: u8,
+}
+
+// Link from a goal to the successor its remainder was rolled into, stored at
+// [b"goal-rollover", predecessor] so a goal rolls over at most once.
+#[account]
+#[derive(Default, Debug)]
+pub struct GoalRollover {
+    pub predecessor: Pubkey,
+    pub successor: Pubkey,
+    pub amount: u64, // escrow moved to the successor
+    pub rolled_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(Default, Debug)]
+pub struct GoalSponsor {
+    pub goal: Pubkey,
+    pub sponsor: Pubkey,
+    pub contributed_amount: u64, // net of protocol fees
+    pub refunded: bool,
+    pub bump: u8,
 }
 
 impl TotalGoal {
     pub fn description_capacity() -> usize {
         MAX_DESCRIPTION_BYTES
     }
+
+    // Legacy goals share the same cap, so migration keeps descriptions whole;
+    // anything longer is cut at a char boundary to fit the buffer.
+    pub fn set_description(&mut self, description: &str) {
+        let mut len = description.len().min(MAX_DESCRIPTION_BYTES);
+        while !description.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.description = string_to_fixed(&description[..len]);
+        self.description_len = len as u16;
+    }
+
+    pub fn room(&self) -> Roomspace {
+        Roomspace::from_u8(self.room)
+    }
+
+    pub fn relations(&self) -> Relations {
+        Relations::from_u8(self.relations)
+    }
+
+    pub fn eventype(&self) -> EventType {
+        EventType::from_u8(self.eventype)
+    }
+
+    // Goal addresses stay derived from the keys used at creation even after
+    // issuer/taker transfers. Legacy goals never recorded them, so fall back
+    // to the current parties, which cannot have changed for those accounts.
+    pub fn address_issuer(&self) -> Pubkey {
+        if self.seed_issuer == Pubkey::default() {
+            self.issuer
+        } else {
+            self.seed_issuer
+        }
+    }
+
+    pub fn address_taker(&self) -> Pubkey {
+        if self.seed_taker == Pubkey::default() {
+            self.taker
+        } else {
+            self.seed_taker
+        }
+    }
+
+    pub fn pin_address_keys(&mut self) {
+        self.seed_issuer = self.address_issuer();
+        self.seed_taker = self.address_taker();
+    }
+
+    // Proposals were made by the previous parties, so any completed transfer
+    // drops both of them.
+    pub fn clear_pending_transfers(&mut self) {
+        self.pending_issuer = Pubkey::default();
+        self.pending_taker = Pubkey::default();
+    }
+
+    pub fn has_surprise_window(&self) -> bool {
+        self.surprise_window_end > 0
+    }
+
+    pub fn unreleased_amount(&self) -> u64 {
+        self.deposited_amount.saturating_sub(self.released_amount)
+    }
+
+    pub fn issuer_contribution(&self) -> u64 {
+        self.deposited_amount.saturating_sub(self.sponsored_amount)
+    }
+
+    // Pro-rata slice of the refund pool for a contribution of `amount`.
+    pub fn refund_share(&self, amount: u64) -> u64 {
+        if self.deposited_amount == 0 {
+            return 0;
+        }
+        ((self.refund_pool as u128) * (amount as u128) / (self.deposited_amount as u128)) as u64
+    }
+
+    // Freezes the unspent balance the first time anyone claims a refund so
+    // every later pro-rata share is computed against the same pool. Stream
+    // funds the taker has vested but not withdrawn stay out of the pool.
+    pub fn start_refund(&mut self, now: i64) {
+        if self.refund_started == 0 {
+            self.refund_pool = self
+                .deposited_amount
+                .saturating_sub(self.released_amount)
+                .saturating_sub(self.stream_owed(now));
+            self.refund_started = 1;
+        }
+    }
+
+    pub fn has_late_penalty(&self) -> bool {
+        self.late_penalty.grace_period > 0
+    }
+
+    pub fn has_dead_man_switch(&self) -> bool {
+        self.dead_man_switch.grace_period > 0
+    }
+
+    pub fn is_rollover(&self) -> bool {
+        self.predecessor != Pubkey::default()
+    }
+
+    pub fn has_sealed_message(&self) -> bool {
+        self.sealed_message.commitment != [0u8; 32]
+    }
+
+    pub fn is_recurring(&self) -> bool {
+        self.recurrence_period > 0
+    }
+
+    pub fn is_stream_halted(&self) -> bool {
+        self.stream_halted_at > 0
+    }
+
+    pub fn is_dao_governed(&self) -> bool {
+        self.dao_governed != 0
+    }
+
+    pub fn has_split_payouts(&self) -> bool {
+        self.split_payouts != 0
+    }
+
+    pub fn is_emergency_exited(&self) -> bool {
+        self.emergency_exited != 0
+    }
 }
 
 pub fn string_to_fixed<const N: usize>(value: &str) -> [u8; N] {
@@ -212,6 +541,15 @@ impl FeePool {
     pub fn seeds() -> &'static [u8] {
         b"gluex-fee-pool"
     }
+
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        let num = self.protocol_fee_numerator;
+        let den = self.protocol_fee_denominator;
+        if den == 0 || num == 0 {
+            return 0;
+        }
+        ((amount as u128) * (num as u128) / (den as u128)) as u64
+    }
 }
 
 // Todo: needs another program to log user infos
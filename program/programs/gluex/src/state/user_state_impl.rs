@@ -1,5 +1,9 @@
 use crate::state::*;
-use super::constraints::{HABIT_CHECKPOINTS, HABIT_INTERVAL_SECONDS, MAXIUMUN_SUBGOALS};
+use super::constraints::{
+    BASIS_POINTS, HABIT_CHECKPOINTS, HABIT_INTERVAL_SECONDS, MAX_KEEPER_TIP_BPS,
+};
+use anchor_lang::prelude::*;
+use solana_program::hash::hashv;
 
 impl SubGoal {
     pub fn from_input(input: &SubGoalInput) -> Self {
@@ -17,8 +21,292 @@ impl SubGoal {
     }
 }
 
-pub fn blank_sub_goals() -> [SubGoal; MAXIUMUN_SUBGOALS] {
-    [SubGoal::default(); MAXIUMUN_SUBGOALS]
+impl SubGoalAccount {
+    pub fn from_sub_goal(goal: Pubkey, index: u8, sub_goal: &SubGoal, prerequisites: u64) -> Self {
+        SubGoalAccount {
+            goal,
+            index,
+            title: sub_goal.title,
+            deadline: sub_goal.deadline,
+            incentive_amount: sub_goal.incentive_amount,
+            status: sub_goal.status,
+            proof_uri: sub_goal.proof_uri,
+            submitted_at: sub_goal.submitted_at,
+            verifier: sub_goal.verifier,
+            auto_release_at: sub_goal.auto_release_at,
+            is_active: sub_goal.is_active,
+            prerequisites,
+            bump: 0,
+        }
+    }
+
+    pub fn is_settled(&self) -> bool {
+        !self.is_active || matches!(self.status, SubGoalStatus::Paid)
+    }
+}
+
+impl Roomspace {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            2 => Roomspace::GroupGame,
+            _ => Roomspace::LoveGame,
+        }
+    }
+}
+
+impl Relations {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            2 => Relations::Lover,
+            3 => Relations::Bosstaff,
+            4 => Relations::Partner,
+            5 => Relations::Dao,
+            _ => Relations::Parents,
+        }
+    }
+}
+
+impl FallbackRecipient {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            2 => FallbackRecipient::Wallet,
+            3 => FallbackRecipient::FeePool,
+            _ => FallbackRecipient::Taker,
+        }
+    }
+}
+
+impl EventType {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            2 => EventType::TargetAchieve,
+            3 => EventType::SurpriseTime,
+            4 => EventType::Streaming,
+            _ => EventType::HabitTraning,
+        }
+    }
+}
+
+impl From<LatePenalty> for LatePenaltyConfig {
+    fn from(penalty: LatePenalty) -> Self {
+        LatePenaltyConfig {
+            grace_period: penalty.grace_period,
+            step_interval: penalty.step_interval,
+            floor_bps: penalty.floor_bps,
+            step_bps: penalty.step_bps,
+            curve: penalty.curve as u8,
+            padding: [0; 3],
+        }
+    }
+}
+
+impl From<LatePenaltyConfig> for LatePenalty {
+    fn from(config: LatePenaltyConfig) -> Self {
+        LatePenalty {
+            grace_period: config.grace_period,
+            curve: match config.curve {
+                2 => DecayCurve::Step,
+                _ => DecayCurve::Linear,
+            },
+            floor_bps: config.floor_bps,
+            step_interval: config.step_interval,
+            step_bps: config.step_bps,
+        }
+    }
+}
+
+impl From<DeadManSwitch> for DeadManSwitchConfig {
+    fn from(switch: DeadManSwitch) -> Self {
+        DeadManSwitchConfig {
+            wallet: switch.wallet,
+            grace_period: switch.grace_period,
+            keeper_tip_bps: switch.keeper_tip_bps,
+            recipient: switch.recipient as u8,
+            padding: [0; 5],
+        }
+    }
+}
+
+impl DeadManSwitch {
+    pub fn validate(&self) -> bool {
+        let wallet_ok = match self.recipient {
+            FallbackRecipient::Taker => self.wallet == Pubkey::default(),
+            FallbackRecipient::Wallet | FallbackRecipient::FeePool => {
+                self.wallet != Pubkey::default()
+            }
+        };
+        wallet_ok && self.grace_period > 0 && self.keeper_tip_bps <= MAX_KEEPER_TIP_BPS
+    }
+}
+
+impl LatePenalty {
+    pub fn validate(&self) -> bool {
+        let bps_ok = (self.floor_bps as u64) <= BASIS_POINTS && (self.step_bps as u64) <= BASIS_POINTS;
+        let curve_ok = match self.curve {
+            DecayCurve::Linear => true,
+            DecayCurve::Step => self.step_interval > 0 && self.step_bps > 0,
+        };
+        self.grace_period > 0 && bps_ok && curve_ok
+    }
+
+    // Share of the incentive (in basis points) still paid `lateness` seconds
+    // after the deadline. Callers reject submissions past the grace period.
+    pub fn payout_bps(&self, lateness: i64) -> u64 {
+        if lateness <= 0 {
+            return BASIS_POINTS;
+        }
+        let floor = self.floor_bps as u64;
+        let lateness = lateness.min(self.grace_period) as u64;
+        let withheld = match self.curve {
+            DecayCurve::Linear => {
+                (BASIS_POINTS - floor).saturating_mul(lateness) / (self.grace_period as u64)
+            }
+            DecayCurve::Step => {
+                let interval = self.step_interval.max(1) as u64;
+                let steps = lateness.div_ceil(interval);
+                steps.saturating_mul(self.step_bps as u64)
+            }
+        };
+        BASIS_POINTS.saturating_sub(withheld).max(floor)
+    }
+
+    pub fn apply(&self, amount: u64, lateness: i64) -> u64 {
+        ((amount as u128) * (self.payout_bps(lateness) as u128) / (BASIS_POINTS as u128)) as u64
+    }
+}
+
+impl TotalGoal {
+    // A subgoal is unlocked once every prerequisite (and, for sequential
+    // goals, every earlier subgoal) has been paid out or deactivated.
+    pub fn prerequisites_met(&self, sub_goal: &SubGoalAccount) -> bool {
+        let mut required = sub_goal.prerequisites;
+        if self.sequential != 0 {
+            required |= low_bits(sub_goal.index as usize);
+        }
+        required & !self.settled_mask == 0
+    }
+
+    // Marks a subgoal paid or deactivated and drops its incentive from the
+    // open allocation.
+    pub fn settle_sub_goal(&mut self, index: u8, incentive_amount: u64) {
+        self.settled_mask |= 1u64 << index;
+        self.allocated_amount = self.allocated_amount.saturating_sub(incentive_amount);
+    }
+
+    // Every subgoal is paid or deactivated.
+    pub fn all_settled(&self) -> bool {
+        let all = low_bits(self.active_sub_goals as usize);
+        self.settled_mask & all == all
+    }
+
+    // Part of a streamed `amount` vested at `now`. Vesting runs linearly from
+    // start_time to completion_time; halting cuts the stream down to what had
+    // vested, so a halted stream is fully vested.
+    pub fn vested_amount(&self, amount: u64, now: i64) -> u64 {
+        let duration = self.completion_time.saturating_sub(self.start_time);
+        let elapsed = now.saturating_sub(self.start_time);
+        if self.is_stream_halted() || duration <= 0 || elapsed >= duration {
+            return amount;
+        }
+        if elapsed <= 0 {
+            return 0;
+        }
+        ((amount as u128) * (elapsed as u128) / (duration as u128)) as u64
+    }
+
+    // Streamed funds vested at `now` that the taker has not withdrawn yet.
+    // Until the stream subgoal settles its incentive is the allocated amount.
+    pub fn stream_owed(&self, now: i64) -> u64 {
+        if !matches!(self.eventype(), EventType::Streaming) {
+            return 0;
+        }
+        self.vested_amount(self.allocated_amount, now)
+            .saturating_sub(self.stream_withdrawn)
+    }
+}
+
+// Mask with the lowest `count` bits set.
+pub fn low_bits(count: usize) -> u64 {
+    if count >= 64 {
+        u64::MAX
+    } else {
+        (1u64 << count) - 1
+    }
+}
+
+// Converts declared prerequisite indices of subgoals appended after
+// `existing` ones into bitmasks and rejects self references,
+// out-of-range indices and cycles.
+pub fn prerequisite_masks(existing: usize, sub_goals: &[SubGoalInput]) -> Result<Vec<u64>> {
+    let count = sub_goals.len();
+    let mut masks = vec![0u64; count];
+    for (idx, input) in sub_goals.iter().enumerate() {
+        for prerequisite in input.prerequisites.iter() {
+            let required = *prerequisite as usize;
+            require!(
+                required < existing + count && required != existing + idx,
+                GluXError::SubGoalPrerequisiteInvalid
+            );
+            masks[idx] |= 1u64 << required;
+        }
+    }
+
+    // earlier subgoals cannot depend on new ones, so only the batch can cycle;
+    // peel off subgoals whose prerequisites are already resolved
+    let mut resolved = low_bits(existing);
+    for _ in 0..count {
+        let mut progressed = false;
+        for (idx, mask) in masks.iter().enumerate() {
+            let bit = 1u64 << (existing + idx);
+            if resolved & bit == 0 && mask & !resolved == 0 {
+                resolved |= bit;
+                progressed = true;
+            }
+        }
+        if !progressed {
+            break;
+        }
+    }
+    require!(
+        resolved == low_bits(existing + count),
+        GluXError::SubGoalDependencyCycle
+    );
+    Ok(masks)
+}
+
+// Spreads extra funds over active subgoals proportionally to their
+// incentives; rounding dust lands on the last active subgoal.
+pub fn distribute_bonus(sub_goals: &mut [SubGoal], amount: u64) {
+    let incentives: Vec<u64> = sub_goals
+        .iter()
+        .filter(|goal| goal.is_active)
+        .map(|goal| goal.incentive_amount)
+        .collect();
+    let shares = bonus_shares(&incentives, amount);
+    for (goal, share) in sub_goals.iter_mut().filter(|goal| goal.is_active).zip(shares) {
+        goal.incentive_amount = goal.incentive_amount.saturating_add(share);
+    }
+}
+
+// Splits `amount` proportionally to `incentives`, rounding dust onto the last
+// share. Empty when there is nothing to spread over.
+pub fn bonus_shares(incentives: &[u64], amount: u64) -> Vec<u64> {
+    let base: u64 = incentives.iter().sum();
+    let mut shares: Vec<u64> = incentives
+        .iter()
+        .map(|&incentive| {
+            if base == 0 {
+                0
+            } else {
+                ((amount as u128) * (incentive as u128) / (base as u128)) as u64
+            }
+        })
+        .collect();
+    let distributed: u64 = shares.iter().sum();
+    if let Some(last) = shares.last_mut() {
+        *last = last.saturating_add(amount.saturating_sub(distributed));
+    }
+    shares
 }
 
 pub fn habit_amounts(total: u64) -> [u64; HABIT_CHECKPOINTS] {
@@ -48,3 +336,38 @@ pub fn default_checkpoint_interval(input: i64) -> i64 {
         HABIT_INTERVAL_SECONDS
     }
 }
+
+// Mixes the issuer secret with the hash of a slot that came after the
+// commitment, so neither party can predict the trigger time.
+pub fn derive_surprise_ts(
+    secret: &[u8; 32],
+    slot_hash: &[u8],
+    goal: &Pubkey,
+    window_start: i64,
+    window_end: i64,
+) -> i64 {
+    let entropy = hashv(&[secret, slot_hash, goal.as_ref()]).to_bytes();
+    let mut seed = [0u8; 8];
+    seed.copy_from_slice(&entropy[..8]);
+    let span = window_end.saturating_sub(window_start).saturating_add(1).max(1) as u64;
+    window_start.saturating_add((u64::from_le_bytes(seed) % span) as i64)
+}
+
+// Hash of the first block at or after `slot` in the slot hashes sysvar
+// (u64 entry count, then (slot: u64, hash: [u8; 32]) newest first), or None
+// once the sysvar no longer reaches back to `slot`.
+pub fn slot_hash_at(data: &[u8], slot: u64) -> Option<[u8; 32]> {
+    let count = u64::from_le_bytes(data.get(..8)?.try_into().ok()?) as usize;
+    let mut found = None;
+    for entry in data.get(8..)?.chunks_exact(40).take(count) {
+        let entry_slot = u64::from_le_bytes(entry[..8].try_into().ok()?);
+        if entry_slot < slot {
+            return found;
+        }
+        found = Some(entry[8..].try_into().ok()?);
+        if entry_slot == slot {
+            return found;
+        }
+    }
+    None
+}
@@ -3,6 +3,9 @@ import { PublicKey, Connection, Keypair } from "@solana/web3.js";
 import fs from "fs";
 import BN from "bn.js";
 
+const LEGACY_SUBGOAL_SLOTS = 5;
+const SUB_GOAL_SIZE = 242; // title 48, u64/i64 x5, status 1, proof uri 128, verifier 32, is_active 1
+
 async function main() {
   const RPC =
     process.env.RPC_URL ||
@@ -48,29 +51,45 @@ async function main() {
   console.log("goal PDA:", pda.toBase58(), "bump:", bump);
 
   try {
-    // Backup existing account data (if present)
     const acct = await connection.getAccountInfo(pda);
-    if (acct) {
-      const backupsDir = `${process.cwd()}/migrations-backups`;
-      if (!fs.existsSync(backupsDir))
-        fs.mkdirSync(backupsDir, { recursive: true });
-      const fname = `${backupsDir}/${pda.toBase58()}.bin`;
-      fs.writeFileSync(fname, acct.data);
-      console.log("Saved backup to", fname);
-    } else {
-      console.log(
-        "No existing account data found at PDA (will create new account on setup)"
-      );
-    }
+    if (!acct) throw new Error(`no goal account at ${pda.toBase58()}`);
+
+    // Backup existing account data before it is rewritten
+    const backupsDir = `${process.cwd()}/migrations-backups`;
+    if (!fs.existsSync(backupsDir))
+      fs.mkdirSync(backupsDir, { recursive: true });
+    const fname = `${backupsDir}/${pda.toBase58()}.bin`;
+    fs.writeFileSync(fname, acct.data);
+    console.log("Saved backup to", fname);
+
+    // legacy layout: discriminator, issuer, taker, id, description (String),
+    // room/relations/eventype, 5 inline subgoals, then active_sub_goals
+    const descLen = acct.data.readUInt32LE(8 + 32 + 32 + 8);
+    const activeOffset =
+      8 + 32 + 32 + 8 + 4 + descLen + 3 + LEGACY_SUBGOAL_SLOTS * SUB_GOAL_SIZE;
+    const active = Math.min(acct.data[activeOffset], LEGACY_SUBGOAL_SLOTS);
+    const subGoals = Array.from({ length: active }, (_, index) => ({
+      pubkey: PublicKey.findProgramAddressSync(
+        [Buffer.from("gluex-subgoal"), pda.toBuffer(), Buffer.from([index])],
+        program.programId
+      )[0],
+      isWritable: true,
+      isSigner: false,
+    }));
 
     const sig = await program.methods
-      .migrateTotalGoal(issuer, taker, new BN(goalId), bump)
-      .accounts({ goals: pda, initiator: provider.wallet.publicKey })
+      .migrateGoalSubgoals()
+      .accounts({
+        goals: pda,
+        payer: provider.wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(subGoals)
       .rpc();
 
-    console.log("migrateTotalGoal tx sig:", sig);
+    console.log("migrateGoalSubgoals tx sig:", sig);
   } catch (err) {
-    console.error("migrateTotalGoal failed", err);
+    console.error("migrateGoalSubgoals failed", err);
     process.exit(1);
   }
 }
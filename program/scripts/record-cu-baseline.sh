@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+# Records submit_proof/review_subgoal compute units on the last Borsh goal
+# layout into tests/fixtures/compute_units_baseline.json. Needs the Anchor
+# CLI and a local solana-test-validator.
+set -euo pipefail
+
+BASELINE_COMMIT="${BASELINE_COMMIT:-95ed6a6}" # [user-035], before zero-copy goals
+program_dir="$(cd "$(dirname "$0")/.." && pwd)"
+worktree="$(mktemp -d)"
+trap 'git -C "$program_dir" worktree remove --force "$worktree"' EXIT
+
+git -C "$program_dir" worktree add --detach "$worktree" "$BASELINE_COMMIT"
+cp "$program_dir/tests/compute_units.test.ts" "$worktree/program/tests/"
+ln -s "$program_dir/node_modules" "$worktree/program/node_modules"
+
+cd "$worktree/program"
+sed -i.bak 's|^test = .*|test = "ts-mocha -p ./tsconfig.json --timeout 1000000 tests/compute_units.test.ts"|' Anchor.toml
+mkdir -p "$program_dir/tests/fixtures"
+CU_BASELINE_OUT="$program_dir/tests/fixtures/compute_units_baseline.json" \
+  anchor test --provider.cluster localnet
+cat "$program_dir/tests/fixtures/compute_units_baseline.json"
@@ -0,0 +1,141 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("batch_goals", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const subGoalPda = (goal: web3.PublicKey, index: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gluex-subgoal"), goal.toBuffer(), Buffer.from([index])],
+      program.programId
+    )[0];
+
+  const goalPda = (issuer: web3.PublicKey, taker: web3.PublicKey, goalId: BN) =>
+    web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gluex-goals"),
+        issuer.toBuffer(),
+        taker.toBuffer(),
+        Buffer.from(goalId.toArray("le", 8)),
+      ],
+      program.programId
+    )[0];
+
+  const writable = (pubkey: web3.PublicKey) => ({
+    pubkey,
+    isWritable: true,
+    isSigner: false,
+  });
+
+  const teacher = web3.Keypair.generate();
+  const students = Array.from({ length: 4 }, () => web3.Keypair.generate());
+  const goalId = new BN(2024);
+  const amount = 500_000;
+
+  before(async () => {
+    for (const wallet of [teacher, ...students]) {
+      const sig = await connection.requestAirdrop(
+        wallet.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction(sig);
+    }
+  });
+
+  it("creates identical goals for a class in one transaction", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const goals = students.map((s) => goalPda(teacher.publicKey, s.publicKey, goalId));
+
+    await program.methods
+      .batchSetupGoals(
+        students.map((s) => s.publicKey),
+        goalId,
+        {
+          description: "30-day reading challenge",
+          room: { groupGame: {} },
+          relations: { bosstaff: {} },
+          eventype: { targetAchieve: {} },
+          subGoals: [
+            {
+              title: "Finish the first book",
+              deadline: new BN(now + 3600),
+              incentiveAmount: new BN(amount),
+              autoReleaseAt: new BN(0),
+              prerequisites: Buffer.from([]),
+            },
+          ],
+          totalIncentiveAmount: new BN(amount),
+          completionTime: new BN(now + 3600),
+          lockedAmount: new BN(0),
+          unlockTime: new BN(now + 7200),
+          config: {
+            startTime: new BN(0),
+            surpriseTime: new BN(0),
+            checkpointInterval: new BN(0),
+            surpriseWindow: null,
+            sealedMessage: null,
+            sequential: false,
+            latePenalty: null,
+          },
+        }
+      )
+      .accounts({
+        payer: teacher.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts(
+        goals.flatMap((goal) => [writable(goal), writable(subGoalPda(goal, 0))])
+      )
+      .signers([teacher])
+      .rpc();
+
+    for (const [i, goal] of goals.entries()) {
+      const goalAccount = await program.account.totalGoal.fetch(goal);
+      expect(goalAccount.taker.equals(students[i].publicKey)).to.be.true;
+      expect(goalAccount.depositedAmount.toNumber()).to.eq(amount);
+      expect(goalAccount.activeSubGoals).to.eq(1);
+    }
+  });
+
+  it("approves the same subgoal across the class in one transaction", async () => {
+    const goals = students.map((s) => goalPda(teacher.publicKey, s.publicKey, goalId));
+    for (const [i, goal] of goals.entries()) {
+      await program.methods
+        .submitProof(0, `ipfs://book-report-${i}`)
+        .accounts({
+          goals: goal,
+          subGoal: subGoalPda(goal, 0),
+          taker: students[i].publicKey,
+        })
+        .signers([students[i]])
+        .rpc();
+    }
+
+    const before = await connection.getBalance(students[0].publicKey);
+    await program.methods
+      .batchReviewSubgoals(0, true)
+      .accounts({ reviewer: teacher.publicKey })
+      .remainingAccounts(
+        goals.flatMap((goal, i) => [
+          writable(goal),
+          writable(subGoalPda(goal, 0)),
+          writable(students[i].publicKey),
+        ])
+      )
+      .signers([teacher])
+      .rpc();
+
+    for (const goal of goals) {
+      const subGoal = await program.account.subGoalAccount.fetch(subGoalPda(goal, 0));
+      expect(subGoal.status).to.deep.eq({ paid: {} });
+    }
+    const after = await connection.getBalance(students[0].publicKey);
+    expect(after - before).to.eq(amount);
+  });
+});
@@ -0,0 +1,201 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("beneficiary_split", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const coach = web3.Keypair.generate();
+  const runner = web3.Keypair.generate();
+  const charity = web3.Keypair.generate();
+  const goalId = new BN(47);
+  const [goalsPda] = web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("gluex-goals"),
+      coach.publicKey.toBuffer(),
+      runner.publicKey.toBuffer(),
+      Buffer.from(goalId.toArray("le", 8)),
+    ],
+    program.programId
+  );
+  const [subGoalPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("gluex-subgoal"), goalsPda.toBuffer(), Buffer.from([0])],
+    program.programId
+  );
+  const [beneficiariesPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("goal-beneficiaries"), goalsPda.toBuffer()],
+    program.programId
+  );
+  const amount = 1_000_000;
+  const walletMetas = (wallets: web3.PublicKey[]) =>
+    wallets.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false }));
+
+  before(async () => {
+    for (const wallet of [coach, runner, charity]) {
+      const sig = await connection.requestAirdrop(
+        wallet.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction(sig);
+    }
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .setupGoal(
+        runner.publicKey,
+        goalId,
+        "Charity 10k",
+        { loveGame: {} },
+        { partner: {} },
+        { targetAchieve: {} },
+        [
+          {
+            title: "Finish the 10k",
+            deadline: new BN(now + 3600),
+            incentiveAmount: new BN(amount),
+            autoReleaseAt: new BN(0),
+            prerequisites: Buffer.from([]),
+          },
+        ],
+        new BN(amount),
+        new BN(now + 3600),
+        new BN(0),
+        new BN(now + 7200),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: coach.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: subGoalPda, isWritable: true, isSigner: false }])
+      .signers([coach])
+      .rpc();
+  });
+
+  it("rejects splits that do not add up to 100%", async () => {
+    try {
+      await program.methods
+        .configureBeneficiaries({
+          shares: [
+            { wallet: runner.publicKey, bps: 7_000 },
+            { wallet: charity.publicKey, bps: 2_000 },
+          ],
+          subGoalShares: [],
+        })
+        .accounts({
+          goals: goalsPda,
+          beneficiaries: beneficiariesPda,
+          issuer: coach.publicKey,
+          taker: runner.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .remainingAccounts(walletMetas([runner.publicKey, charity.publicKey]))
+        .signers([coach, runner])
+        .rpc();
+      expect.fail("shares must sum to 10,000 bps");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("BeneficiariesInvalid");
+    }
+  });
+
+  it("rejects wallets that could not receive a dust portion", async () => {
+    const unfunded = web3.Keypair.generate();
+    try {
+      await program.methods
+        .configureBeneficiaries({
+          shares: [
+            { wallet: runner.publicKey, bps: 9_999 },
+            { wallet: unfunded.publicKey, bps: 1 },
+          ],
+          subGoalShares: [],
+        })
+        .accounts({
+          goals: goalsPda,
+          beneficiaries: beneficiariesPda,
+          issuer: coach.publicKey,
+          taker: runner.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .remainingAccounts(walletMetas([runner.publicKey, unfunded.publicKey]))
+        .signers([coach, runner])
+        .rpc();
+      expect.fail("an empty wallet cannot take a 100 lamport portion");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("BeneficiaryWalletUnfunded");
+    }
+  });
+
+  it("pays approved subgoals across the beneficiaries", async () => {
+    await program.methods
+      .configureBeneficiaries({
+        shares: [
+          { wallet: runner.publicKey, bps: 7_000 },
+          { wallet: charity.publicKey, bps: 3_000 },
+        ],
+        subGoalShares: [],
+      })
+      .accounts({
+        goals: goalsPda,
+        beneficiaries: beneficiariesPda,
+        issuer: coach.publicKey,
+        taker: runner.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts(walletMetas([runner.publicKey, charity.publicKey]))
+      .signers([coach, runner])
+      .rpc();
+
+    await program.methods
+      .submitProof(0, "https://race.example/result")
+      .accounts({ goals: goalsPda, subGoal: subGoalPda, taker: runner.publicKey })
+      .signers([runner])
+      .rpc();
+
+    const review = (wallets: web3.PublicKey[]) =>
+      program.methods
+        .reviewSubgoal(0, true)
+        .accounts({
+          goals: goalsPda,
+          subGoal: subGoalPda,
+          issuer: coach.publicKey,
+          takerAccount: runner.publicKey,
+          beneficiaries: beneficiariesPda,
+        })
+        .remainingAccounts(
+          wallets.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+        )
+        .signers([coach])
+        .rpc();
+
+    // wallets must follow the stored order
+    try {
+      await review([charity.publicKey, runner.publicKey]);
+      expect.fail("beneficiary order mismatch");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("BeneficiaryMismatch");
+    }
+
+    const runnerBefore = await connection.getBalance(runner.publicKey);
+    const charityBefore = await connection.getBalance(charity.publicKey);
+    await review([runner.publicKey, charity.publicKey]);
+    const runnerAfter = await connection.getBalance(runner.publicKey);
+    const charityAfter = await connection.getBalance(charity.publicKey);
+
+    expect(runnerAfter - runnerBefore).to.eq(700_000);
+    expect(charityAfter - charityBefore).to.eq(300_000);
+  });
+});
@@ -0,0 +1,138 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("chore_board", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const parent = web3.Keypair.generate();
+  const child = web3.Keypair.generate();
+  const [boardPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("chore-board"), parent.publicKey.toBuffer(), child.publicKey.toBuffer()],
+    program.programId
+  );
+  const [chorePda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("chore"), boardPda.toBuffer(), Buffer.from([0])],
+    program.programId
+  );
+  const completionPda = (id: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("chore-completion"), chorePda.toBuffer(), new BN(id).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    )[0];
+  const reward = 1_000_000;
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  const checkIn = (id: number, proof: string) =>
+    program.methods
+      .checkInChore(proof)
+      .accounts({
+        board: boardPda,
+        chore: chorePda,
+        completion: completionPda(id),
+        child: child.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([child])
+      .rpc();
+
+  before(async () => {
+    for (const wallet of [parent, child]) {
+      const sig = await connection.requestAirdrop(
+        wallet.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction(sig);
+    }
+
+    await program.methods
+      .createChoreBoard(child.publicKey, new BN(3), new BN(10 * reward))
+      .accounts({
+        board: boardPda,
+        parent: parent.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([parent])
+      .rpc();
+    await program.methods
+      .addChore("Take out the trash", new BN(reward), 3)
+      .accounts({
+        board: boardPda,
+        chore: chorePda,
+        parent: parent.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([parent])
+      .rpc();
+  });
+
+  it("caps check-ins per week and batch-reviews them", async () => {
+    await checkIn(0, "ipfs://trash-monday");
+    await checkIn(1, "");
+    await checkIn(2, "ipfs://trash-friday");
+    try {
+      await checkIn(3, "");
+      expect.fail("weekly cap reached");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("ChoreCapReached");
+    }
+
+    const before = await connection.getBalance(child.publicKey);
+    await program.methods
+      .reviewChoreCompletions([true, false])
+      .accounts({ board: boardPda, parent: parent.publicKey, child: child.publicKey })
+      .remainingAccounts(
+        [completionPda(0), chorePda, completionPda(1), chorePda].map((pubkey) => ({
+          pubkey,
+          isWritable: true,
+          isSigner: false,
+        }))
+      )
+      .signers([parent])
+      .rpc();
+    const after = await connection.getBalance(child.publicKey);
+    expect(after - before).to.be.greaterThanOrEqual(reward);
+
+    const chore = await program.account.chore.fetch(chorePda);
+    expect(chore.weekCount).to.eq(2); // the rejected check-in frees its slot
+    const board = await program.account.choreBoard.fetch(boardPda);
+    expect(board.pendingRewards.toNumber()).to.eq(reward);
+    expect(await connection.getAccountInfo(completionPda(0))).to.be.null;
+  });
+
+  it("auto-approves check-ins left unreviewed past the timeout", async () => {
+    try {
+      await program.methods
+        .autoApproveChore()
+        .accounts({
+          board: boardPda,
+          chore: chorePda,
+          completion: completionPda(2),
+          child: child.publicKey,
+        })
+        .rpc();
+      expect.fail("review window still open");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("ChoreReviewPending");
+    }
+
+    await sleep(4000);
+    await program.methods
+      .autoApproveChore()
+      .accounts({
+        board: boardPda,
+        chore: chorePda,
+        completion: completionPda(2),
+        child: child.publicKey,
+      })
+      .rpc();
+
+    const board = await program.account.choreBoard.fetch(boardPda);
+    expect(board.pendingRewards.toNumber()).to.eq(0);
+    expect(board.released.toNumber()).to.eq(2 * reward);
+  });
+});
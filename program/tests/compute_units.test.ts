@@ -0,0 +1,136 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as fs from "fs";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+// Units measured on the last Borsh goal layout, written by
+// scripts/record-cu-baseline.sh. When CU_BASELINE_OUT is set this test
+// records its own measurements there instead of comparing.
+const BASELINE_PATH = "tests/fixtures/compute_units_baseline.json";
+
+describe("compute_units", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const subGoalPda = (goal: web3.PublicKey, index: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gluex-subgoal"), goal.toBuffer(), Buffer.from([index])],
+      program.programId
+    )[0];
+
+  const unitsUsed = async (sig: string) => {
+    const tx = await connection.getTransaction(sig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    return tx.meta.computeUnitsConsumed;
+  };
+
+  it("submits and reviews a proof against the zero-copy goal", async () => {
+    const taker = web3.Keypair.generate();
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(taker.publicKey, web3.LAMPORTS_PER_SOL)
+    );
+
+    const now = Math.floor(Date.now() / 1000);
+    const goalIdBn = new BN(now + 3);
+    const [goalsPda] = web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gluex-goals"),
+        program.provider.publicKey.toBuffer(),
+        taker.publicKey.toBuffer(),
+        Buffer.from(goalIdBn.toArray("le", 8)),
+      ],
+      program.programId
+    );
+    const totalAmount = web3.LAMPORTS_PER_SOL / 2;
+
+    await program.methods
+      .setupGoal(
+        taker.publicKey,
+        goalIdBn,
+        "d".repeat(128),
+        { loveGame: {} },
+        { partner: {} },
+        { targetAchieve: {} },
+        [
+          {
+            title: "Measured milestone",
+            deadline: new BN(now + 3600),
+            incentiveAmount: new BN(totalAmount),
+            autoReleaseAt: new BN(0),
+            prerequisites: [],
+          },
+        ],
+        new BN(totalAmount),
+        new BN(now + 3600),
+        new BN(0),
+        new BN(now + 7200),
+        {
+          startTime: new BN(now),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: program.provider.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: subGoalPda(goalsPda, 0), isWritable: true, isSigner: false },
+      ])
+      .rpc();
+
+    const submitSig = await program.methods
+      .submitProof(0, "ipfs://proof")
+      .accounts({
+        goals: goalsPda,
+        subGoal: subGoalPda(goalsPda, 0),
+        taker: taker.publicKey,
+      })
+      .signers([taker])
+      .rpc({ commitment: "confirmed" });
+    const reviewSig = await program.methods
+      .reviewSubgoal(0, true)
+      .accounts({
+        goals: goalsPda,
+        subGoal: subGoalPda(goalsPda, 0),
+        issuer: program.provider.publicKey,
+        takerAccount: taker.publicKey,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const goalAccount = await program.account.totalGoal.fetch(goalsPda);
+    expect(goalAccount.releasedAmount.toNumber()).to.eq(totalAmount);
+
+    const measured = {
+      submitProof: await unitsUsed(submitSig),
+      reviewSubgoal: await unitsUsed(reviewSig),
+    };
+    if (process.env.CU_BASELINE_OUT) {
+      fs.writeFileSync(
+        process.env.CU_BASELINE_OUT,
+        JSON.stringify(measured, null, 2) + "\n"
+      );
+      return;
+    }
+
+    expect(
+      fs.existsSync(BASELINE_PATH),
+      `${BASELINE_PATH} missing, run scripts/record-cu-baseline.sh`
+    ).to.be.true;
+    const baseline = JSON.parse(fs.readFileSync(BASELINE_PATH, "utf8"));
+    expect(goalAccount.descriptionLen).to.eq(128);
+    expect(measured.submitProof).to.be.below(baseline.submitProof);
+    expect(measured.reviewSubgoal).to.be.below(baseline.reviewSubgoal);
+  });
+});
@@ -0,0 +1,196 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("dao_governance", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const treasury = web3.Keypair.generate();
+  const contributor = web3.Keypair.generate();
+  const members = Array.from({ length: 3 }, () => web3.Keypair.generate());
+  const goalId = new BN(45);
+  const [goalsPda] = web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("gluex-goals"),
+      treasury.publicKey.toBuffer(),
+      contributor.publicKey.toBuffer(),
+      Buffer.from(goalId.toArray("le", 8)),
+    ],
+    program.programId
+  );
+  const [subGoalPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("gluex-subgoal"), goalsPda.toBuffer(), Buffer.from([0])],
+    program.programId
+  );
+  const [governancePda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("dao-governance"), goalsPda.toBuffer()],
+    program.programId
+  );
+  const [tallyPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("dao-tally"), subGoalPda.toBuffer()],
+    program.programId
+  );
+  const amount = 2_000_000;
+  const votingPeriod = 5;
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  before(async () => {
+    for (const wallet of [treasury, contributor, ...members]) {
+      const sig = await connection.requestAirdrop(
+        wallet.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction(sig);
+    }
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .setupGoal(
+        contributor.publicKey,
+        goalId,
+        "Ship the governance dashboard",
+        { groupGame: {} },
+        { dao: {} },
+        { targetAchieve: {} },
+        [
+          {
+            title: "Dashboard live",
+            deadline: new BN(now + 3600),
+            incentiveAmount: new BN(amount),
+            autoReleaseAt: new BN(0),
+            prerequisites: Buffer.from([]),
+          },
+        ],
+        new BN(amount),
+        new BN(now + 3600),
+        new BN(0),
+        new BN(now + 7200),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: treasury.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: subGoalPda, isWritable: true, isSigner: false }])
+      .signers([treasury])
+      .rpc();
+
+    await program.methods
+      .configureDaoGovernance({
+        mode: { memberList: {} },
+        membershipMint: web3.PublicKey.default,
+        members: members.map((m) => m.publicKey),
+        quorum: new BN(2),
+        approvalBps: 6_000,
+        votingPeriod: new BN(votingPeriod),
+      })
+      .accounts({
+        goals: goalsPda,
+        governance: governancePda,
+        issuer: treasury.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([treasury])
+      .rpc();
+  });
+
+  it("pays out a subgoal the members vote through", async () => {
+    await program.methods
+      .submitProof(0, "https://dao.example/dashboard")
+      .accounts({ goals: goalsPda, subGoal: subGoalPda, taker: contributor.publicKey })
+      .signers([contributor])
+      .rpc();
+
+    // the issuer no longer reviews directly
+    try {
+      await program.methods
+        .reviewSubgoal(0, true)
+        .accounts({
+          goals: goalsPda,
+          subGoal: subGoalPda,
+          issuer: treasury.publicKey,
+          takerAccount: contributor.publicKey,
+        })
+        .signers([treasury])
+        .rpc();
+      expect.fail("DAO goals are decided by vote");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("DaoGoverned");
+    }
+
+    const subGoal = await program.account.subGoalAccount.fetch(subGoalPda);
+    for (const [i, member] of members.entries()) {
+      const [votePda] = web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("dao-vote"),
+          subGoalPda.toBuffer(),
+          member.publicKey.toBuffer(),
+          Buffer.from(subGoal.submittedAt.toArray("le", 8)),
+        ],
+        program.programId
+      );
+      await program.methods
+        .castDaoVote(0, i < 2)
+        .accounts({
+          goals: goalsPda,
+          subGoal: subGoalPda,
+          governance: governancePda,
+          tally: tallyPda,
+          voteRecord: votePda,
+          voter: member.publicKey,
+          voterToken: null,
+          membershipMint: null,
+          voteEscrow: null,
+          tokenProgram: null,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([member])
+        .rpc();
+    }
+
+    const tally = await program.account.daoTally.fetch(tallyPda);
+    expect(tally.yesWeight.toNumber()).to.eq(2);
+    expect(tally.noWeight.toNumber()).to.eq(1);
+
+    const finalize = () =>
+      program.methods
+        .finalizeDaoVote(0)
+        .accounts({
+          goals: goalsPda,
+          subGoal: subGoalPda,
+          governance: governancePda,
+          tally: tallyPda,
+          takerAccount: contributor.publicKey,
+        })
+        .rpc();
+    try {
+      await finalize();
+      expect.fail("voting window still open");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("DaoVotingOpen");
+    }
+
+    await sleep((votingPeriod + 1) * 1000);
+    const before = await connection.getBalance(contributor.publicKey);
+    await finalize();
+    const after = await connection.getBalance(contributor.publicKey);
+    expect(after - before).to.eq(amount);
+
+    const paid = await program.account.subGoalAccount.fetch(subGoalPda);
+    expect(paid.status).to.deep.eq({ paid: {} });
+  });
+});
@@ -0,0 +1,136 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("dead_man_switch", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const issuer = web3.Keypair.generate();
+  const taker = web3.Keypair.generate();
+  const charity = web3.Keypair.generate();
+  const keeper = web3.Keypair.generate();
+  const goalId = new BN(48);
+  const [goalsPda] = web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("gluex-goals"),
+      issuer.publicKey.toBuffer(),
+      taker.publicKey.toBuffer(),
+      Buffer.from(goalId.toArray("le", 8)),
+    ],
+    program.programId
+  );
+  const [subGoalPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("gluex-subgoal"), goalsPda.toBuffer(), Buffer.from([0])],
+    program.programId
+  );
+  const amount = 1_000_000;
+  const gracePeriod = 3;
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  before(async () => {
+    for (const wallet of [issuer, keeper]) {
+      const sig = await connection.requestAirdrop(
+        wallet.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction(sig);
+    }
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .setupGoal(
+        taker.publicKey,
+        goalId,
+        "Learn to juggle",
+        { loveGame: {} },
+        { partner: {} },
+        { targetAchieve: {} },
+        [
+          {
+            title: "Three balls",
+            deadline: new BN(now + 3),
+            incentiveAmount: new BN(amount),
+            autoReleaseAt: new BN(0),
+            prerequisites: Buffer.from([]),
+          },
+        ],
+        new BN(amount),
+        new BN(now + 3),
+        new BN(0),
+        new BN(now + 3),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+          deadManSwitch: {
+            recipient: { wallet: {} },
+            wallet: charity.publicKey,
+            gracePeriod: new BN(gracePeriod),
+            keeperTipBps: 100,
+          },
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: issuer.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: subGoalPda, isWritable: true, isSigner: false }])
+      .signers([issuer])
+      .rpc();
+  });
+
+  const trigger = (fallback: web3.PublicKey) =>
+    program.methods
+      .triggerDeadManSwitch()
+      .accounts({ goals: goalsPda, fallback, keeper: keeper.publicKey })
+      .signers([keeper])
+      .rpc();
+
+  it("waits out the grace period after unlock", async () => {
+    try {
+      await trigger(charity.publicKey);
+      expect.fail("grace period still running");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("DeadManSwitchNotReady");
+    }
+  });
+
+  it("releases unclaimed funds to the fallback with a keeper tip", async () => {
+    await sleep((3 + gracePeriod + 1) * 1000);
+
+    try {
+      await trigger(keeper.publicKey);
+      expect.fail("fallback must match the configured recipient");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("DeadManSwitchInvalid");
+    }
+
+    await trigger(charity.publicKey);
+    expect(await connection.getBalance(charity.publicKey)).to.eq(990_000);
+
+    const goal = await program.account.totalGoal.fetch(goalsPda);
+    expect(goal.releasedAmount.toNumber()).to.eq(amount);
+
+    // nothing is left for the issuer
+    try {
+      await program.methods
+        .claimUnused()
+        .accounts({ goals: goalsPda, issuer: issuer.publicKey })
+        .signers([issuer])
+        .rpc();
+      expect.fail("funds already released");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("NoFundsAvailable");
+    }
+  });
+});
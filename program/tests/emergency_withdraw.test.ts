@@ -0,0 +1,168 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("emergency_withdraw", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const issuer = web3.Keypair.generate();
+  const taker = web3.Keypair.generate();
+  const goalPdas = (goalId: BN) => {
+    const [goals] = web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gluex-goals"),
+        issuer.publicKey.toBuffer(),
+        taker.publicKey.toBuffer(),
+        Buffer.from(goalId.toArray("le", 8)),
+      ],
+      program.programId
+    );
+    const [subGoal] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gluex-subgoal"), goals.toBuffer(), Buffer.from([0])],
+      program.programId
+    );
+    return { goals, subGoal };
+  };
+  const { goals: goalsPda, subGoal: subGoalPda } = goalPdas(new BN(51));
+  const amount = 1_000_000;
+
+  const setupGoal = async (goalId: BN) => {
+    const { goals, subGoal } = goalPdas(goalId);
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .setupGoal(
+        taker.publicKey,
+        goalId,
+        "Renovate the kitchen",
+        { loveGame: {} },
+        { partner: {} },
+        { targetAchieve: {} },
+        [
+          {
+            title: "Cabinets installed",
+            deadline: new BN(now + 86_400),
+            incentiveAmount: new BN(amount),
+            autoReleaseAt: new BN(0),
+            prerequisites: Buffer.from([]),
+          },
+        ],
+        new BN(amount),
+        new BN(now + 86_400),
+        new BN(0),
+        new BN(now + 172_800),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+          deadManSwitch: null,
+        }
+      )
+      .accounts({
+        goals,
+        payer: issuer.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: subGoal, isWritable: true, isSigner: false }])
+      .signers([issuer])
+      .rpc();
+  };
+
+  before(async () => {
+    for (const wallet of [issuer, taker]) {
+      const sig = await connection.requestAirdrop(
+        wallet.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction(sig);
+    }
+    await setupGoal(new BN(51));
+  });
+
+  const withdraw = (takerAmount: number, signers: web3.Keypair[], goals = goalsPda) =>
+    program.methods
+      .emergencyWithdraw(new BN(takerAmount))
+      .accounts({
+        goals,
+        issuer: issuer.publicKey,
+        taker: taker.publicKey,
+      })
+      .signers(signers)
+      .rpc();
+
+  it("needs both signatures", async () => {
+    try {
+      await withdraw(400_000, [issuer]);
+      expect.fail("taker must co-sign");
+    } catch (err: any) {
+      expect(String(err)).to.match(/Signature verification failed|Missing signature/i);
+    }
+  });
+
+  it("rejects a split larger than the remaining funds", async () => {
+    try {
+      await withdraw(amount + 1, [issuer, taker]);
+      expect.fail("split exceeds escrow");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("EmergencySplitInvalid");
+    }
+  });
+
+  it("splits the escrow before unlock and ends the goal", async () => {
+    const takerBefore = await connection.getBalance(taker.publicKey);
+    await withdraw(400_000, [issuer, taker]);
+    const takerAfter = await connection.getBalance(taker.publicKey);
+    // transaction fees are paid by the provider wallet
+    expect(takerAfter - takerBefore).to.eq(400_000);
+
+    const goal = await program.account.totalGoal.fetch(goalsPda);
+    expect(goal.emergencyExited).to.eq(1);
+    expect(goal.releasedAmount.toNumber()).to.eq(amount);
+
+    try {
+      await program.methods
+        .submitProof(0, "https://late.example/proof")
+        .accounts({ goals: goalsPda, subGoal: subGoalPda, taker: taker.publicKey })
+        .signers([taker])
+        .rpc();
+      expect.fail("goal is closed");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("GoalEmergencyExited");
+    }
+  });
+
+  it("still ends a goal whose escrow is already paid out", async () => {
+    const goalId = new BN(52);
+    const { goals, subGoal } = goalPdas(goalId);
+    await setupGoal(goalId);
+    await program.methods
+      .submitProof(0, "https://done.example/proof")
+      .accounts({ goals, subGoal, taker: taker.publicKey })
+      .signers([taker])
+      .rpc();
+    await program.methods
+      .reviewSubgoal(0, true)
+      .accounts({
+        goals,
+        subGoal,
+        issuer: issuer.publicKey,
+        takerAccount: taker.publicKey,
+        beneficiaries: null,
+      })
+      .signers([issuer])
+      .rpc();
+
+    await withdraw(0, [issuer, taker], goals);
+    const goal = await program.account.totalGoal.fetch(goals);
+    expect(goal.emergencyExited).to.eq(1);
+    expect(goal.releasedAmount.toNumber()).to.eq(amount);
+  });
+});
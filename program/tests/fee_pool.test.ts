@@ -10,6 +10,12 @@ describe("fee_pool", () => {
 
   const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
 
+  const subGoalPda = (goal: web3.PublicKey, index: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gluex-subgoal"), goal.toBuffer(), Buffer.from([index])],
+      program.programId
+    )[0];
+
   let feePoolPda: web3.PublicKey;
 
   it("creates fee pool with default params 1/1000", async () => {
@@ -72,6 +78,7 @@ describe("fee_pool", () => {
         deadline: new BN(now + 3600),
         incentiveAmount: new BN(500),
         autoReleaseAt: new BN(0),
+        prerequisites: [],
       },
     ] as any[];
 
@@ -79,6 +86,10 @@ describe("fee_pool", () => {
       startTime: new BN(0),
       surpriseTime: new BN(0),
       checkpointInterval: new BN(0),
+      surpriseWindow: null,
+      sealedMessage: null,
+      sequential: false,
+      latePenalty: null,
     };
 
     await program.methods
@@ -102,6 +113,9 @@ describe("fee_pool", () => {
         feePool: feePoolPda,
         systemProgram: web3.SystemProgram.programId,
       })
+      .remainingAccounts([
+        { pubkey: subGoalPda(goalsPda, 0), isWritable: true, isSigner: false },
+      ])
       .rpc();
 
     const poolInfo =
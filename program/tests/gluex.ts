@@ -2,6 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import BN from "bn.js";
 import { expect } from "chai";
 import * as web3 from "@solana/web3.js";
+import { createHash, randomBytes } from "crypto";
 import type { Gluex } from "../target/types/gluex";
 
 describe("gluex", () => {
@@ -10,6 +11,19 @@ describe("gluex", () => {
 
   const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
 
+  const subGoalPda = (goal: web3.PublicKey, index: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gluex-subgoal"), goal.toBuffer(), Buffer.from([index])],
+      program.programId
+    )[0];
+
+  const subGoalMetas = (goal: web3.PublicKey, from: number, count: number) =>
+    Array.from({ length: count }, (_, offset) => ({
+      pubkey: subGoalPda(goal, from + offset),
+      isWritable: true,
+      isSigner: false,
+    }));
+
   it("creates and stores a staged goal", async () => {
     const taker = web3.Keypair.generate();
     const lamports = 2 * web3.LAMPORTS_PER_SOL;
@@ -38,12 +52,14 @@ describe("gluex", () => {
         deadline: new BN(now + 3600),
         incentiveAmount: new BN(Math.floor(totalAmount / 2)),
         autoReleaseAt: new BN(0),
+        prerequisites: [],
       },
       {
         title: "Launch milestone",
         deadline: new BN(now + 7200),
         incentiveAmount: new BN(Math.floor(totalAmount / 2)),
         autoReleaseAt: new BN(0),
+        prerequisites: [0],
       },
     ];
 
@@ -51,6 +67,10 @@ describe("gluex", () => {
       start_time: new BN(now + 60),
       surprise_time: new BN(0),
       checkpoint_interval: new BN(0),
+      surpriseWindow: null,
+      sealedMessage: null,
+      sequential: false,
+      latePenalty: null,
     };
 
     // Use TargetAchieve enum variant
@@ -77,6 +97,7 @@ describe("gluex", () => {
         feePool: null,
         systemProgram: web3.SystemProgram.programId,
       })
+      .remainingAccounts(subGoalMetas(goalsPda, 0, subGoals.length))
       .rpc();
 
     console.log(`Use 'solana confirm -v ${txHash}' to see the logs`);
@@ -91,5 +112,264 @@ describe("gluex", () => {
     expect(goalAccount.taker.equals(taker.publicKey)).to.be.true;
     expect(goalAccount.activeSubGoals).to.eq(2);
     expect(goalAccount.totalIncentiveAmount.toNumber()).to.eq(totalAmount);
+    expect(goalAccount.allocatedAmount.toNumber()).to.eq(totalAmount);
+
+    const launch = await program.account.subGoalAccount.fetch(
+      subGoalPda(goalsPda, 1)
+    );
+    expect(launch.index).to.eq(1);
+    expect(launch.prerequisites.toNumber()).to.eq(1);
+  });
+
+  it("appends milestones after setup", async () => {
+    const taker = web3.Keypair.generate();
+    const now = Math.floor(Date.now() / 1000);
+    const goalIdBn = new BN(now + 3);
+    const [goalsPda] = web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gluex-goals"),
+        program.provider.publicKey.toBuffer(),
+        taker.publicKey.toBuffer(),
+        Buffer.from(goalIdBn.toArray("le", 8)),
+      ],
+      program.programId
+    );
+    const milestone = (title: string, offset: number, prerequisites: number[]) => ({
+      title,
+      deadline: new BN(now + offset),
+      incentiveAmount: new BN(100_000),
+      autoReleaseAt: new BN(0),
+      prerequisites,
+    });
+
+    await program.methods
+      .setupGoal(
+        taker.publicKey,
+        goalIdBn,
+        "Semester plan",
+        { loveGame: {} },
+        { parents: {} },
+        { targetAchieve: {} },
+        [milestone("Week 1 reading", 3600, [])],
+        new BN(1_000_000),
+        new BN(now + 86400),
+        new BN(0),
+        new BN(now + 86400),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: true,
+          latePenalty: null,
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: program.provider.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts(subGoalMetas(goalsPda, 0, 1))
+      .rpc();
+
+    const more = [
+      milestone("Week 2 reading", 7200, [0]),
+      milestone("Week 3 reading", 10800, [1]),
+    ];
+    await program.methods
+      .addSubgoals(more)
+      .accounts({
+        goals: goalsPda,
+        issuer: program.provider.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts(subGoalMetas(goalsPda, 1, more.length))
+      .rpc();
+
+    const goalAccount = await program.account.totalGoal.fetch(goalsPda);
+    expect(goalAccount.activeSubGoals).to.eq(3);
+    expect(goalAccount.allocatedAmount.toNumber()).to.eq(300_000);
+
+    const last = await program.account.subGoalAccount.fetch(
+      subGoalPda(goalsPda, 2)
+    );
+    expect(last.goal.equals(goalsPda)).to.be.true;
+    expect(last.prerequisites.toNumber()).to.eq(1 << 1);
+  });
+
+  it("fixes a randomized surprise time inside the window on reveal", async () => {
+    const taker = web3.Keypair.generate();
+    const now = Math.floor(Date.now() / 1000);
+    const goalIdBn = new BN(now + 1);
+    const [goalsPda] = web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gluex-goals"),
+        program.provider.publicKey.toBuffer(),
+        taker.publicKey.toBuffer(),
+        Buffer.from(goalIdBn.toArray("le", 8)),
+      ],
+      program.programId
+    );
+
+    const secret = randomBytes(32);
+    const commitment = createHash("sha256").update(secret).digest();
+    const windowStart = now + 600;
+    const windowEnd = now + 3600;
+
+    await program.methods
+      .setupGoal(
+        taker.publicKey,
+        goalIdBn,
+        "Surprise within a window",
+        { loveGame: {} },
+        { lover: {} },
+        { surpriseTime: {} },
+        [],
+        new BN(web3.LAMPORTS_PER_SOL / 10),
+        new BN(windowEnd),
+        new BN(0),
+        new BN(windowEnd),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(windowStart),
+          checkpointInterval: new BN(0),
+          surpriseWindow: {
+            windowEnd: new BN(windowEnd),
+            commitment: Array.from(commitment),
+          },
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: program.provider.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts(subGoalMetas(goalsPda, 0, 1))
+      .rpc();
+
+    let goalAccount = await program.account.totalGoal.fetch(goalsPda);
+    expect(goalAccount.surpriseTriggerTs.toNumber()).to.eq(windowEnd);
+    expect(goalAccount.surpriseRevealed).to.equal(0);
+
+    const reveal = () =>
+      program.methods
+        .revealSurprise(Array.from(secret))
+        .accounts({
+          goals: goalsPda,
+          subGoal: subGoalPda(goalsPda, 0),
+          issuer: program.provider.publicKey,
+          recentSlothashes: web3.SYSVAR_SLOT_HASHES_PUBKEY,
+        })
+        .rpc();
+
+    // the reveal slot recorded at setup has to pass first
+    const revealSlot = goalAccount.surpriseRevealSlot.toNumber();
+    while ((await program.provider.connection.getSlot()) <= revealSlot) {
+      await new Promise((resolve) => setTimeout(resolve, 400));
+    }
+    await reveal();
+
+    goalAccount = await program.account.totalGoal.fetch(goalsPda);
+    const triggerTs = goalAccount.surpriseTriggerTs.toNumber();
+    expect(goalAccount.surpriseRevealed).to.equal(1);
+    expect(triggerTs).to.be.within(windowStart, windowEnd);
+
+    // the time is fixed once
+    try {
+      await reveal();
+      expect.fail("already revealed");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("SurpriseAlreadyRevealed");
+    }
+  });
+
+  it("hands a goal over to a new taker at the same address", async () => {
+    const taker = web3.Keypair.generate();
+    const newTaker = web3.Keypair.generate();
+    const now = Math.floor(Date.now() / 1000);
+    const goalIdBn = new BN(now + 2);
+    const [goalsPda] = web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gluex-goals"),
+        program.provider.publicKey.toBuffer(),
+        taker.publicKey.toBuffer(),
+        Buffer.from(goalIdBn.toArray("le", 8)),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .setupGoal(
+        taker.publicKey,
+        goalIdBn,
+        "Transferable goal",
+        { loveGame: {} },
+        { bosstaff: {} },
+        { targetAchieve: {} },
+        [
+          {
+            title: "Quarterly KPI",
+            deadline: new BN(now + 3600),
+            incentiveAmount: new BN(1_000_000),
+            autoReleaseAt: new BN(0),
+            prerequisites: [],
+          },
+        ],
+        new BN(1_000_000),
+        new BN(now + 3600),
+        new BN(0),
+        new BN(now + 7200),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: program.provider.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts(subGoalMetas(goalsPda, 0, 1))
+      .rpc();
+
+    // the taker cannot pick a successor without the issuer
+    try {
+      await program.methods
+        .proposeTakerTransfer(newTaker.publicKey)
+        .accounts({ goals: goalsPda, authority: taker.publicKey })
+        .signers([taker])
+        .rpc();
+      expect.fail("only the issuer proposes a new taker");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("UnauthorizedSigner");
+    }
+
+    await program.methods
+      .proposeTakerTransfer(newTaker.publicKey)
+      .accounts({ goals: goalsPda, authority: program.provider.publicKey })
+      .rpc();
+
+    await program.methods
+      .acceptTakerTransfer()
+      .accounts({ goals: goalsPda, newParty: newTaker.publicKey })
+      .signers([newTaker])
+      .rpc();
+
+    const goalAccount = await program.account.totalGoal.fetch(goalsPda);
+    expect(goalAccount.taker.equals(newTaker.publicKey)).to.be.true;
+    expect(goalAccount.seedTaker.equals(taker.publicKey)).to.be.true;
+    expect(goalAccount.pendingTaker.equals(web3.PublicKey.default)).to.be.true;
   });
 });
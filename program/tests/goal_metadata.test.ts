@@ -0,0 +1,145 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import { createHash } from "crypto";
+import type { Gluex } from "../target/types/gluex";
+
+describe("goal_metadata", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+
+  const subGoalPda = (goal: web3.PublicKey, index: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gluex-subgoal"), goal.toBuffer(), Buffer.from([index])],
+      program.programId
+    )[0];
+
+  const metadataPda = (goal: web3.PublicKey) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("goal-metadata"), goal.toBuffer()],
+      program.programId
+    )[0];
+
+  const sha256 = (body: string) =>
+    Array.from(createHash("sha256").update(body).digest());
+
+  const taker = web3.Keypair.generate();
+  const stranger = web3.Keypair.generate();
+
+  it("pins off-chain metadata to the goal by content hash", async () => {
+    const sig = await program.provider.connection.requestAirdrop(
+      stranger.publicKey,
+      web3.LAMPORTS_PER_SOL
+    );
+    await program.provider.connection.confirmTransaction(sig);
+
+    const now = Math.floor(Date.now() / 1000);
+    const goalId = new BN(now + 11);
+    const [goalsPda] = web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gluex-goals"),
+        program.provider.publicKey.toBuffer(),
+        taker.publicKey.toBuffer(),
+        Buffer.from(goalId.toArray("le", 8)),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .setupGoal(
+        taker.publicKey,
+        goalId,
+        "Learn Spanish",
+        { loveGame: {} },
+        { partner: {} },
+        { targetAchieve: {} },
+        [
+          {
+            title: "Pass the B1 exam",
+            deadline: new BN(now + 3600),
+            incentiveAmount: new BN(1_000_000),
+            autoReleaseAt: new BN(0),
+            prerequisites: [],
+          },
+        ],
+        new BN(1_000_000),
+        new BN(now + 3600),
+        new BN(0),
+        new BN(now + 7200),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: program.provider.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: subGoalPda(goalsPda, 0), isWritable: true, isSigner: false },
+      ])
+      .rpc();
+
+    const document = JSON.stringify({
+      title: "Learn Spanish",
+      description: "Weekly lessons and a B1 exam by the end of the year.",
+      localized: { es: { title: "Aprender español" } },
+    });
+
+    await program.methods
+      .setGoalMetadata("ipfs://goal-metadata-v1", sha256(document))
+      .accounts({
+        goals: goalsPda,
+        metadata: metadataPda(goalsPda),
+        issuer: program.provider.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    let metadata = await program.account.goalMetadata.fetch(metadataPda(goalsPda));
+    expect(metadata.goal.equals(goalsPda)).to.be.true;
+    expect(metadata.uri).to.eq("ipfs://goal-metadata-v1");
+    expect(metadata.contentHash).to.deep.eq(sha256(document));
+    expect(metadata.revision).to.eq(0);
+
+    // a tampered document no longer matches the stored hash
+    const tampered = document.replace("B1", "C2");
+    expect(sha256(tampered)).to.not.deep.eq(metadata.contentHash);
+
+    await program.methods
+      .setGoalMetadata("ipfs://goal-metadata-v2", sha256(tampered))
+      .accounts({
+        goals: goalsPda,
+        metadata: metadataPda(goalsPda),
+        issuer: program.provider.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    metadata = await program.account.goalMetadata.fetch(metadataPda(goalsPda));
+    expect(metadata.revision).to.eq(1);
+
+    try {
+      await program.methods
+        .setGoalMetadata("ipfs://spoofed", sha256("{}"))
+        .accounts({
+          goals: goalsPda,
+          metadata: metadataPda(goalsPda),
+          issuer: stranger.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([stranger])
+        .rpc();
+      expect.fail("only the issuer may set metadata");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("UnauthorizedSigner");
+    }
+  });
+});
@@ -0,0 +1,154 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("goal_rollover", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const mentor = web3.Keypair.generate();
+  const student = web3.Keypair.generate();
+  const firstId = new BN(49);
+  const nextId = new BN(50);
+  const goalPda = (id: BN) =>
+    web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gluex-goals"),
+        mentor.publicKey.toBuffer(),
+        student.publicKey.toBuffer(),
+        Buffer.from(id.toArray("le", 8)),
+      ],
+      program.programId
+    )[0];
+  const subGoalPda = (goal: web3.PublicKey, index: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gluex-subgoal"), goal.toBuffer(), Buffer.from([index])],
+      program.programId
+    )[0];
+  const [rolloverPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("goal-rollover"), goalPda(firstId).toBuffer()],
+    program.programId
+  );
+  const config = {
+    startTime: new BN(0),
+    surpriseTime: new BN(0),
+    checkpointInterval: new BN(0),
+    surpriseWindow: null,
+    sealedMessage: null,
+    sequential: false,
+    latePenalty: null,
+    deadManSwitch: null,
+  };
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  before(async () => {
+    const sig = await connection.requestAirdrop(
+      mentor.publicKey,
+      web3.LAMPORTS_PER_SOL
+    );
+    await connection.confirmTransaction(sig);
+
+    const now = Math.floor(Date.now() / 1000);
+    const predecessor = goalPda(firstId);
+    await program.methods
+      .setupGoal(
+        student.publicKey,
+        firstId,
+        "Spring term",
+        { loveGame: {} },
+        { parents: {} },
+        { targetAchieve: {} },
+        [0, 1].map((i) => ({
+          title: `Chapter ${i + 1}`,
+          deadline: new BN(now + 3),
+          incentiveAmount: new BN(500_000),
+          autoReleaseAt: new BN(0),
+          prerequisites: Buffer.from([]),
+        })),
+        new BN(1_000_000),
+        new BN(now + 3),
+        new BN(0),
+        new BN(now + 3),
+        config
+      )
+      .accounts({
+        goals: predecessor,
+        payer: mentor.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts(
+        [0, 1].map((i) => ({
+          pubkey: subGoalPda(predecessor, i),
+          isWritable: true,
+          isSigner: false,
+        }))
+      )
+      .signers([mentor])
+      .rpc();
+
+    // only the first chapter gets done
+    await program.methods
+      .reviewSubgoal(0, true)
+      .accounts({
+        goals: predecessor,
+        subGoal: subGoalPda(predecessor, 0),
+        issuer: mentor.publicKey,
+        takerAccount: student.publicKey,
+      })
+      .signers([mentor])
+      .rpc();
+  });
+
+  it("rolls the unreleased remainder into a linked successor", async () => {
+    await sleep(4_000);
+    const now = Math.floor(Date.now() / 1000);
+    const predecessor = goalPda(firstId);
+    const successor = goalPda(nextId);
+
+    await program.methods
+      .rolloverGoal(student.publicKey, nextId, {
+        description: "Summer term",
+        eventype: { targetAchieve: {} },
+        subGoals: [
+          {
+            title: "Chapter 2, again",
+            deadline: new BN(now + 3600),
+            incentiveAmount: new BN(500_000),
+            autoReleaseAt: new BN(0),
+            prerequisites: Buffer.from([]),
+          },
+        ],
+        completionTime: new BN(now + 3600),
+        lockedAmount: new BN(0),
+        unlockTime: new BN(now + 7200),
+        config,
+      })
+      .accounts({
+        predecessor,
+        goals: successor,
+        rollover: rolloverPda,
+        issuer: mentor.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: subGoalPda(successor, 0), isWritable: true, isSigner: false },
+      ])
+      .signers([mentor])
+      .rpc();
+
+    const next = await program.account.totalGoal.fetch(successor);
+    expect(next.predecessor.toBase58()).to.eq(predecessor.toBase58());
+    expect(next.depositedAmount.toNumber()).to.eq(500_000);
+
+    const link = await program.account.goalRollover.fetch(rolloverPda);
+    expect(link.successor.toBase58()).to.eq(successor.toBase58());
+    expect(link.amount.toNumber()).to.eq(500_000);
+
+    const prev = await program.account.totalGoal.fetch(predecessor);
+    expect(prev.releasedAmount.toNumber()).to.eq(prev.depositedAmount.toNumber());
+  });
+});
@@ -0,0 +1,149 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("goal_sponsor", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+
+  const subGoalPda = (goal: web3.PublicKey, index: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gluex-subgoal"), goal.toBuffer(), Buffer.from([index])],
+      program.programId
+    )[0];
+
+  const taker = web3.Keypair.generate();
+  const sponsor = web3.Keypair.generate();
+
+  let goalsPda: web3.PublicKey;
+  let sponsorRecordPda: web3.PublicKey;
+
+  before(async () => {
+    const sig = await program.provider.connection.requestAirdrop(
+      sponsor.publicKey,
+      2 * web3.LAMPORTS_PER_SOL
+    );
+    await program.provider.connection.confirmTransaction(sig);
+
+    const now = Math.floor(Date.now() / 1000);
+    const goalIdBn = new BN(now);
+    [goalsPda] = web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gluex-goals"),
+        program.provider.publicKey.toBuffer(),
+        taker.publicKey.toBuffer(),
+        Buffer.from(goalIdBn.toArray("le", 8)),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .setupGoal(
+        taker.publicKey,
+        goalIdBn,
+        "Sponsored goal",
+        { loveGame: {} },
+        { parents: {} },
+        { targetAchieve: {} },
+        [
+          {
+            title: "Read ten books",
+            deadline: new BN(now + 3600),
+            incentiveAmount: new BN(1_000_000),
+            autoReleaseAt: new BN(0),
+            prerequisites: [],
+          },
+        ],
+        new BN(1_000_000),
+        new BN(now + 3600),
+        new BN(0),
+        new BN(now + 7200),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: program.provider.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: subGoalPda(goalsPda, 0), isWritable: true, isSigner: false },
+      ])
+      .rpc();
+
+    [sponsorRecordPda] = web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("goal-sponsor"),
+        goalsPda.toBuffer(),
+        sponsor.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+  });
+
+  it("adds earmarked funds from a third-party sponsor", async () => {
+    await program.methods
+      .addFunds(new BN(500_000))
+      .accounts({
+        goals: goalsPda,
+        sponsorRecord: sponsorRecordPda,
+        sponsor: sponsor.publicKey,
+        feePool: null,
+        subGoal: subGoalPda(goalsPda, 0),
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([sponsor])
+      .rpc();
+
+    const goal = await program.account.totalGoal.fetch(goalsPda);
+    expect(goal.depositedAmount.toNumber()).to.eq(1_500_000);
+    expect(goal.sponsoredAmount.toNumber()).to.eq(500_000);
+    const subGoal = await program.account.subGoalAccount.fetch(
+      subGoalPda(goalsPda, 0)
+    );
+    expect(subGoal.incentiveAmount.toNumber()).to.eq(1_500_000);
+
+    const record = await program.account.goalSponsor.fetch(sponsorRecordPda);
+    expect(record.sponsor.equals(sponsor.publicKey)).to.be.true;
+    expect(record.contributedAmount.toNumber()).to.eq(500_000);
+    expect(record.refunded).to.be.false;
+  });
+
+  it("spreads general top-ups over the open subgoals", async () => {
+    await program.methods
+      .addFunds(new BN(250_000))
+      .accounts({
+        goals: goalsPda,
+        sponsorRecord: sponsorRecordPda,
+        sponsor: sponsor.publicKey,
+        feePool: null,
+        subGoal: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: subGoalPda(goalsPda, 0), isWritable: true, isSigner: false },
+      ])
+      .signers([sponsor])
+      .rpc();
+
+    const subGoal = await program.account.subGoalAccount.fetch(
+      subGoalPda(goalsPda, 0)
+    );
+    expect(subGoal.incentiveAmount.toNumber()).to.eq(1_750_000);
+    const goal = await program.account.totalGoal.fetch(goalsPda);
+    expect(goal.allocatedAmount.toNumber()).to.eq(1_750_000);
+
+    const record = await program.account.goalSponsor.fetch(sponsorRecordPda);
+    expect(record.contributedAmount.toNumber()).to.eq(750_000);
+  });
+});
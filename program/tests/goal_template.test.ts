@@ -0,0 +1,189 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("goal_template", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const subGoalPda = (goal: web3.PublicKey, index: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gluex-subgoal"), goal.toBuffer(), Buffer.from([index])],
+      program.programId
+    )[0];
+
+  const templatePda = (creator: web3.PublicKey, templateId: BN) =>
+    web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("goal-template"),
+        creator.toBuffer(),
+        Buffer.from(templateId.toArray("le", 8)),
+      ],
+      program.programId
+    )[0];
+
+  const goalPda = (issuer: web3.PublicKey, taker: web3.PublicKey, goalId: BN) =>
+    web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gluex-goals"),
+        issuer.toBuffer(),
+        taker.toBuffer(),
+        Buffer.from(goalId.toArray("le", 8)),
+      ],
+      program.programId
+    )[0];
+
+  const coach = web3.Keypair.generate();
+  const assistant = web3.Keypair.generate();
+  const clients = [web3.Keypair.generate(), web3.Keypair.generate()];
+  const templateId = new BN(1);
+
+  before(async () => {
+    for (const wallet of [coach, clients[0]]) {
+      const sig = await connection.requestAirdrop(
+        wallet.publicKey,
+        3 * web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction(sig);
+    }
+  });
+
+  it("instantiates the same program for several clients", async () => {
+    await program.methods
+      .createGoalTemplate(templateId, {
+        description: "8-week strength program",
+        room: { loveGame: {} },
+        relations: { bosstaff: {} },
+        eventype: { targetAchieve: {} },
+        subGoals: [
+          {
+            title: "Week 4 check-in",
+            deadlineOffset: new BN(4 * 7 * 86400),
+            incentiveBps: 4_000,
+            autoReleaseOffset: new BN(0),
+            prerequisites: Buffer.from([]),
+          },
+          {
+            title: "Week 8 final test",
+            deadlineOffset: new BN(8 * 7 * 86400),
+            incentiveBps: 6_000,
+            autoReleaseOffset: new BN(0),
+            prerequisites: Buffer.from([0]),
+          },
+        ],
+        duration: new BN(8 * 7 * 86400),
+        unlockDelay: new BN(86400),
+        lockedBps: 1_000,
+        checkpointInterval: new BN(0),
+        surpriseOffset: new BN(0),
+        sequential: true,
+        latePenalty: null,
+        feePool: web3.PublicKey.default,
+        verifier: assistant.publicKey,
+      })
+      .accounts({
+        template: templatePda(coach.publicKey, templateId),
+        creator: coach.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([coach])
+      .rpc();
+
+    const start = Math.floor(Date.now() / 1000) + 60;
+    const amount = 1_000_000;
+    for (const [i, client] of clients.entries()) {
+      const goalId = new BN(i);
+      const goal = goalPda(coach.publicKey, client.publicKey, goalId);
+      await program.methods
+        .setupGoalFromTemplate(client.publicKey, goalId, new BN(amount), new BN(start))
+        .accounts({
+          goals: goal,
+          payer: coach.publicKey,
+          feePool: null,
+          template: templatePda(coach.publicKey, templateId),
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .remainingAccounts([0, 1].map((index) => ({
+          pubkey: subGoalPda(goal, index),
+          isWritable: true,
+          isSigner: false,
+        })))
+        .signers([coach])
+        .rpc();
+
+      const goalAccount = await program.account.totalGoal.fetch(goal);
+      expect(goalAccount.activeSubGoals).to.eq(2);
+      expect(goalAccount.completionTime.toNumber()).to.eq(start + 8 * 7 * 86400);
+      expect(goalAccount.lockedAmount.toNumber()).to.eq(amount / 10);
+      expect(goalAccount.sequential).to.eq(1);
+
+      const final = await program.account.subGoalAccount.fetch(subGoalPda(goal, 1));
+      expect(final.incentiveAmount.toNumber()).to.eq(600_000);
+      expect(final.deadline.toNumber()).to.eq(start + 8 * 7 * 86400);
+      expect(final.verifier.equals(assistant.publicKey)).to.be.true;
+    }
+
+    const template = await program.account.goalTemplate.fetch(
+      templatePda(coach.publicKey, templateId)
+    );
+    expect(template.instances.toNumber()).to.eq(clients.length);
+
+    // the template's verifier reviews on the coach's behalf
+    const goal = goalPda(coach.publicKey, clients[0].publicKey, new BN(0));
+    await program.methods
+      .submitProof(0, "ipfs://week-4")
+      .accounts({
+        goals: goal,
+        subGoal: subGoalPda(goal, 0),
+        taker: clients[0].publicKey,
+      })
+      .signers([clients[0]])
+      .rpc();
+    await program.methods
+      .reviewSubgoal(0, true)
+      .accounts({
+        goals: goal,
+        subGoal: subGoalPda(goal, 0),
+        issuer: assistant.publicKey,
+        takerAccount: clients[0].publicKey,
+      })
+      .signers([assistant])
+      .rpc();
+    const checkIn = await program.account.subGoalAccount.fetch(subGoalPda(goal, 0));
+    expect(checkIn.status).to.deep.eq({ paid: {} });
+  });
+
+  it("keeps templates private to their creator", async () => {
+    const goalId = new BN(7);
+    const goal = goalPda(clients[0].publicKey, coach.publicKey, goalId);
+    try {
+      await program.methods
+        .setupGoalFromTemplate(
+          coach.publicKey,
+          goalId,
+          new BN(1_000_000),
+          new BN(Math.floor(Date.now() / 1000) + 60)
+        )
+        .accounts({
+          goals: goal,
+          payer: clients[0].publicKey,
+          feePool: null,
+          template: templatePda(coach.publicKey, templateId),
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .remainingAccounts([0, 1].map((index) => ({
+          pubkey: subGoalPda(goal, index),
+          isWritable: true,
+          isSigner: false,
+        })))
+        .signers([clients[0]])
+        .rpc();
+      expect.fail("only the creator may instantiate the template");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("TemplateMismatch");
+    }
+  });
+});
@@ -0,0 +1,151 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("group_challenge", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const organizer = web3.Keypair.generate();
+  const runners = Array.from({ length: 3 }, () => web3.Keypair.generate());
+  const challengeId = new BN(1);
+  const [challengePda] = web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("group-challenge"),
+      organizer.publicKey.toBuffer(),
+      Buffer.from(challengeId.toArray("le", 8)),
+    ],
+    program.programId
+  );
+  const recordPda = (participant: web3.PublicKey) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("challenge-participant"), challengePda.toBuffer(), participant.toBuffer()],
+      program.programId
+    )[0];
+  const stake = 50_000_000;
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  before(async () => {
+    for (const wallet of [organizer, ...runners]) {
+      const sig = await connection.requestAirdrop(
+        wallet.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction(sig);
+    }
+  });
+
+  it("splits the pot among finishers, dropouts' stakes included", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createGroupChallenge(challengeId, {
+        description: "Run 5k twice this month",
+        stakeAmount: new BN(stake),
+        checkpoints: [new BN(now + 8), new BN(now + 10)],
+        joinDeadline: new BN(now + 4),
+        settleTime: new BN(now + 14),
+        maxParticipants: 5,
+        feePool: web3.PublicKey.default,
+      })
+      .accounts({
+        challenge: challengePda,
+        organizer: organizer.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([organizer])
+      .rpc();
+
+    try {
+      await program.methods
+        .joinGroupChallenge()
+        .accounts({
+          challenge: challengePda,
+          participantRecord: recordPda(organizer.publicKey),
+          participant: organizer.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([organizer])
+        .rpc();
+      expect.fail("the organizer joined their own challenge");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("UnauthorizedSigner");
+    }
+
+    for (const runner of runners) {
+      await program.methods
+        .joinGroupChallenge()
+        .accounts({
+          challenge: challengePda,
+          participantRecord: recordPda(runner.publicKey),
+          participant: runner.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([runner])
+        .rpc();
+    }
+
+    // the first two runners prove both checkpoints, the last drops out after one
+    for (const [i, runner] of runners.entries()) {
+      const checkpoints = i < 2 ? [0, 1] : [0];
+      for (const checkpoint of checkpoints) {
+        await program.methods
+          .submitChallengeProof(checkpoint, `ipfs://run-${i}-${checkpoint}`)
+          .accounts({
+            challenge: challengePda,
+            participantRecord: recordPda(runner.publicKey),
+            participant: runner.publicKey,
+          })
+          .signers([runner])
+          .rpc();
+        await program.methods
+          .reviewChallengeProof(checkpoint, true)
+          .accounts({
+            challenge: challengePda,
+            participantRecord: recordPda(runner.publicKey),
+            organizer: organizer.publicKey,
+          })
+          .signers([organizer])
+          .rpc();
+      }
+    }
+
+    let challenge = await program.account.groupChallenge.fetch(challengePda);
+    expect(challenge.participants).to.eq(3);
+    expect(challenge.finishers).to.eq(2);
+    expect(challenge.pot.toNumber()).to.eq(3 * stake);
+
+    await sleep(15_000);
+    await program.methods
+      .settleGroupChallenge()
+      .accounts({ challenge: challengePda, feePool: null })
+      .rpc();
+    challenge = await program.account.groupChallenge.fetch(challengePda);
+    expect(challenge.payoutPerFinisher.toNumber()).to.eq((3 * stake) / 2);
+
+    const before = await connection.getBalance(runners[0].publicKey);
+    for (const runner of runners) {
+      await program.methods
+        .claimChallengePayout()
+        .accounts({
+          challenge: challengePda,
+          participantRecord: recordPda(runner.publicKey),
+          participant: runner.publicKey,
+        })
+        .signers([runner])
+        .rpc();
+    }
+    const after = await connection.getBalance(runners[0].publicKey);
+    expect(after - before).to.be.greaterThan((3 * stake) / 2);
+
+    await program.methods
+      .closeGroupChallenge()
+      .accounts({ challenge: challengePda, organizer: organizer.publicKey })
+      .signers([organizer])
+      .rpc();
+    expect(await connection.getAccountInfo(challengePda)).to.be.null;
+  });
+});
@@ -0,0 +1,143 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("joint_savings", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const alice = web3.Keypair.generate();
+  const bob = web3.Keypair.generate();
+  const travelAgent = web3.Keypair.generate();
+  const contribution = 1_000_000;
+
+  const savingsPda = (id: BN) =>
+    web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("joint-savings"),
+        alice.publicKey.toBuffer(),
+        bob.publicKey.toBuffer(),
+        Buffer.from(id.toArray("le", 8)),
+      ],
+      program.programId
+    )[0];
+
+  const create = async (id: BN, targetAmount: number) => {
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createJointSavings(id, {
+        relations: { lover: {} },
+        destination: travelAgent.publicKey,
+        targetAmount: new BN(targetAmount),
+        targetDate: new BN(now + 3600),
+        contributionAmount: new BN(contribution),
+        period: new BN(600),
+        startTime: new BN(now),
+      })
+      .accounts({
+        savings: savingsPda(id),
+        initiator: alice.publicKey,
+        partner: bob.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([alice, bob])
+      .rpc();
+  };
+
+  const contribute = (id: BN, who: web3.Keypair, amount: number) =>
+    program.methods
+      .contributeJointSavings(new BN(amount))
+      .accounts({
+        savings: savingsPda(id),
+        contributor: who.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([who])
+      .rpc();
+
+  before(async () => {
+    for (const wallet of [alice, bob]) {
+      const sig = await connection.requestAirdrop(
+        wallet.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction(sig);
+    }
+  });
+
+  it("sends the savings to the joint destination once the target is met", async () => {
+    const id = new BN(1);
+    await create(id, 2 * contribution);
+
+    const release = () =>
+      program.methods
+        .releaseJointSavings()
+        .accounts({
+          savings: savingsPda(id),
+          destination: travelAgent.publicKey,
+          initiator: alice.publicKey,
+        })
+        .rpc();
+
+    await contribute(id, alice, contribution);
+    try {
+      await release();
+      expect.fail("target not reached yet");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("JointSavingsTargetPending");
+    }
+
+    await contribute(id, bob, contribution);
+    const savings = await program.account.jointSavings.fetch(savingsPda(id));
+    expect(savings.contributed.map((c: BN) => c.toNumber())).to.deep.eq([
+      contribution,
+      contribution,
+    ]);
+
+    await release();
+    const received = await connection.getBalance(travelAgent.publicKey);
+    expect(received).to.eq(2 * contribution);
+  });
+
+  it("refunds each side on a jointly signed early withdrawal", async () => {
+    const id = new BN(2);
+    await create(id, 10 * contribution);
+    await contribute(id, alice, 3 * contribution);
+    try {
+      await contribute(id, bob, contribution / 2);
+      expect.fail("the first period is already due");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("JointContributionBelowSchedule");
+    }
+    await contribute(id, bob, contribution);
+
+    const withdraw = (signers: web3.Keypair[]) =>
+      program.methods
+        .withdrawJointSavings()
+        .accounts({
+          savings: savingsPda(id),
+          initiator: alice.publicKey,
+          partner: bob.publicKey,
+        })
+        .signers(signers)
+        .rpc();
+
+    try {
+      await withdraw([alice]);
+      expect.fail("both partners must sign");
+    } catch (err: any) {
+      expect(String(err)).to.match(/Signature verification failed|Missing signature/i);
+    }
+
+    const bobBefore = await connection.getBalance(bob.publicKey);
+    await withdraw([alice, bob]);
+    const bobAfter = await connection.getBalance(bob.publicKey);
+    expect(bobAfter - bobBefore).to.eq(contribution);
+
+    const closed = await connection.getAccountInfo(savingsPda(id));
+    expect(closed).to.eq(null);
+  });
+});
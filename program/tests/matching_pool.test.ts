@@ -0,0 +1,215 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("matching_pool", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+
+  const subGoalPda = (goal: web3.PublicKey, index: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gluex-subgoal"), goal.toBuffer(), Buffer.from([index])],
+      program.programId
+    )[0];
+
+  const sponsor = web3.Keypair.generate();
+  const poolId = new BN(Math.floor(Date.now() / 1000));
+  let matchingPoolPda: web3.PublicKey;
+  let allowancePda: web3.PublicKey;
+
+  before(async () => {
+    const sig = await program.provider.connection.requestAirdrop(
+      sponsor.publicKey,
+      5 * web3.LAMPORTS_PER_SOL
+    );
+    await program.provider.connection.confirmTransaction(sig);
+
+    [matchingPoolPda] = web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("matching-pool"),
+        sponsor.publicKey.toBuffer(),
+        Buffer.from(poolId.toArray("le", 8)),
+      ],
+      program.programId
+    );
+    [allowancePda] = web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("matching-allowance"),
+        matchingPoolPda.toBuffer(),
+        program.provider.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+  });
+
+  it("creates and funds a matching pool", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createMatchingPool(
+        poolId,
+        {
+          ratioBps: 5_000,
+          perGoalCap: new BN(web3.LAMPORTS_PER_SOL / 10),
+          perBountyCap: new BN(0),
+          perIssuerCap: new BN(web3.LAMPORTS_PER_SOL / 10),
+          relationsMask: 1 << 1, // parents only
+          eventTypesMask: 0,
+          minReputation: new BN(0),
+          expiresAt: new BN(now + 86400),
+        },
+        new BN(web3.LAMPORTS_PER_SOL)
+      )
+      .accounts({
+        matchingPool: matchingPoolPda,
+        sponsor: sponsor.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([sponsor])
+      .rpc();
+
+    const pool = await program.account.matchingPool.fetch(matchingPoolPda);
+    expect(pool.sponsor.equals(sponsor.publicKey)).to.be.true;
+    expect(pool.ratioBps).to.eq(5_000);
+    expect(pool.totalFunded.toNumber()).to.eq(web3.LAMPORTS_PER_SOL);
+
+    await program.methods
+      .openMatchingAllowance()
+      .accounts({
+        matchingPool: matchingPoolPda,
+        allowance: allowancePda,
+        issuer: program.provider.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  const setupMatchedGoal = async (taker: web3.PublicKey, goalIdBn: BN, deposit: number) => {
+    const now = Math.floor(Date.now() / 1000);
+    const [goalsPda] = web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gluex-goals"),
+        program.provider.publicKey.toBuffer(),
+        taker.toBuffer(),
+        Buffer.from(goalIdBn.toArray("le", 8)),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .setupGoal(
+        taker,
+        goalIdBn,
+        "Matched goal",
+        { loveGame: {} },
+        { parents: {} },
+        { targetAchieve: {} },
+        [
+          {
+            title: "Finish the science project",
+            deadline: new BN(now + 3600),
+            incentiveAmount: new BN(deposit),
+            autoReleaseAt: new BN(0),
+            prerequisites: [],
+          },
+        ],
+        new BN(deposit),
+        new BN(now + 3600),
+        new BN(0),
+        new BN(now + 7200),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: program.provider.publicKey,
+        feePool: null,
+        matchingPool: matchingPoolPda,
+        matchingAllowance: allowancePda,
+        issuerProfile: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: subGoalPda(goalsPda, 0), isWritable: true, isSigner: false },
+      ])
+      .rpc();
+    return goalsPda;
+  };
+
+  it("matches an eligible goal at creation", async () => {
+    const taker = web3.Keypair.generate();
+    const deposit = 100_000_000;
+    const goalsPda = await setupMatchedGoal(
+      taker.publicKey,
+      new BN(Math.floor(Date.now() / 1000)),
+      deposit
+    );
+
+    const goal = await program.account.totalGoal.fetch(goalsPda);
+    expect(goal.matchedAmount.toNumber()).to.eq(deposit / 2);
+    expect(goal.depositedAmount.toNumber()).to.eq(deposit + deposit / 2);
+    const subGoal = await program.account.subGoalAccount.fetch(
+      subGoalPda(goalsPda, 0)
+    );
+    expect(subGoal.incentiveAmount.toNumber()).to.eq(deposit + deposit / 2);
+    expect(goal.matchingPool.equals(matchingPoolPda)).to.be.true;
+
+    const pool = await program.account.matchingPool.fetch(matchingPoolPda);
+    expect(pool.totalMatched.toNumber()).to.eq(deposit / 2);
+    const allowance = await program.account.matchingAllowance.fetch(allowancePda);
+    expect(allowance.matchedAmount.toNumber()).to.eq(deposit / 2);
+
+    // a matched subgoal cannot be approved before the taker submits a proof
+    try {
+      await program.methods
+        .reviewSubgoal(0, true)
+        .accounts({
+          goals: goalsPda,
+          subGoal: subGoalPda(goalsPda, 0),
+          issuer: program.provider.publicKey,
+          takerAccount: taker.publicKey,
+          beneficiaries: null,
+        })
+        .rpc();
+      expect.fail("approved a matched subgoal without a proof");
+    } catch (err) {
+      expect(String(err)).to.include("ProofMissing");
+    }
+  });
+
+  it("refuses to match a goal the issuer takes themselves", async () => {
+    try {
+      await setupMatchedGoal(
+        program.provider.publicKey,
+        new BN(Math.floor(Date.now() / 1000) + 1),
+        100_000_000
+      );
+      expect.fail("matched a self-taken goal");
+    } catch (err) {
+      expect(String(err)).to.include("MatchingPoolIneligible");
+    }
+  });
+
+  it("stops matching once the issuer cap is spent", async () => {
+    // the first goal drew half of the 0.1 SOL issuer cap; this one is capped
+    // at the remaining half even though the goal cap would allow more
+    const taker = web3.Keypair.generate();
+    const goalsPda = await setupMatchedGoal(
+      taker.publicKey,
+      new BN(Math.floor(Date.now() / 1000) + 2),
+      200_000_000
+    );
+    const goal = await program.account.totalGoal.fetch(goalsPda);
+    expect(goal.matchedAmount.toNumber()).to.eq(50_000_000);
+    const allowance = await program.account.matchingAllowance.fetch(allowancePda);
+    expect(allowance.matchedAmount.toNumber()).to.eq(web3.LAMPORTS_PER_SOL / 10);
+  });
+});
@@ -0,0 +1,165 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("recurring_goal", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const subGoalPda = (goal: web3.PublicKey, index: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gluex-subgoal"), goal.toBuffer(), Buffer.from([index])],
+      program.programId
+    )[0];
+
+  const parent = web3.Keypair.generate();
+  const child = web3.Keypair.generate();
+  const goalId = new BN(52);
+  const [goalsPda] = web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("gluex-goals"),
+      parent.publicKey.toBuffer(),
+      child.publicKey.toBuffer(),
+      Buffer.from(goalId.toArray("le", 8)),
+    ],
+    program.programId
+  );
+  const allowance = 100_000;
+  const week = 7 * 86400;
+
+  const payAllowance = async () => {
+    await program.methods
+      .submitProof(0, "ipfs://chores-done")
+      .accounts({
+        goals: goalsPda,
+        subGoal: subGoalPda(goalsPda, 0),
+        taker: child.publicKey,
+      })
+      .signers([child])
+      .rpc();
+    await program.methods
+      .reviewSubgoal(0, true)
+      .accounts({
+        goals: goalsPda,
+        subGoal: subGoalPda(goalsPda, 0),
+        issuer: parent.publicKey,
+        takerAccount: child.publicKey,
+      })
+      .signers([parent])
+      .rpc();
+  };
+
+  const rearm = () =>
+    program.methods
+      .rearmRecurringGoal()
+      .accounts({ goals: goalsPda })
+      .remainingAccounts([
+        { pubkey: subGoalPda(goalsPda, 0), isWritable: true, isSigner: false },
+      ])
+      .rpc();
+
+  before(async () => {
+    for (const wallet of [parent, child]) {
+      const sig = await connection.requestAirdrop(
+        wallet.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction(sig);
+    }
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .setupGoal(
+        child.publicKey,
+        goalId,
+        "Weekly allowance",
+        { loveGame: {} },
+        { parents: {} },
+        { targetAchieve: {} },
+        [
+          {
+            title: "Tidy room every day",
+            deadline: new BN(now + week),
+            incentiveAmount: new BN(allowance),
+            autoReleaseAt: new BN(0),
+            prerequisites: Buffer.from([]),
+          },
+        ],
+        new BN(allowance),
+        new BN(now + week),
+        new BN(0),
+        new BN(now + week),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: parent.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: subGoalPda(goalsPda, 0), isWritable: true, isSigner: false },
+      ])
+      .signers([parent])
+      .rpc();
+  });
+
+  it("re-arms each period from the pre-funded balance until cycles run out", async () => {
+    // three weeks in total: the first is funded at setup, two more up front
+    await program.methods
+      .makeGoalRecurring(new BN(week), 3, new BN(2 * allowance))
+      .accounts({
+        goals: goalsPda,
+        issuer: parent.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([parent])
+      .rpc();
+
+    const first = await program.account.subGoalAccount.fetch(subGoalPda(goalsPda, 0));
+
+    // the period is still open while the allowance is unpaid
+    try {
+      await rearm();
+      expect.fail("period has not ended");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("RecurrenceNotDue");
+    }
+
+    for (const cycle of [1, 2]) {
+      await payAllowance();
+      await rearm();
+
+      const goal = await program.account.totalGoal.fetch(goalsPda);
+      expect(goal.recurrenceCycle).to.eq(cycle);
+      expect(goal.settledMask.toNumber()).to.eq(0);
+      expect(goal.allocatedAmount.toNumber()).to.eq(allowance);
+
+      const subGoal = await program.account.subGoalAccount.fetch(subGoalPda(goalsPda, 0));
+      expect(subGoal.status).to.deep.eq({ pending: {} });
+      expect(subGoal.deadline.toNumber()).to.eq(first.deadline.toNumber() + cycle * week);
+    }
+
+    await payAllowance();
+    try {
+      await rearm();
+      expect.fail("all three weeks have run");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("RecurrenceExhausted");
+    }
+
+    const goal = await program.account.totalGoal.fetch(goalsPda);
+    expect(goal.releasedAmount.toNumber()).to.eq(3 * allowance);
+  });
+});
@@ -0,0 +1,148 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("savings_circle", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const organizer = web3.Keypair.generate();
+  const members = Array.from({ length: 3 }, () => web3.Keypair.generate());
+  const circleId = new BN(1);
+  const [circlePda] = web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("savings-circle"),
+      organizer.publicKey.toBuffer(),
+      Buffer.from(circleId.toArray("le", 8)),
+    ],
+    program.programId
+  );
+  const contribution = 20_000_000;
+  const collateral = 30_000_000;
+  const period = 6;
+  const order = [2, 0, 1];
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  const contribute = (member: web3.Keypair) =>
+    program.methods
+      .contributeSavingsCircle()
+      .accounts({
+        circle: circlePda,
+        member: member.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([member])
+      .rpc();
+
+  const payout = (round: number) =>
+    program.methods
+      .payoutSavingsRound()
+      .accounts({ circle: circlePda, recipient: members[order[round]].publicKey })
+      .rpc();
+
+  before(async () => {
+    for (const wallet of [organizer, ...members]) {
+      const sig = await connection.requestAirdrop(
+        wallet.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction(sig);
+    }
+  });
+
+  it("pays one member per round and covers missed contributions from collateral", async () => {
+    const start = Math.floor(Date.now() / 1000) + 8;
+    await program.methods
+      .createSavingsCircle(circleId, {
+        relations: { partner: {} },
+        members: members.map((m) => m.publicKey),
+        contributionAmount: new BN(contribution),
+        collateralAmount: new BN(collateral),
+        period: new BN(period),
+        startTime: new BN(start),
+        payoutOrder: Buffer.from(order),
+      })
+      .accounts({
+        circle: circlePda,
+        organizer: organizer.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([organizer])
+      .rpc();
+
+    for (const member of members) {
+      await program.methods
+        .joinSavingsCircle()
+        .accounts({
+          circle: circlePda,
+          member: member.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([member])
+        .rpc();
+    }
+    const joined = await program.account.savingsCircle.fetch(circlePda);
+    expect(joined.drawSlot.toNumber()).to.be.greaterThan(0);
+
+    // any member can start the circle, not only the organizer
+    await program.methods
+      .startSavingsCircle()
+      .accounts({
+        circle: circlePda,
+        member: members[1].publicKey,
+        recentSlothashes: web3.SYSVAR_SLOT_HASHES_PUBKEY,
+      })
+      .signers([members[1]])
+      .rpc();
+
+    // round 0: everyone pays, so the pot can go out straight away
+    for (const member of members) await contribute(member);
+    let before = await connection.getBalance(members[2].publicKey);
+    await payout(0);
+    let after = await connection.getBalance(members[2].publicKey);
+    expect(after - before).to.eq(3 * contribution);
+
+    // round 1: the last member misses and is charged from collateral
+    await contribute(members[0]);
+    await contribute(members[2]);
+    try {
+      await payout(1);
+      expect.fail("round is still open");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("CircleNotReady");
+    }
+    await sleep((start + 2 * period - Math.floor(Date.now() / 1000) + 1) * 1000);
+
+    // round 2 is open before round 1 is paid out; contributions still count for it
+    for (const member of members) await contribute(member);
+    before = await connection.getBalance(members[0].publicKey);
+    await payout(1);
+    after = await connection.getBalance(members[0].publicKey);
+    expect(after - before).to.eq(3 * contribution);
+
+    let circle = await program.account.savingsCircle.fetch(circlePda);
+    expect(circle.missed).to.deep.eq([0, 1, 0]);
+    expect(circle.collateral[1].toNumber()).to.eq(collateral - contribution);
+
+    // round 2 closes the circle without anyone being charged
+    await payout(2);
+    circle = await program.account.savingsCircle.fetch(circlePda);
+    expect(circle.missed).to.deep.eq([0, 1, 0]);
+    expect(circle.currentRound).to.eq(3);
+
+    before = await connection.getBalance(members[1].publicKey);
+    await program.methods
+      .closeSavingsCircle()
+      .accounts({ circle: circlePda, organizer: organizer.publicKey })
+      .remainingAccounts(
+        members.map((m) => ({ pubkey: m.publicKey, isWritable: true, isSigner: false }))
+      )
+      .rpc();
+    after = await connection.getBalance(members[1].publicKey);
+    expect(after - before).to.eq(collateral - contribution);
+    expect(await connection.getAccountInfo(circlePda)).to.be.null;
+  });
+});
@@ -0,0 +1,136 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("streaming_goal", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+  const connection = program.provider.connection;
+
+  const employer = web3.Keypair.generate();
+  const employee = web3.Keypair.generate();
+  const goalId = new BN(41);
+  const [goalsPda] = web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("gluex-goals"),
+      employer.publicKey.toBuffer(),
+      employee.publicKey.toBuffer(),
+      Buffer.from(goalId.toArray("le", 8)),
+    ],
+    program.programId
+  );
+  const [streamPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("gluex-subgoal"), goalsPda.toBuffer(), Buffer.from([0])],
+    program.programId
+  );
+  const amount = 10_000_000;
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  const withdraw = () =>
+    program.methods
+      .withdrawStreamed()
+      .accounts({ goals: goalsPda, subGoal: streamPda, taker: employee.publicKey })
+      .signers([employee])
+      .rpc();
+
+  before(async () => {
+    for (const wallet of [employer, employee]) {
+      const sig = await connection.requestAirdrop(
+        wallet.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+      await connection.confirmTransaction(sig);
+    }
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .setupGoal(
+        employee.publicKey,
+        goalId,
+        "Quarterly onboarding",
+        { loveGame: {} },
+        { bosstaff: {} },
+        { streaming: {} },
+        [],
+        new BN(amount),
+        new BN(now + 60),
+        new BN(0),
+        new BN(now + 60),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: employer.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: streamPda, isWritable: true, isSigner: false }])
+      .signers([employer])
+      .rpc();
+  });
+
+  it("lets the taker withdraw what has vested so far", async () => {
+    await sleep(3000);
+    const before = await connection.getBalance(employee.publicKey);
+    await withdraw();
+    const after = await connection.getBalance(employee.publicKey);
+
+    const goal = await program.account.totalGoal.fetch(goalsPda);
+    const streamed = goal.streamWithdrawn.toNumber();
+    expect(streamed).to.be.greaterThan(0).and.lessThan(amount);
+    expect(after - before).to.eq(streamed - 5000); // net of the tx fee
+
+    // proofs and reviews do not apply to streamed incentives
+    try {
+      await program.methods
+        .submitProof(0, "ipfs://timesheet")
+        .accounts({ goals: goalsPda, subGoal: streamPda, taker: employee.publicKey })
+        .signers([employee])
+        .rpc();
+      expect.fail("streaming goals are not reviewed");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("EventTypeNotSupport");
+    }
+  });
+
+  it("returns the unvested remainder when the issuer halts", async () => {
+    const before = await connection.getBalance(employer.publicKey);
+    await program.methods
+      .haltStream()
+      .accounts({ goals: goalsPda, subGoal: streamPda, issuer: employer.publicKey })
+      .signers([employer])
+      .rpc();
+    const after = await connection.getBalance(employer.publicKey);
+
+    const goal = await program.account.totalGoal.fetch(goalsPda);
+    const stream = await program.account.subGoalAccount.fetch(streamPda);
+    expect(goal.failed).to.eq(1);
+    expect(goal.streamHaltedAt.toNumber()).to.be.greaterThan(0);
+    expect(after - before).to.eq(amount - stream.incentiveAmount.toNumber() - 5000);
+
+    // the vested part stays withdrawable and settles the stream
+    await sleep(2000);
+    await withdraw();
+    const settled = await program.account.totalGoal.fetch(goalsPda);
+    const paid = await program.account.subGoalAccount.fetch(streamPda);
+    expect(settled.streamWithdrawn.toNumber()).to.eq(stream.incentiveAmount.toNumber());
+    expect(paid.status).to.deep.eq({ paid: {} });
+
+    try {
+      await withdraw();
+      expect.fail("stream is fully withdrawn");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("SubGoalAlreadyFinalized");
+    }
+  });
+});
@@ -0,0 +1,171 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("subgoal_amendment", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+
+  const taker = web3.Keypair.generate();
+  let goalsPda: web3.PublicKey;
+  let amendmentPda: web3.PublicKey;
+
+  const subGoalPda = (goal: web3.PublicKey, index: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gluex-subgoal"), goal.toBuffer(), Buffer.from([index])],
+      program.programId
+    )[0];
+
+  const meta = (pubkey: web3.PublicKey) => ({
+    pubkey,
+    isWritable: true,
+    isSigner: false,
+  });
+
+  before(async () => {
+    const sig = await program.provider.connection.requestAirdrop(
+      taker.publicKey,
+      web3.LAMPORTS_PER_SOL
+    );
+    await program.provider.connection.confirmTransaction(sig);
+
+    const now = Math.floor(Date.now() / 1000);
+    const goalIdBn = new BN(now);
+    [goalsPda] = web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gluex-goals"),
+        program.provider.publicKey.toBuffer(),
+        taker.publicKey.toBuffer(),
+        Buffer.from(goalIdBn.toArray("le", 8)),
+      ],
+      program.programId
+    );
+    [amendmentPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("subgoal-amendment"), goalsPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .setupGoal(
+        taker.publicKey,
+        goalIdBn,
+        "Amendable goal",
+        { loveGame: {} },
+        { bosstaff: {} },
+        { targetAchieve: {} },
+        [
+          {
+            title: "Draft report",
+            deadline: new BN(now + 3600),
+            incentiveAmount: new BN(600_000),
+            autoReleaseAt: new BN(0),
+            prerequisites: [],
+          },
+          {
+            title: "Present report",
+            deadline: new BN(now + 7200),
+            incentiveAmount: new BN(400_000),
+            autoReleaseAt: new BN(0),
+            prerequisites: [],
+          },
+        ],
+        new BN(1_000_000),
+        new BN(now + 86400),
+        new BN(0),
+        new BN(now + 86400),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: program.provider.publicKey,
+        feePool: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([meta(subGoalPda(goalsPda, 0)), meta(subGoalPda(goalsPda, 1))])
+      .rpc();
+  });
+
+  it("applies edits, deactivations and additions once the taker accepts", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .proposeSubgoalAmendment(
+        [
+          {
+            index: 0,
+            title: null,
+            deadline: new BN(now + 10800),
+            incentiveAmount: new BN(500_000),
+            autoReleaseAt: null,
+            deactivate: false,
+          },
+          {
+            index: 1,
+            title: null,
+            deadline: null,
+            incentiveAmount: null,
+            autoReleaseAt: null,
+            deactivate: true,
+          },
+        ],
+        [
+          {
+            title: "Publish summary",
+            deadline: new BN(now + 14400),
+            incentiveAmount: new BN(500_000),
+            autoReleaseAt: new BN(0),
+            prerequisites: [0],
+          },
+        ]
+      )
+      .accounts({
+        goals: goalsPda,
+        amendment: amendmentPda,
+        proposer: program.provider.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .acceptSubgoalAmendment()
+      .accounts({
+        goals: goalsPda,
+        amendment: amendmentPda,
+        proposer: program.provider.publicKey,
+        counterparty: taker.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([
+        meta(subGoalPda(goalsPda, 0)),
+        meta(subGoalPda(goalsPda, 1)),
+        meta(subGoalPda(goalsPda, 2)),
+      ])
+      .signers([taker])
+      .rpc();
+
+    const goal = await program.account.totalGoal.fetch(goalsPda);
+    expect(goal.activeSubGoals).to.eq(3);
+    expect(goal.allocatedAmount.toNumber()).to.eq(1_000_000);
+    expect(goal.settledMask.toNumber()).to.eq(1 << 1);
+
+    const first = await program.account.subGoalAccount.fetch(subGoalPda(goalsPda, 0));
+    expect(first.incentiveAmount.toNumber()).to.eq(500_000);
+    expect(first.deadline.toNumber()).to.eq(now + 10800);
+    const second = await program.account.subGoalAccount.fetch(subGoalPda(goalsPda, 1));
+    expect(second.isActive).to.be.false;
+    const added = await program.account.subGoalAccount.fetch(subGoalPda(goalsPda, 2));
+    expect(added.prerequisites.toNumber()).to.eq(1);
+
+    const closed = await program.provider.connection.getAccountInfo(amendmentPda);
+    expect(closed).to.be.null;
+  });
+});
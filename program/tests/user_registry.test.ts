@@ -0,0 +1,196 @@
+import * as anchor from "@coral-xyz/anchor";
+import BN from "bn.js";
+import { expect } from "chai";
+import * as web3 from "@solana/web3.js";
+import type { Gluex } from "../target/types/gluex";
+
+describe("user_registry", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.Gluex as anchor.Program<Gluex>;
+
+  const subGoalPda = (goal: web3.PublicKey, index: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gluex-subgoal"), goal.toBuffer(), Buffer.from([index])],
+      program.programId
+    )[0];
+
+  const issuer = web3.Keypair.generate();
+  const taker = web3.Keypair.generate();
+
+  const indexPda = (owner: web3.PublicKey) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("user-goal-index"), owner.toBuffer()],
+      program.programId
+    )[0];
+
+  const pagePda = (owner: web3.PublicKey, kind: number, page: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("user-index-page"),
+        owner.toBuffer(),
+        Buffer.from([kind]),
+        Buffer.from(new BN(page).toArray("le", 4)),
+      ],
+      program.programId
+    )[0];
+
+  const goalPda = (goalTaker: web3.PublicKey, goalId: BN) =>
+    web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gluex-goals"),
+        issuer.publicKey.toBuffer(),
+        goalTaker.toBuffer(),
+        Buffer.from(goalId.toArray("le", 8)),
+      ],
+      program.programId
+    )[0];
+
+  const setupGoal = (goalTaker: web3.PublicKey, goalId: BN) => {
+    const goalsPda = goalPda(goalTaker, goalId);
+    const now = Math.floor(Date.now() / 1000);
+    return program.methods
+      .setupGoal(
+        goalTaker,
+        goalId,
+        "Indexed goal",
+        { loveGame: {} },
+        { partner: {} },
+        { targetAchieve: {} },
+        [
+          {
+            title: "Run a half marathon",
+            deadline: new BN(now + 3600),
+            incentiveAmount: new BN(1_000_000),
+            autoReleaseAt: new BN(0),
+            prerequisites: [],
+          },
+        ],
+        new BN(1_000_000),
+        new BN(now + 3600),
+        new BN(0),
+        new BN(now + 7200),
+        {
+          startTime: new BN(0),
+          surpriseTime: new BN(0),
+          checkpointInterval: new BN(0),
+          surpriseWindow: null,
+          sealedMessage: null,
+          sequential: false,
+          latePenalty: null,
+        }
+      )
+      .accounts({
+        goals: goalsPda,
+        payer: issuer.publicKey,
+        feePool: null,
+        issuerIndex: indexPda(issuer.publicKey),
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: subGoalPda(goalsPda, 0), isWritable: true, isSigner: false },
+      ])
+      .signers([issuer])
+      .rpc();
+  };
+
+  before(async () => {
+    const sig = await program.provider.connection.requestAirdrop(
+      issuer.publicKey,
+      2 * web3.LAMPORTS_PER_SOL
+    );
+    await program.provider.connection.confirmTransaction(sig);
+  });
+
+  it("allocates goal ids and lists goals per user", async () => {
+    for (const owner of [issuer.publicKey, taker.publicKey]) {
+      await program.methods
+        .initUserIndex(owner)
+        .accounts({
+          userIndex: indexPda(owner),
+          payer: issuer.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([issuer])
+        .rpc();
+    }
+
+    const goalsPda = goalPda(taker.publicKey, new BN(0));
+    await setupGoal(taker.publicKey, new BN(0));
+
+    const issuerIndex = await program.account.userGoalIndex.fetch(
+      indexPda(issuer.publicKey)
+    );
+    expect(issuerIndex.nextGoalId.toNumber()).to.eq(1);
+
+    const kinds: Array<[web3.PublicKey, number, any]> = [
+      [issuer.publicKey, 1, { issuedGoal: {} }],
+      [taker.publicKey, 2, { takenGoal: {} }],
+    ];
+    for (const [owner, kindByte, kind] of kinds) {
+      await program.methods
+        .indexGoal(kind)
+        .accounts({
+          goals: goalsPda,
+          userIndex: indexPda(owner),
+          page: pagePda(owner, kindByte, 0),
+          payer: issuer.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([issuer])
+        .rpc();
+
+      const page = await program.account.userIndexPage.fetch(
+        pagePda(owner, kindByte, 0)
+      );
+      expect(page.entries.map((e: web3.PublicKey) => e.toBase58())).to.deep.eq([
+        goalsPda.toBase58(),
+      ]);
+    }
+
+    const goal = await program.account.totalGoal.fetch(goalsPda);
+    expect(goal.issuerIndexed).to.equal(1);
+    expect(goal.takerIndexed).to.equal(1);
+  });
+
+  it("rejects manual ids inside the allocated range", async () => {
+    const otherTaker = web3.Keypair.generate().publicKey;
+    try {
+      await setupGoal(otherTaker, new BN(0));
+      expect.fail("id 0 was already allocated");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eq("GoalIdMismatch");
+    }
+  });
+
+  it("drops the goal from the old issuer's index on handover", async () => {
+    const goalsPda = goalPda(taker.publicKey, new BN(0));
+    const newIssuer = web3.Keypair.generate();
+    await program.methods
+      .proposeIssuerTransfer(newIssuer.publicKey)
+      .accounts({ goals: goalsPda, authority: issuer.publicKey })
+      .signers([issuer])
+      .rpc();
+    await program.methods
+      .acceptIssuerTransfer()
+      .accounts({
+        goals: goalsPda,
+        newParty: newIssuer.publicKey,
+        oldIndex: indexPda(issuer.publicKey),
+        entryPage: pagePda(issuer.publicKey, 1, 0),
+        lastPage: null,
+      })
+      .signers([newIssuer])
+      .rpc();
+
+    const page = await program.account.userIndexPage.fetch(
+      pagePda(issuer.publicKey, 1, 0)
+    );
+    expect(page.entries).to.be.empty;
+    const index = await program.account.userGoalIndex.fetch(
+      indexPda(issuer.publicKey)
+    );
+    expect(index.issuedGoals).to.eq(0);
+    const goal = await program.account.totalGoal.fetch(goalsPda);
+    expect(goal.issuerIndexed).to.equal(0);
+  });
+});